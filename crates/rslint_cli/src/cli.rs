@@ -4,7 +4,9 @@ use crate::lint_err;
 use ansi_term::Color::{Green, White, RGB};
 use colored::Colorize;
 use regex::{Captures, Regex};
-use rslint_core::{get_rule_docs, CstRuleStore};
+use rslint_core::{
+    get_project_rule_by_name, get_rule_by_name, get_rule_docs, CstRuleStore, ProjectRuleStore,
+};
 use rslint_lexer::{ansi_term, color};
 use std::collections::HashSet;
 
@@ -85,7 +87,17 @@ impl ExplanationRunner {
 
     pub fn append_link_to_docs(&mut self) {
         for (docs, name) in self.rules.iter_mut().zip(self.rule_names.iter()) {
-            let group = rslint_core::get_rule_by_name(&name).unwrap().group();
+            // plugin rules have no docs on the website
+            if name.contains('/') {
+                if !docs.ends_with('\n') {
+                    docs.push('\n');
+                }
+                continue;
+            }
+            let group = get_rule_by_name(name)
+                .map(|rule| rule.group())
+                .or_else(|| get_project_rule_by_name(name).map(|rule| rule.group()))
+                .unwrap();
             let link = format!("https://rslint.org/rules/{}/{}.html", group, name);
             docs.push_str(&format!("{}: {}\n", Green.paint("Docs").to_string(), link));
         }
@@ -109,27 +121,40 @@ impl ExplanationRunner {
 }
 
 pub fn show_all_rules() {
-    let rules = CstRuleStore::new().builtins().rules;
+    // (group, name, docs) of every rule and project rule
+    let rules = CstRuleStore::new()
+        .builtins()
+        .rules
+        .iter()
+        .map(|r| (r.group(), r.name(), r.docs()))
+        .chain(
+            ProjectRuleStore::new()
+                .builtins()
+                .rules
+                .iter()
+                .map(|r| (r.group(), r.name(), r.docs())),
+        )
+        .collect::<Vec<_>>();
     let mut groups = HashSet::new();
-    rules.iter().for_each(|r| {
-        groups.insert(r.group());
+    rules.iter().for_each(|(group, ..)| {
+        groups.insert(*group);
     });
 
     for group in groups {
-        let group_rules = rules.iter().filter(|rule| rule.group() == group);
+        let group_rules = rules.iter().filter(|(rule_group, ..)| *rule_group == group);
         println!("{}:", group.bright_green());
         let max_rule_len = group_rules
             .clone()
-            .map(|r| r.name().len())
+            .map(|(_, name, _)| name.len())
             .max()
             .unwrap_or(0);
 
-        for rule in group_rules {
+        for (_, name, docs) in group_rules {
             println!(
                 " {}{} - {}",
-                rule.name().white(),
-                " ".repeat(max_rule_len - rule.name().len()),
-                rule.docs().lines().next().unwrap_or_default()
+                name.white(),
+                " ".repeat(max_rule_len - name.len()),
+                docs.lines().next().unwrap_or_default()
             );
         }
         println!();
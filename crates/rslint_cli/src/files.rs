@@ -3,10 +3,10 @@
 use crate::lint_warn;
 use rslint_core::File;
 use rslint_errors::file::{FileId, Files};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::read_to_string;
 use std::ops::Range;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 /// A list of ignored-by-default directory/file names
@@ -63,12 +63,31 @@ impl FileWalker {
     }
 
     pub fn load_files(&mut self, paths: impl Iterator<Item = PathBuf>) {
-        let jsfiles: HashMap<usize, File> = paths
+        let jsfiles: HashMap<usize, File> = Self::walk(paths)
+            .filter_map(|path| Self::read_file(&path))
+            .map(|file| (file.id, file))
+            .collect();
+        self.files.extend(jsfiles);
+    }
+
+    /// Walk through paths and the directories in them, yielding the path of every file which should be linted.
+    ///
+    /// Directories are walked lazily so files can be linted while the rest of the directories are being walked.
+    /// Paths are yielded sorted and only once, even if they are in more than one of the paths walked.
+    pub fn walk(paths: impl Iterator<Item = PathBuf>) -> impl Iterator<Item = PathBuf> {
+        let mut paths = paths.collect::<Vec<_>>();
+        paths.sort();
+        paths.dedup();
+        let mut seen = HashSet::new();
+
+        paths
+            .into_iter()
             .filter(|p| {
                 !IGNORED.contains(&p.file_name().unwrap_or_default().to_string_lossy().as_ref())
             })
             .flat_map(|path| {
                 WalkDir::new(path)
+                    .sort_by(|a, b| a.file_name().cmp(b.file_name()))
                     .into_iter()
                     .filter_entry(|p| !IGNORED.contains(&p.file_name().to_string_lossy().as_ref()))
                     .filter_map(Result::ok)
@@ -82,21 +101,19 @@ impl FileWalker {
                         .as_ref(),
                 )
             })
-            .filter_map(|entry| {
-                let path = entry.path();
-                let content = match read_to_string(path) {
-                    Ok(v) => v,
-                    Err(err) => {
-                        crate::lint_err!("failed to read file {}: {}", path.display(), err);
-                        return None;
-                    }
-                };
-                Some((content, path.to_owned()))
-            })
-            .map(|(src, path)| File::new_concrete(src, path))
-            .map(|file| (file.id, file))
-            .collect();
-        self.files.extend(jsfiles);
+            .map(|entry| entry.into_path())
+            .filter(move |path| seen.insert(path.clone()))
+    }
+
+    /// Read a file, emitting an error if it could not be read.
+    pub fn read_file(path: &Path) -> Option<File> {
+        match read_to_string(path) {
+            Ok(src) => Some(File::new_concrete(src, path.to_owned())),
+            Err(err) => {
+                crate::lint_err!("failed to read file {}: {}", path.display(), err);
+                None
+            }
+        }
     }
 
     pub fn line_start(&self, id: usize, line_index: usize) -> Option<usize> {
@@ -1,12 +1,15 @@
 mod cli;
 mod files;
 mod infer;
+mod migrate;
 mod panic_hook;
+mod pipeline;
 
 pub use self::{
     cli::{show_all_rules, ExplanationRunner},
     files::*,
     infer::infer,
+    migrate::migrate,
     panic_hook::*,
 };
 pub use rslint_config as config;
@@ -16,19 +19,21 @@ pub use rslint_errors::{
 };
 
 use colored::*;
-use rslint_core::{autofix::recursively_apply_fixes, File};
-use rslint_core::{lint_file, util::find_best_match_for_name, LintResult, RuleLevel};
+use pipeline::{run_pipeline, PipelineOptions};
+use rslint_core::File;
+use rslint_core::{util::find_best_match_for_name, RuleLevel};
 use rslint_lexer::Lexer;
+use std::path::PathBuf;
 #[allow(unused_imports)]
 use std::process;
-use std::{fs::write, path::PathBuf};
 use yastl::Pool;
 
-#[allow(unused_must_use, unused_variables)]
+#[allow(unused_must_use, unused_variables, clippy::too_many_arguments)]
 pub fn run(
     globs: Vec<String>,
     verbose: bool,
     fix: bool,
+    unsafe_fixes: bool,
     dirty: bool,
     formatter: Option<String>,
     no_global_config: bool,
@@ -38,6 +43,7 @@ pub fn run(
         globs,
         verbose,
         fix,
+        unsafe_fixes,
         dirty,
         formatter,
         no_global_config,
@@ -48,119 +54,70 @@ pub fn run(
 }
 
 /// The inner function for run to call destructors before we call [`process::exit`]
+#[allow(clippy::too_many_arguments)]
 fn run_inner(
     globs: Vec<String>,
     verbose: bool,
     fix: bool,
+    unsafe_fixes: bool,
     dirty: bool,
     formatter: Option<String>,
     no_global_config: bool,
     pool: Pool,
 ) -> i32 {
-    let mut config = None;
-    let mut walker = FileWalker::empty();
-
-    pool.scoped(|scope| {
-        scope.execute(|| {
-            config = Some(config::Config::new(no_global_config, |file, d| {
-                emit_diagnostic(&d, &file)
-            }));
-        });
-
-        scope.execute(|| {
-            walker.load_files(collect_globs(globs).into_iter());
-        });
-    });
+    let mut config = config::Config::new(no_global_config, |file, d| emit_diagnostic(&d, &file));
+    let store = config.rules_store();
+    let project_store = config.project_rules_store();
+    for warning in config.warnings() {
+        emit_diagnostic(&warning, &FileWalker::empty());
+    }
 
-    let mut config = config.expect("config failed to initialize");
-    emit_diagnostics("short", &config.warnings(), &walker);
+    // the config can't be shared between threads, so the level of every rule is looked up ahead of time
+    let levels = store
+        .rules
+        .iter()
+        .map(|rule| rule.name())
+        .chain(project_store.rules.iter().map(|rule| rule.name()))
+        .map(|name| (name, config.rule_level_by_name(name)))
+        .collect();
 
     let mut formatter = formatter.unwrap_or_else(|| config.formatter());
-    let store = config.rules_store();
     verify_formatter(&mut formatter);
+    let options = PipelineOptions {
+        verbose,
+        fix,
+        unsafe_fixes,
+        dirty,
+        formatter,
+    };
 
-    if walker.files.is_empty() {
+    let summary = run_pipeline(
+        collect_globs(globs),
+        &store,
+        &project_store,
+        &levels,
+        config.parser(),
+        &options,
+        &pool,
+    );
+    if summary.files == 0 {
         lint_err!("No matching files found");
         return 2;
     }
 
-    let (tx, rx) = std::sync::mpsc::channel();
-    pool.scoped(|scope| {
-        let store = &store;
-
-        for file in walker.files.values() {
-            let tx = tx.clone();
-            scope.recurse(move |_scope| {
-                tx.send(lint_file(file, store, verbose)).unwrap();
-            });
-        }
-    });
-    drop(tx);
-    let mut results = rx.into_iter().collect::<Vec<_>>();
-
-    let fix_count = if fix {
-        apply_fixes(&mut results, &mut walker, dirty)
-    } else {
-        0
-    };
-    print_results(&mut results, &walker, &config, fix_count, &formatter);
-
-    // print_results remaps the result to the appropriate severity
-    // so these diagnostic severities should be accurate
-    if results
-        .iter()
-        .flat_map(|res| res.diagnostics())
-        .any(|d| matches!(d.severity, Severity::Bug | Severity::Error))
-    {
-        1
-    } else {
-        0
+    output_overall(
+        summary.failures,
+        summary.warnings,
+        summary.successes,
+        summary.fix_count,
+    );
+    if summary.failures > 0 {
+        println!("\nhelp: for more information about the errors try the explain command: `rslint explain <rules>`");
     }
-}
 
-pub fn apply_fixes(results: &mut Vec<LintResult>, walker: &mut FileWalker, dirty: bool) -> usize {
-    let mut fix_count = 0;
-    // TODO: should we aquire a file lock if we know we need to run autofix?
-    for res in results {
-        let file = walker.files.get_mut(&res.file_id).unwrap();
-        // skip virtual files
-        if file.path.is_none() {
-            continue;
-        }
-        if res
-            .parser_diagnostics
-            .iter()
-            .any(|x| x.severity == Severity::Error)
-            && !dirty
-        {
-            lint_note!(
-                "skipping autofix for `{}` because it contains syntax errors",
-                file.path.as_ref().unwrap().to_string_lossy()
-            );
-            continue;
-        }
-        let original_problem_num = res
-            .rule_results
-            .iter()
-            .filter(|(_, x)| x.outcome() == Outcome::Warning || x.outcome() == Outcome::Failure)
-            .map(|(_, res)| res.diagnostics.len())
-            .sum::<usize>();
-        let fixed = recursively_apply_fixes(res, file);
-        let new_problem_num = res
-            .rule_results
-            .iter()
-            .filter(|(_, x)| x.outcome() == Outcome::Warning || x.outcome() == Outcome::Failure)
-            .map(|(_, res)| res.diagnostics.len())
-            .sum::<usize>();
-        let path = file.path.as_ref().unwrap();
-        if let Err(err) = write(path, fixed.clone()) {
-            lint_err!("failed to write to `{:#?}`: {}", path, err.to_string());
-        } else {
-            file.update_src(fixed);
-            fix_count += original_problem_num.saturating_sub(new_problem_num);
-        }
-    }
-    fix_count
+    // the reporter remaps the diagnostics to the appropriate severity
+    // so these diagnostic severities should be accurate
+    summary.errors as i32
 }
 
 pub fn dump_ast(globs: Vec<String>) {
@@ -275,53 +232,6 @@ fn for_each_file(globs: Vec<String>, action: impl Fn(&FileWalker, &File)) {
     walker.files.values().for_each(|file| action(&walker, file))
 }
 
-pub(crate) fn print_results(
-    results: &mut Vec<LintResult>,
-    walker: &FileWalker,
-    config: &config::Config,
-    fix_count: usize,
-    formatter: &str,
-) {
-    // Map each diagnostic to the correct level according to configured rule level
-    for result in results.iter_mut() {
-        for (rule_name, diagnostics) in result
-            .rule_results
-            .iter_mut()
-            .map(|x| (x.0, &mut x.1.diagnostics))
-        {
-            remap_diagnostics_to_level(diagnostics, config.rule_level_by_name(rule_name));
-        }
-    }
-
-    let failures = results
-        .iter()
-        .filter(|res| res.outcome() == Outcome::Failure)
-        .count();
-    let warnings = results
-        .iter()
-        .filter(|res| res.outcome() == Outcome::Warning)
-        .count();
-    let successes = results
-        .iter()
-        .filter(|res| res.outcome() == Outcome::Success)
-        .count();
-
-    let overall = Outcome::merge(results.iter().map(|res| res.outcome()));
-
-    for result in results.iter_mut() {
-        emit_diagnostics(
-            formatter,
-            &result.diagnostics().cloned().collect::<Vec<_>>(),
-            walker,
-        );
-    }
-
-    output_overall(failures, warnings, successes, fix_count);
-    if overall == Outcome::Failure {
-        println!("\nhelp: for more information about the errors try the explain command: `rslint explain <rules>`");
-    }
-}
-
 pub fn verify_formatter(formatter: &mut String) {
     if !matches!(formatter.as_str(), "short" | "long") {
         if let Some(suggestion) =
@@ -383,18 +293,60 @@ fn output_overall(failures: usize, warnings: usize, successes: usize, fix_count:
     );
 }
 
-/// Remap each error diagnostic to a warning diagnostic based on the rule's level.
-/// this leaves warnings untouched because rules should be able to emit errors and warnings for context without
-/// the warnings being remapped to errors.
-pub fn remap_diagnostics_to_level(diagnostics: &mut Vec<Diagnostic>, level: RuleLevel) {
-    for diagnostic in diagnostics.iter_mut() {
-        match diagnostic.severity {
-            Severity::Error if level == RuleLevel::Warning => {
-                diagnostic.severity = Severity::Warning
-            }
-            _ => {}
+/// Load the plugins of the config so their rules can be listed and explained.
+pub fn load_plugins(no_global_config: bool) {
+    config::Config::load_plugins(no_global_config, |file, d| emit_diagnostic(&d, &file));
+}
+
+/// Check the config and emit every problem found in it.
+#[allow(unused_variables)]
+pub fn check_config(no_global_config: bool) {
+    let exit_code = check_config_inner(no_global_config);
+    #[cfg(not(debug_assertions))]
+    process::exit(exit_code);
+}
+
+fn check_config_inner(no_global_config: bool) -> i32 {
+    let (file, diagnostics) = match config::Config::check(no_global_config) {
+        Some(res) => res,
+        None => {
+            lint_err!("no config found");
+            return 2;
         }
+    };
+
+    for d in &diagnostics {
+        emit_diagnostic(d, &file);
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| matches!(d.severity, Severity::Bug | Severity::Error))
+        .count();
+    let warnings = diagnostics.len() - errors;
+    if diagnostics.is_empty() {
+        println!(
+            "{}: `{}` is valid",
+            "Success".bright_green(),
+            file.name(1).unwrap_or_default()
+        );
+    } else {
+        println!(
+            "\n{}: {} error{} and {} warning{} in `{}`",
+            "Checked".bright_green(),
+            errors,
+            if errors == 1 { "" } else { "s" },
+            warnings,
+            if warnings == 1 { "" } else { "s" },
+            file.name(1).unwrap_or_default()
+        );
     }
+    (errors > 0) as i32
+}
+
+/// Remap each error and warning diagnostic to the severity of the rule's level, see [`RuleLevel::remap_diagnostics`].
+pub fn remap_diagnostics_to_level(diagnostics: &mut Vec<Diagnostic>, level: RuleLevel) {
+    level.remap_diagnostics(diagnostics)
 }
 
 pub fn emit_diagnostic(diagnostic: &Diagnostic, walker: &dyn file::Files) {
@@ -1,4 +1,5 @@
 use rslint_cli::ExplanationRunner;
+use std::path::PathBuf;
 use structopt::{clap::arg_enum, StructOpt};
 use yastl::Pool;
 
@@ -28,6 +29,9 @@ pub(crate) struct Options {
     /// Automatically attempt to fix any issues which can be fixed
     #[structopt(short, long)]
     fix: bool,
+    /// Also apply fixes which may change the behavior of the code, implies `--fix`
+    #[structopt(long)]
+    fix_unsafe: bool,
     /// Attempt to run autofixes even if the code contains syntax errors (may produce weird fixes or more errors)
     #[structopt(short = "D", long)]
     dirty: bool,
@@ -63,6 +67,22 @@ pub(crate) enum SubCommand {
     Rules,
     /// Try to infer the options of some rules from various files and print the results
     Infer { files: Vec<String> },
+    /// Migrate an `.eslintrc.json` or `.eslintrc` to an `rslintrc.toml` in the current directory
+    Migrate {
+        /// The ESLint config to migrate, `.eslintrc.json` or `.eslintrc` by default
+        config: Option<PathBuf>,
+        /// Overwrite an existing `rslintrc.toml`
+        #[structopt(short, long)]
+        force: bool,
+    },
+    /// Inspect the config
+    Config(ConfigCommand),
+}
+
+#[derive(Debug, StructOpt, PartialEq, Eq)]
+pub(crate) enum ConfigCommand {
+    /// Check the config for errors without linting any files
+    Check,
 }
 
 fn main() {
@@ -84,13 +104,24 @@ fn execute(opt: Options, pool: Pool) {
         (Some(DevFlag::Tokenize), _) => rslint_cli::tokenize(opt.files),
         (Some(DevFlag::DumpAst), _) => rslint_cli::dump_ast(opt.files),
 
-        (_, Some(SubCommand::Explain { rules })) => ExplanationRunner::new(rules).print(),
-        (_, Some(SubCommand::Rules)) => rslint_cli::show_all_rules(),
+        (_, Some(SubCommand::Explain { rules })) => {
+            rslint_cli::load_plugins(opt.no_global_config);
+            ExplanationRunner::new(rules).print()
+        }
+        (_, Some(SubCommand::Rules)) => {
+            rslint_cli::load_plugins(opt.no_global_config);
+            rslint_cli::show_all_rules()
+        }
         (_, Some(SubCommand::Infer { files })) => rslint_cli::infer(files),
+        (_, Some(SubCommand::Migrate { config, force })) => rslint_cli::migrate(config, force),
+        (_, Some(SubCommand::Config(ConfigCommand::Check))) => {
+            rslint_cli::check_config(opt.no_global_config)
+        }
         (_, None) => rslint_cli::run(
             opt.files,
             opt.verbose,
-            opt.fix,
+            opt.fix || opt.fix_unsafe,
+            opt.fix_unsafe,
             opt.dirty,
             opt.formatter,
             opt.no_global_config,
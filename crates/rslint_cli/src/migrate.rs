@@ -0,0 +1,474 @@
+//! Migration of an ESLint config to an rslintrc.
+
+use crate::*;
+use colored::Colorize;
+use heck::CamelCase;
+use rslint_core::{get_rule_by_name, CstRule};
+use serde_json::{Map, Value};
+use std::fs::{read_to_string, write};
+use std::path::{Path, PathBuf};
+
+/// The names of the ESLint configs to look for, in order, if no config is given.
+const ESLINT_CONFIG_NAMES: [&str; 2] = [".eslintrc.json", ".eslintrc"];
+/// The name of the config written by the migration.
+const OUTPUT_NAME: &str = "rslintrc.toml";
+
+/// The rules of an ESLint config mapped to their rslint equivalent.
+#[derive(Debug, Default)]
+struct Migration {
+    errors: Map<String, Value>,
+    warnings: Map<String, Value>,
+    off: Vec<String>,
+    groups: Vec<String>,
+    global_return: bool,
+    /// ESLint rules without an rslint equivalent.
+    unknown_rules: Vec<String>,
+    /// Rules which were migrated without some of their options, along with the reason.
+    dropped_options: Vec<(String, String)>,
+    /// Top level ESLint config keys which are not migrated.
+    ignored_keys: Vec<String>,
+}
+
+/// Migrate an `.eslintrc.json` or `.eslintrc` (in JSON form) to an `rslintrc.toml` in the current directory.
+pub fn migrate(config: Option<PathBuf>, force: bool) {
+    let path = match config.or_else(find_eslint_config) {
+        Some(path) => path,
+        None => {
+            return lint_err!("no `.eslintrc.json` or `.eslintrc` found in the current directory")
+        }
+    };
+    let out = Path::new(OUTPUT_NAME);
+    if out.exists() && !force {
+        return lint_err!(
+            "`{}` already exists, use `--force` to overwrite it",
+            OUTPUT_NAME
+        );
+    }
+
+    let source = match read_to_string(&path) {
+        Ok(source) => source,
+        Err(err) => return lint_err!("failed to read `{}`: {}", path.display(), err),
+    };
+    let value = match serde_json::from_str::<Value>(&strip_comments(&source)) {
+        Ok(Value::Object(map)) => map,
+        Ok(_) => return lint_err!("`{}` is not a JSON object", path.display()),
+        Err(err) => {
+            return lint_err!(
+                "failed to parse `{}` as JSON (YAML configs are not supported): {}",
+                path.display(),
+                err
+            )
+        }
+    };
+
+    let migration = Migration::new(&value);
+    let toml = match toml::Value::try_from(migration.to_toml())
+        .and_then(|value| toml::to_string_pretty(&value))
+    {
+        Ok(toml) => toml,
+        Err(err) => return lint_err!("failed to serialize the migrated config: {}", err),
+    };
+    if let Err(err) = write(out, toml) {
+        return lint_err!("failed to write to `{}`: {}", OUTPUT_NAME, err);
+    }
+    migration.report(&path);
+}
+
+fn find_eslint_config() -> Option<PathBuf> {
+    ESLINT_CONFIG_NAMES
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.exists())
+}
+
+impl Migration {
+    fn new(config: &Map<String, Value>) -> Self {
+        let mut migration = Self::default();
+
+        for (key, value) in config {
+            match key.as_str() {
+                "rules" => {
+                    for (name, rule_cfg) in value.as_object().into_iter().flatten() {
+                        migration.migrate_rule(name, rule_cfg);
+                    }
+                }
+                "extends" => {
+                    let extends = match value {
+                        Value::String(string) => vec![string.as_str()],
+                        Value::Array(array) => array.iter().filter_map(Value::as_str).collect(),
+                        _ => vec![],
+                    };
+                    for extend in extends {
+                        if extend == "eslint:recommended" {
+                            migration.groups.push("errors".to_string());
+                        } else {
+                            migration.ignored_keys.push(format!("extends: {}", extend));
+                        }
+                    }
+                }
+                "parserOptions" => {
+                    migration.global_return = value
+                        .pointer("/ecmaFeatures/globalReturn")
+                        .and_then(Value::as_bool)
+                        .unwrap_or_default();
+                }
+                "root" | "$schema" => {}
+                _ => migration.ignored_keys.push(key.to_owned()),
+            }
+        }
+        migration
+    }
+
+    fn migrate_rule(&mut self, name: &str, rule_cfg: &Value) {
+        let (severity, options) = match rule_cfg {
+            Value::Array(array) => (array.first(), array.get(1)),
+            severity => (Some(severity), None),
+        };
+        let severity = match severity.and_then(EslintSeverity::from_eslint) {
+            Some(severity) => severity,
+            None => {
+                return self
+                    .ignored_keys
+                    .push(format!("rules: {} (invalid severity)", name))
+            }
+        };
+
+        let rule = match get_rule_by_name(name) {
+            Some(rule) => rule,
+            None => return self.unknown_rules.push(name.to_string()),
+        };
+        if severity == EslintSeverity::Off {
+            return self.off.push(name.to_string());
+        }
+
+        let options = match options.map(|options| translate_options(name, options)) {
+            Some(Ok(options)) => options,
+            Some(Err(reason)) => {
+                self.dropped_options.push((name.to_string(), reason));
+                Map::new()
+            }
+            None => Map::new(),
+        };
+        let options = match check_options(&*rule, options) {
+            Ok(options) => options,
+            Err((options, reason)) => {
+                self.dropped_options.push((name.to_string(), reason));
+                options
+            }
+        };
+
+        if severity == EslintSeverity::Warning {
+            self.warnings
+                .insert(name.to_string(), Value::Object(options));
+        } else {
+            self.errors.insert(name.to_string(), Value::Object(options));
+        }
+    }
+
+    fn to_toml(&self) -> Value {
+        let mut rules = Map::new();
+        if !self.groups.is_empty() {
+            rules.insert("groups".to_string(), self.groups.clone().into());
+        }
+        if !self.off.is_empty() {
+            let levels = self
+                .off
+                .iter()
+                .map(|name| (name.to_owned(), Value::from("off")))
+                .collect();
+            rules.insert("levels".to_string(), Value::Object(levels));
+        }
+        if !self.errors.is_empty() {
+            rules.insert("errors".to_string(), Value::Object(self.errors.clone()));
+        }
+        if !self.warnings.is_empty() {
+            rules.insert("warnings".to_string(), Value::Object(self.warnings.clone()));
+        }
+
+        let mut config = Map::new();
+        config.insert("rules".to_string(), Value::Object(rules));
+        if self.global_return {
+            let mut parser = Map::new();
+            parser.insert("globalReturn".to_string(), true.into());
+            config.insert("parser".to_string(), Value::Object(parser));
+        }
+        Value::Object(config)
+    }
+
+    fn report(&self, path: &Path) {
+        let migrated = self.errors.len() + self.warnings.len() + self.off.len();
+        println!(
+            "{}: {} rule{} from `{}` to `{}`",
+            "Migrated".bright_green(),
+            migrated,
+            if migrated == 1 { "" } else { "s" },
+            path.display(),
+            OUTPUT_NAME
+        );
+
+        if !self.unknown_rules.is_empty() {
+            println!(
+                "\n{}:",
+                "The following rules have no rslint equivalent".yellow()
+            );
+            for rule in &self.unknown_rules {
+                println!(" {}", rule.white());
+            }
+        }
+
+        if !self.dropped_options.is_empty() {
+            println!(
+                "\n{}:",
+                "The following rule options were not migrated".yellow()
+            );
+            for (rule, reason) in &self.dropped_options {
+                println!(" {} - {}", rule.white(), reason);
+            }
+        }
+
+        if !self.ignored_keys.is_empty() {
+            println!("\n{}:", "The following settings were not migrated".yellow());
+            for key in &self.ignored_keys {
+                println!(" {}", key.white());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EslintSeverity {
+    Off,
+    Warning,
+    Error,
+}
+
+impl EslintSeverity {
+    fn from_eslint(value: &Value) -> Option<Self> {
+        Some(match value {
+            Value::String(string) => match string.as_str() {
+                "off" => EslintSeverity::Off,
+                "warn" => EslintSeverity::Warning,
+                "error" => EslintSeverity::Error,
+                _ => return None,
+            },
+            Value::Number(number) => match number.as_u64()? {
+                0 => EslintSeverity::Off,
+                1 => EslintSeverity::Warning,
+                2 => EslintSeverity::Error,
+                _ => return None,
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Translate the ESLint options of a rule to the rslint options.
+///
+/// Most rules take an object of camelCase options just like rslint, some however take a string
+/// which is a field in rslint.
+fn translate_options(name: &str, options: &Value) -> Result<Map<String, Value>, String> {
+    let mut map = Map::new();
+    match (name, options) {
+        (_, Value::Object(options)) => return Ok(options.to_owned()),
+        ("no-cond-assign", Value::String(string)) => {
+            let allow_parens = match string.as_str() {
+                "except-parens" => true,
+                "always" => false,
+                _ => return Err(format!("unknown option `{}`", string)),
+            };
+            map.insert("allowParens".to_string(), allow_parens.into());
+        }
+        ("no-inner-declarations", Value::String(string)) => {
+            let disallowed = match string.as_str() {
+                "functions" => vec!["functions"],
+                "both" => vec!["functions", "variables"],
+                _ => return Err(format!("unknown option `{}`", string)),
+            };
+            map.insert("disallowed".to_string(), disallowed.into());
+        }
+        ("block-spacing", Value::String(string)) => {
+            map.insert("style".to_string(), string.to_owned().into());
+        }
+        (_, options) => {
+            return Err(format!(
+                "options of the form `{}` are not supported",
+                options
+            ))
+        }
+    }
+    Ok(map)
+}
+
+/// Check that the rule can be deserialized from the options and remove any options it does not accept.
+///
+/// Returns the remaining options and the reason options were removed as an `Err` if any were.
+fn check_options(
+    rule: &dyn CstRule,
+    mut options: Map<String, Value>,
+) -> Result<Map<String, Value>, (Map<String, Value>, String)> {
+    if options.is_empty() {
+        return Ok(options);
+    }
+
+    let mut tagged = Map::new();
+    tagged.insert(rule.name().to_camel_case(), Value::Object(options.clone()));
+    let accepted = serde_json::from_value::<Box<dyn CstRule>>(Value::Object(tagged))
+        .and_then(|rule| serde_json::to_value(&rule))
+        .map_err(|err| (Map::new(), err.to_string()))?;
+    let accepted = accepted
+        .as_object()
+        .and_then(|tagged| tagged.values().next())
+        .and_then(Value::as_object);
+
+    let unknown = options
+        .keys()
+        .filter(|key| accepted.map_or(true, |accepted| !accepted.contains_key(*key)))
+        .cloned()
+        .collect::<Vec<_>>();
+    if unknown.is_empty() {
+        return Ok(options);
+    }
+
+    for key in &unknown {
+        options.remove(key);
+    }
+    let unknown = unknown
+        .iter()
+        .map(|key| format!("`{}`", key))
+        .collect::<Vec<_>>();
+    Err((options, format!("unknown options {}", unknown.join(", "))))
+}
+
+/// Strip line and block comments from JSON, which ESLint allows in its JSON configs.
+fn strip_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if c == '\\' {
+                out.extend(chars.next());
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.peek().map_or(false, |c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn eslint_severities() {
+        assert_eq!(
+            EslintSeverity::from_eslint(&json!("off")),
+            Some(EslintSeverity::Off)
+        );
+        assert_eq!(
+            EslintSeverity::from_eslint(&json!("warn")),
+            Some(EslintSeverity::Warning)
+        );
+        assert_eq!(
+            EslintSeverity::from_eslint(&json!(2)),
+            Some(EslintSeverity::Error)
+        );
+        assert_eq!(EslintSeverity::from_eslint(&json!(3)), None);
+        assert_eq!(EslintSeverity::from_eslint(&json!("fatal")), None);
+        assert_eq!(EslintSeverity::from_eslint(&json!(-1)), None);
+        assert_eq!(EslintSeverity::from_eslint(&json!(null)), None);
+    }
+
+    #[test]
+    fn string_options() {
+        let translate = |name, options| translate_options(name, &options).map(Value::Object);
+        assert_eq!(
+            translate("no-cond-assign", json!("except-parens")),
+            Ok(json!({ "allowParens": true }))
+        );
+        assert_eq!(
+            translate("no-cond-assign", json!("always")),
+            Ok(json!({ "allowParens": false }))
+        );
+        assert!(translate("no-cond-assign", json!("never")).is_err());
+        assert_eq!(
+            translate("no-inner-declarations", json!("functions")),
+            Ok(json!({ "disallowed": ["functions"] }))
+        );
+        assert_eq!(
+            translate("no-inner-declarations", json!("both")),
+            Ok(json!({ "disallowed": ["functions", "variables"] }))
+        );
+        assert_eq!(
+            translate("block-spacing", json!("never")),
+            Ok(json!({ "style": "never" }))
+        );
+        assert_eq!(
+            translate("block-spacing", json!({ "style": "always" })),
+            Ok(json!({ "style": "always" }))
+        );
+        assert!(translate("no-empty", json!("always")).is_err());
+        assert!(translate("no-empty", json!(["always"])).is_err());
+    }
+
+    #[test]
+    fn unknown_options_are_dropped() {
+        let rule = get_rule_by_name("no-cond-assign").unwrap();
+        let options = |value: Value| value.as_object().unwrap().to_owned();
+
+        let known = options(json!({ "allowParens": false }));
+        assert_eq!(check_options(&*rule, known.clone()), Ok(known));
+
+        let (kept, reason) = check_options(
+            &*rule,
+            options(json!({ "allowParens": false, "foo": 1, "bar": true })),
+        )
+        .unwrap_err();
+        assert_eq!(kept, options(json!({ "allowParens": false })));
+        assert!(reason.contains("`foo`") && reason.contains("`bar`"));
+    }
+
+    #[test]
+    fn comments_are_stripped() {
+        assert_eq!(
+            strip_comments("{\n  // rules\n  \"a\": 1 /* one */\n}"),
+            "{\n  \n  \"a\": 1 \n}"
+        );
+        assert_eq!(
+            strip_comments(r#"{ "a": "// not a comment", "b": "/* nor this */" }"#),
+            r#"{ "a": "// not a comment", "b": "/* nor this */" }"#
+        );
+        assert_eq!(
+            strip_comments(r#"{ "a": "\" // still a string" } // comment"#),
+            r#"{ "a": "\" // still a string" } "#
+        );
+    }
+}
@@ -1,9 +1,15 @@
 //! The custom panic hook used by the linter to issue a more descriptive explanation.
 
+use rslint_core::isolation::{is_catching_panics, record_panic, BUG_REPORT_URL};
 use std::io::{self, Write};
 use std::panic::PanicInfo;
 
 pub fn panic_hook(info: &PanicInfo) {
+    // panics of the parser and of rules are caught and reported as diagnostics of the file being linted
+    if is_catching_panics() {
+        return record_panic(info);
+    }
+
     let stderr = io::stderr();
 
     let mut stderr_lock = stderr.lock();
@@ -14,7 +20,7 @@ pub fn panic_hook(info: &PanicInfo) {
 
     write("The linter panicked unexpectedly. This is a bug.\n");
 
-    write("We would appreciate a bug report: https://github.com/RDambrosio016/RSLint/issues/new?labels=ILE%2C+bug&template=internal-linter-error.md\n");
+    write(format!("We would appreciate a bug report: {}\n", BUG_REPORT_URL).as_str());
 
     write("Please include the following info: \n");
 
@@ -0,0 +1,643 @@
+//! The lint pipeline, which walks, reads, parses, lints and emits files in parallel stages.
+//!
+//! Paths are found by a walker thread and sent through a bounded queue to the workers of the pool, which read,
+//! parse, lint and fix each file. The reports of the files go through another bounded queue to the thread
+//! running the pipeline, which emits them in the order the files were found as soon as every file before them
+//! is emitted. The walker stops while [`QUEUE_SIZE`] files are found but not emitted yet, so a slow file only
+//! holds back that many reports. Only the summary of each file is kept once it is emitted, so memory is bounded
+//! by the size of the queues rather than by the number of files. If project rules which need trees are run, files are kept with
+//! their trees and emitted once the project rules are run on every file. Project rules which do not need trees
+//! only keep the metadata of each file, and the diagnostics they find are emitted after every file.
+//!
+//! The rules of large files are split into batches, workers run the batches of other workers' files whenever
+//! they are waiting for their own batches or there is nothing else to do.
+
+use crate::{emit_diagnostics, lint_note, FileWalker};
+use crossbeam_channel::{bounded, select, unbounded, Receiver, Sender};
+use rslint_config::ParserConfig;
+use rslint_core::{
+    autofix::recursively_apply_fixes, lint_project, CstRuleStore, Diagnostic, File, LintJob,
+    LintResult, Outcome, Project, ProjectFile, ProjectRuleStore, RuleLevel, RuleResult,
+};
+use rslint_errors::Severity;
+use rslint_parser::SyntaxNode;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{read_to_string, write},
+    ops::Range,
+    path::PathBuf,
+    sync::Arc,
+};
+use yastl::Pool;
+
+/// The number of items which can wait in each queue of the pipeline, and the number of files which can be
+/// found but not emitted yet.
+pub(crate) const QUEUE_SIZE: usize = 64;
+
+/// How a pipeline lints and emits files.
+#[derive(Debug, Clone)]
+pub(crate) struct PipelineOptions {
+    pub verbose: bool,
+    pub fix: bool,
+    pub unsafe_fixes: bool,
+    pub dirty: bool,
+    pub formatter: String,
+}
+
+/// The outcome of linting every file, once all of them have been emitted.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Summary {
+    pub files: usize,
+    pub failures: usize,
+    pub warnings: usize,
+    pub successes: usize,
+    pub fix_count: usize,
+    /// Whether any error or bug diagnostic was emitted.
+    pub errors: bool,
+}
+
+impl Summary {
+    /// Remove a file which is added again with more diagnostics.
+    fn remove(&mut self, outcome: Outcome) {
+        self.files -= 1;
+        match outcome {
+            Outcome::Failure => self.failures -= 1,
+            Outcome::Warning => self.warnings -= 1,
+            Outcome::Success => self.successes -= 1,
+        }
+    }
+
+    fn add(&mut self, outcome: Outcome, diagnostics: &[Diagnostic]) {
+        self.files += 1;
+        match outcome {
+            Outcome::Failure => self.failures += 1,
+            Outcome::Warning => self.warnings += 1,
+            Outcome::Success => self.successes += 1,
+        }
+        self.errors |= diagnostics
+            .iter()
+            .any(|d| matches!(d.severity, Severity::Bug | Severity::Error));
+    }
+}
+
+/// What is kept of a linted file until it is emitted.
+struct Report {
+    file: File,
+    diagnostics: Vec<Diagnostic>,
+    fix_count: usize,
+    /// The file's tree, only kept if project rules which need trees are run.
+    root: Option<SyntaxNode>,
+}
+
+/// A batch of the rules of a large file, which any worker can run.
+struct Batch<'s> {
+    job: Arc<LintJob<'s>>,
+    rules: Range<usize>,
+    results: Sender<Vec<(&'static str, RuleResult)>>,
+}
+
+impl Batch<'_> {
+    fn run(self) {
+        let Batch {
+            job,
+            rules,
+            results,
+        } = self;
+        let res = job.run(rules);
+        // the job must be released before the results are sent so the file's worker can take it back
+        drop(job);
+        let _ = results.send(res);
+    }
+}
+
+/// Lint the files found in a list of paths on the pool and emit their diagnostics.
+///
+/// Files are emitted sorted by their path. Project rules are run once every file is linted, if any of them
+/// needs trees the files are only emitted after that.
+pub(crate) fn run_pipeline(
+    paths: Vec<PathBuf>,
+    store: &CstRuleStore,
+    project_store: &ProjectRuleStore,
+    levels: &HashMap<&'static str, RuleLevel>,
+    parser: &ParserConfig,
+    options: &PipelineOptions,
+    pool: &Pool,
+) -> Summary {
+    run_pipeline_with(
+        paths,
+        store,
+        project_store,
+        levels,
+        parser,
+        options,
+        pool,
+        &mut |file, diagnostics| emit_file(&options.formatter, file, diagnostics),
+    )
+}
+
+/// Run the pipeline, giving every file to `emit` with its diagnostics as it is emitted.
+///
+/// Files are given to `emit` even if they have no diagnostics, files which were emitted before project rules
+/// which do not need trees are run are given to it again with the diagnostics of the project rules.
+#[allow(clippy::too_many_arguments)]
+fn run_pipeline_with(
+    paths: Vec<PathBuf>,
+    store: &CstRuleStore,
+    project_store: &ProjectRuleStore,
+    levels: &HashMap<&'static str, RuleLevel>,
+    parser: &ParserConfig,
+    options: &PipelineOptions,
+    pool: &Pool,
+    emit: &mut dyn FnMut(File, &[Diagnostic]),
+) -> Summary {
+    let (batch_tx, batch_rx) = unbounded();
+    let worker = Worker {
+        store,
+        levels,
+        parser,
+        options,
+        keep_roots: project_store.needs_trees(),
+        threads: num_cpus::get(),
+        batch_tx,
+        batch_rx,
+    };
+    let mut reporter = Reporter::new(emit, !project_store.rules.is_empty());
+
+    let (path_tx, path_rx) = bounded(QUEUE_SIZE);
+    let (report_tx, report_rx) = bounded(QUEUE_SIZE);
+    // the walker takes a slot for every file it finds and the reporter frees it once the file is emitted
+    let (slot_tx, slot_rx) = bounded(QUEUE_SIZE);
+    std::thread::scope(|threads| {
+        threads.spawn(move || {
+            for path in FileWalker::walk(paths.into_iter()).enumerate() {
+                if slot_tx.send(()).is_err() || path_tx.send(path).is_err() {
+                    break;
+                }
+            }
+        });
+
+        pool.scoped(|scope| {
+            for _ in 0..worker.threads {
+                let path_rx = path_rx.clone();
+                let report_tx = report_tx.clone();
+                let worker = &worker;
+                scope.execute(move || worker.work(path_rx, report_tx));
+            }
+            drop(report_tx);
+
+            for (idx, report) in report_rx {
+                for _ in 0..reporter.add(idx, report) {
+                    let _ = slot_rx.recv();
+                }
+            }
+        });
+    });
+
+    reporter.finish(project_store, levels, options.verbose)
+}
+
+/// The state shared by the workers of a pipeline.
+struct Worker<'a, 's> {
+    store: &'s CstRuleStore,
+    levels: &'a HashMap<&'static str, RuleLevel>,
+    parser: &'a ParserConfig,
+    options: &'a PipelineOptions,
+    keep_roots: bool,
+    threads: usize,
+    batch_tx: Sender<Batch<'s>>,
+    batch_rx: Receiver<Batch<'s>>,
+}
+
+impl<'s> Worker<'_, 's> {
+    /// Lint files until there are no paths left, running the batches of other files in between.
+    fn work(&self, paths: Receiver<(usize, PathBuf)>, reports: Sender<(usize, Option<Report>)>) {
+        loop {
+            select! {
+                recv(self.batch_rx) -> batch => batch.expect("the worker holds a sender").run(),
+                recv(paths) -> path => match path {
+                    Ok((idx, path)) => {
+                        if reports.send((idx, self.lint(path))).is_err() {
+                            return;
+                        }
+                    }
+                    Err(_) => return,
+                },
+            }
+        }
+    }
+
+    fn lint(&self, path: PathBuf) -> Option<Report> {
+        let mut file = FileWalker::read_file(&path)?;
+        file.syntax = self.parser.syntax(file.path.as_deref(), file.kind);
+
+        let mut result = self.run(LintJob::new(&file, self.store, self.options.verbose));
+        let fix_count = if self.options.fix {
+            self.fix(&mut result, &mut file)
+        } else {
+            0
+        };
+
+        for (rule, res) in result.rule_results.iter_mut() {
+            let level = self.levels.get(rule).copied().unwrap_or(RuleLevel::Error);
+            level.remap_diagnostics(&mut res.diagnostics);
+        }
+        Some(Report {
+            diagnostics: result.diagnostics().cloned().collect(),
+            root: Some(result.parsed).filter(|_| self.keep_roots),
+            file,
+            fix_count,
+        })
+    }
+
+    /// Run every batch of a job, sharing all but the first batch with the other workers.
+    fn run(&self, job: LintJob<'s>) -> LintResult<'s> {
+        let mut batches = job.batches(self.threads).into_iter();
+        let first = batches.next().unwrap_or(0..0);
+        if batches.len() == 0 {
+            let results = job.run(first);
+            return job.finish(results);
+        }
+
+        let job = Arc::new(job);
+        let (tx, rx) = unbounded();
+        let mut pending = batches.len();
+        for rules in batches {
+            let batch = Batch {
+                job: job.clone(),
+                rules,
+                results: tx.clone(),
+            };
+            self.batch_tx
+                .send(batch)
+                .expect("the worker holds a receiver");
+        }
+
+        let mut results = job.run(first);
+        while pending > 0 {
+            select! {
+                recv(rx) -> res => {
+                    results.extend(res.expect("the worker holds a sender"));
+                    pending -= 1;
+                }
+                recv(self.batch_rx) -> batch => batch.expect("the worker holds a sender").run(),
+            }
+        }
+        match Arc::try_unwrap(job) {
+            Ok(job) => job.finish(results),
+            Err(_) => unreachable!("every batch of the job released it"),
+        }
+    }
+
+    /// Fix a file and write it, returning the number of issues fixed.
+    fn fix(&self, result: &mut LintResult, file: &mut File) -> usize {
+        let path = match file.path.clone() {
+            Some(path) => path,
+            // skip virtual files
+            None => return 0,
+        };
+        // the tree of a file the parser panicked on is empty
+        if result.parser_panicked() {
+            return 0;
+        }
+        if result
+            .parser_diagnostics
+            .iter()
+            .any(|x| x.severity == Severity::Error)
+            && !self.options.dirty
+        {
+            lint_note!(
+                "skipping autofix for `{}` because it contains syntax errors",
+                path.to_string_lossy()
+            );
+            return 0;
+        }
+
+        let problems = |result: &LintResult| {
+            result
+                .rule_results
+                .values()
+                .filter(|x| x.outcome() == Outcome::Warning || x.outcome() == Outcome::Failure)
+                .map(|res| res.diagnostics.len())
+                .sum::<usize>()
+        };
+        let original_problem_num = problems(result);
+        let fixed = recursively_apply_fixes(result, file, self.options.unsafe_fixes);
+        let new_problem_num = problems(result);
+        if let Err(err) = write(&path, fixed.clone()) {
+            crate::lint_err!("failed to write to `{:#?}`: {}", path, err.to_string());
+            0
+        } else {
+            file.update_src(fixed);
+            original_problem_num.saturating_sub(new_problem_num)
+        }
+    }
+}
+
+/// Emits the reports of files as they come in, in the order the files were found.
+struct Reporter<'a> {
+    emit: &'a mut dyn FnMut(File, &[Diagnostic]),
+    /// The index of the next file to emit.
+    next: usize,
+    /// Reports which arrived before the reports of the files found before them, there are less than
+    /// [`QUEUE_SIZE`] of them.
+    pending: BTreeMap<usize, Option<Report>>,
+    /// Files kept to run project rules on, which are emitted once the project rules are run.
+    kept: Vec<(File, SyntaxNode, Vec<Diagnostic>)>,
+    /// Whether project rules are run, the metadata of files emitted without their tree is kept for them.
+    project: bool,
+    /// The metadata and outcome of files emitted before project rules which do not need trees are run.
+    emitted: Vec<(File, Outcome)>,
+    summary: Summary,
+}
+
+impl<'a> Reporter<'a> {
+    fn new(emit: &'a mut dyn FnMut(File, &[Diagnostic]), project: bool) -> Self {
+        Self {
+            emit,
+            next: 0,
+            pending: BTreeMap::new(),
+            kept: vec![],
+            project,
+            emitted: vec![],
+            summary: Summary::default(),
+        }
+    }
+
+    /// Add the report of a file and emit every report which is next in order, returning how many were emitted.
+    fn add(&mut self, idx: usize, report: Option<Report>) -> usize {
+        self.pending.insert(idx, report);
+        let first = self.next;
+        while let Some(report) = self.pending.remove(&self.next) {
+            self.emit(report);
+            self.next += 1;
+        }
+        self.next - first
+    }
+
+    fn emit(&mut self, report: Option<Report>) {
+        let Report {
+            file,
+            diagnostics,
+            fix_count,
+            root,
+        } = match report {
+            Some(report) => report,
+            None => return,
+        };
+        self.summary.fix_count += fix_count;
+        if let Some(root) = root {
+            return self.kept.push((file, root, diagnostics));
+        }
+
+        let outcome = Outcome::from(diagnostics.iter());
+        if self.project {
+            self.emitted.push((file.metadata(), outcome));
+        }
+        self.summary.add(outcome, &diagnostics);
+        (self.emit)(file, &diagnostics);
+    }
+
+    /// Run the project rules on the files kept and emit them with the diagnostics of the project rules,
+    /// then emit the diagnostics of the project rules for files which were already emitted and return the summary.
+    fn finish(
+        mut self,
+        project_store: &ProjectRuleStore,
+        levels: &HashMap<&'static str, RuleLevel>,
+        verbose: bool,
+    ) -> Summary {
+        if self.kept.is_empty() && self.emitted.is_empty() {
+            return self.summary;
+        }
+
+        let mut by_file = HashMap::<usize, Vec<Diagnostic>>::new();
+        {
+            let kept = self.kept.iter().map(|(file, root, _)| ProjectFile {
+                file,
+                root: Some(root.clone()),
+            });
+            let emitted = self
+                .emitted
+                .iter()
+                .map(|(file, _)| ProjectFile { file, root: None });
+            let project = Project::new(kept.chain(emitted));
+            for (rule, mut diagnostics) in
+                lint_project(&project, project_store, verbose).rule_results
+            {
+                let level = levels.get(rule).copied().unwrap_or(RuleLevel::Error);
+                level.remap_diagnostics(&mut diagnostics);
+                for d in diagnostics {
+                    by_file.entry(d.file_id).or_default().push(d);
+                }
+            }
+        }
+
+        for (file, _, mut diagnostics) in std::mem::take(&mut self.kept) {
+            diagnostics.extend(by_file.remove(&file.id).unwrap_or_default());
+            self.summary
+                .add(Outcome::from(diagnostics.iter()), &diagnostics);
+            (self.emit)(file, &diagnostics);
+        }
+
+        for (mut file, outcome) in std::mem::take(&mut self.emitted) {
+            let diagnostics = match by_file.remove(&file.id) {
+                Some(diagnostics) => diagnostics,
+                None => continue,
+            };
+            // the source is read again to render the diagnostics, it is the fixed source if the file was fixed
+            if let Some(source) = file
+                .path
+                .as_ref()
+                .and_then(|path| read_to_string(path).ok())
+            {
+                file.update_src(source);
+            }
+            self.summary.remove(outcome);
+            self.summary.add(
+                Outcome::merge([outcome, Outcome::from(diagnostics.iter())]),
+                &diagnostics,
+            );
+            (self.emit)(file, &diagnostics);
+        }
+        self.summary
+    }
+}
+
+/// Emit the diagnostics of a file with a formatter.
+fn emit_file(formatter: &str, file: File, diagnostics: &[Diagnostic]) {
+    let files = FileWalker {
+        files: std::iter::once((file.id, file)).collect(),
+    };
+    if !diagnostics.is_empty() {
+        emit_diagnostics(formatter, diagnostics, &files);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_core::{get_project_rule_by_name, get_rule_by_name};
+    use std::{
+        fs,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    /// The number of files linted by each test, enough for the walker to wait for files to be emitted.
+    const FILES: usize = QUEUE_SIZE * 3;
+
+    /// A temporary directory with some files in it, which is removed when it is dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(files: &[(String, &str)]) -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let dir = std::env::temp_dir().join(format!(
+                "rslint-pipeline-test-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::SeqCst)
+            ));
+            fs::create_dir_all(&dir).unwrap();
+            for (name, source) in files {
+                fs::write(dir.join(name), source).unwrap();
+            }
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Files `000.js` to the last of [`FILES`], created in reverse order so the order of the directory
+    /// does not match the order they are emitted in.
+    fn files<'a>(sources: &[(&str, &'a str)]) -> Vec<(String, &'a str)> {
+        let mut files = (0..FILES)
+            .rev()
+            .map(|idx| (format!("{:03}.js", idx), "let a = 1;\n"))
+            .collect::<Vec<_>>();
+        for (name, source) in sources {
+            files.retain(|(x, _)| x != name);
+            files.push((name.to_string(), *source));
+        }
+        files
+    }
+
+    /// Lint a directory with `no-empty` and some project rules, returning the name of each file given to
+    /// `emit` with the sorted codes of its diagnostics.
+    ///
+    /// The last file is removed as soon as the first file is emitted, it is only linted if every file is
+    /// linted before any is emitted.
+    fn lint(dir: &TempDir, project_rules: &[&str]) -> (Vec<(String, Vec<String>)>, Summary) {
+        let mut store = CstRuleStore::new();
+        store.load_rules(get_rule_by_name("no-empty"));
+        let mut project_store = ProjectRuleStore::new();
+        project_store.load_rules(
+            project_rules
+                .iter()
+                .map(|name| get_project_rule_by_name(name).unwrap()),
+        );
+        let options = PipelineOptions {
+            verbose: false,
+            fix: false,
+            unsafe_fixes: false,
+            dirty: false,
+            formatter: "short".to_string(),
+        };
+
+        let last = dir.0.join(format!("{:03}.js", FILES - 1));
+        let mut emitted = vec![];
+        let summary = run_pipeline_with(
+            vec![dir.0.clone()],
+            &store,
+            &project_store,
+            &HashMap::new(),
+            &ParserConfig::default(),
+            &options,
+            &Pool::new(4),
+            &mut |file, diagnostics| {
+                let _ = fs::remove_file(&last);
+                let mut codes = diagnostics
+                    .iter()
+                    .map(|d| d.code.clone().unwrap())
+                    .collect::<Vec<_>>();
+                codes.sort();
+                let name = file
+                    .path
+                    .unwrap()
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned();
+                emitted.push((name, codes));
+            },
+        );
+        (emitted, summary)
+    }
+
+    fn names(emitted: &[(String, Vec<String>)]) -> Vec<&str> {
+        emitted.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn files_are_emitted_in_order() {
+        let dir = TempDir::new(&files(&[("007.js", "{}\n")]));
+        let (emitted, summary) = lint(&dir, &[]);
+
+        let expected = (0..FILES - 1)
+            .map(|idx| format!("{:03}.js", idx))
+            .collect::<Vec<_>>();
+        assert_eq!(names(&emitted), expected);
+        for (name, codes) in &emitted {
+            let expected: &[&str] = if name == "007.js" { &["no-empty"] } else { &[] };
+            assert_eq!(codes, expected, "{}", name);
+        }
+        assert_eq!(summary.files, FILES - 1);
+        assert_eq!(summary.failures, 1);
+        assert!(summary.errors);
+    }
+
+    #[test]
+    fn files_are_streamed_if_project_rules_do_not_need_trees() {
+        let dir = TempDir::new(&files(&[("000.ts", "{}\n")]));
+        let (emitted, summary) = lint(&dir, &["no-duplicate-module-names"]);
+
+        // the last file was removed before it was read, and 000.ts is emitted again with the project diagnostics
+        let mut expected = vec!["000.js".to_string(), "000.ts".to_string()];
+        expected.extend((1..FILES - 1).map(|idx| format!("{:03}.js", idx)));
+        expected.push("000.ts".to_string());
+        assert_eq!(names(&emitted), expected);
+        assert_eq!(emitted[1].1, ["no-empty"]);
+        assert_eq!(emitted.last().unwrap().1, ["no-duplicate-module-names"]);
+        assert_eq!(summary.files, FILES);
+        assert_eq!(summary.failures, 1);
+    }
+
+    #[test]
+    fn project_diagnostics_are_merged_if_project_rules_need_trees() {
+        let dir = TempDir::new(&files(&[("000.ts", "import \"./missing\";\n{}\n")]));
+        let (emitted, summary) = lint(
+            &dir,
+            &["no-duplicate-module-names", "no-unresolved-imports"],
+        );
+
+        // every file is linted before the first is emitted, so the last file is linted too
+        let mut expected = vec!["000.js".to_string(), "000.ts".to_string()];
+        expected.extend((1..FILES).map(|idx| format!("{:03}.js", idx)));
+        assert_eq!(names(&emitted), expected);
+        assert_eq!(
+            emitted[1].1,
+            [
+                "no-duplicate-module-names",
+                "no-empty",
+                "no-unresolved-imports"
+            ]
+        );
+        assert!(emitted
+            .iter()
+            .filter(|(name, _)| name != "000.ts")
+            .all(|(_, codes)| codes.is_empty()));
+        assert_eq!(summary.files, FILES + 1);
+        assert_eq!(summary.failures, 1);
+    }
+}
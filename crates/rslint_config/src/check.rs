@@ -0,0 +1,1107 @@
+//! Validation of a config which collects every problem in it along with its exact span.
+//!
+//! Deserialization stops at the first error and silently ignores unknown keys, therefore the
+//! config is first parsed into a loosely typed tree which keeps the span of every key and value,
+//! then the tree is checked against what the config accepts.
+
+use crate::normalize_rule_name;
+use crate::ConfigStyle;
+use heck::CamelCase;
+use rslint_core::{
+    get_group_rules_by_name, get_project_rule_by_name, get_project_rule_suggestion,
+    get_rule_by_name, get_rule_suggestion, util::find_best_match_for_name, CstRule, CustomRule,
+    CustomRuleConfig, CustomRuleError, Diagnostic, ProjectRule, RuleLevel, Severity,
+};
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::{fmt, ops::Range};
+
+const TOP_LEVEL_KEYS: [&str; 5] = ["rules", "errors", "parser", "plugins", "custom_rules"];
+const RULES_KEYS: [&str; 6] = [
+    "errors", "warnings", "project", "groups", "allowed", "levels",
+];
+const CUSTOM_RULE_KEYS: [&str; 6] = ["name", "message", "severity", "pattern", "kind", "rewrite"];
+const ERRORS_KEYS: [&str; 1] = ["formatter"];
+const FORMATTERS: [&str; 2] = ["short", "long"];
+const PARSER_OPTIONS: [&str; 4] = ["decorators", "classFields", "globalReturn", "topLevelAwait"];
+const LEVELS: [&str; 6] = ["off", "hint", "info", "warn", "warning", "error"];
+// TODO: dont hardcode it like this, same as the schema
+const GROUPS: [&str; 3] = ["errors", "style", "regex"];
+
+/// A value along with its byte range in the config source.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Spanned<T> {
+    pub span: Range<usize>,
+    pub value: T,
+}
+
+/// A loosely typed config value which can come from both TOML and JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Table(Vec<(Spanned<String>, Spanned<Value>)>),
+    Array(Vec<Spanned<Value>>),
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Null,
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Table(_) => "a table",
+            Value::Array(_) => "an array",
+            Value::String(_) => "a string",
+            Value::Integer(_) => "an integer",
+            Value::Float(_) => "a float",
+            Value::Boolean(_) => "a boolean",
+            Value::Null => "null",
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::Table(entries) => serde_json::Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| (key.value.clone(), value.value.to_json()))
+                    .collect(),
+            ),
+            Value::Array(items) => items.iter().map(|item| item.value.to_json()).collect(),
+            Value::String(string) => string.clone().into(),
+            Value::Integer(int) => (*int).into(),
+            Value::Float(float) => (*float).into(),
+            Value::Boolean(boolean) => (*boolean).into(),
+            Value::Null => serde_json::Value::Null,
+        }
+    }
+}
+
+/// Parse a config source into a spanned value tree.
+///
+/// Returns `None` if the source is not valid TOML or JSON, the deserializer is responsible for reporting that.
+pub(crate) fn parse(source: &str, style: &ConfigStyle) -> Option<Spanned<Value>> {
+    match style {
+        ConfigStyle::Toml => {
+            let root = toml::from_str::<toml::Spanned<Value>>(source).ok()?;
+            Some(from_toml_spanned(root))
+        }
+        ConfigStyle::Json => JsonParser::new(source).parse(),
+        ConfigStyle::PackageJson => match JsonParser::new(source).parse()?.value {
+            Value::Table(entries) => entries
+                .into_iter()
+                .find(|(key, _)| key.value == "rslint")
+                .map(|(_, value)| value),
+            _ => None,
+        },
+    }
+}
+
+/// Check a parsed config, returning a diagnostic for every problem found.
+pub(crate) fn check(root: &Spanned<Value>) -> Vec<Diagnostic> {
+    let mut checker = Checker::default();
+    checker.check_root(root);
+    checker.diagnostics
+}
+
+#[derive(Default)]
+struct Checker {
+    diagnostics: Vec<Diagnostic>,
+    /// The names of the custom rules declared in the config, which are valid in `levels` and `allowed`.
+    custom_rules: Vec<String>,
+}
+
+impl Checker {
+    fn check_root(&mut self, root: &Spanned<Value>) {
+        // custom rules are checked first so the rules table can refer to them
+        let entries = self.table(root);
+        for (_, value) in entries
+            .iter()
+            .filter(|(key, _)| key.value == "custom_rules")
+        {
+            self.check_custom_rules(value);
+        }
+
+        for (key, value) in entries {
+            match key.value.as_str() {
+                "rules" => self.check_rules(value),
+                "errors" => self.check_errors(value),
+                "parser" => self.check_parser(value),
+                "plugins" => self.check_plugins(value),
+                "custom_rules" => {}
+                _ => self.unknown_key(key, &TOP_LEVEL_KEYS, "the config"),
+            }
+        }
+    }
+
+    fn check_rules(&mut self, rules: &Spanned<Value>) {
+        let mut configured: Vec<(String, &Spanned<String>, &str)> = vec![];
+
+        for (key, value) in self.table(rules) {
+            match key.value.as_str() {
+                "errors" | "warnings" => {
+                    for (name, options) in self.table(value) {
+                        let kebab = normalize_rule_name(&name.value);
+                        if !self.check_rule_name(name, false) {
+                            continue;
+                        }
+                        if let Some((_, prev, prev_table)) =
+                            configured.iter().find(|(n, ..)| *n == kebab)
+                        {
+                            let (errors, warnings) = if *prev_table == "errors" {
+                                (*prev, name)
+                            } else {
+                                (name, *prev)
+                            };
+                            let d = Diagnostic::warning(
+                                1,
+                                "config",
+                                format!("'{}' is configured in both errors and warnings", kebab),
+                            )
+                            .primary(
+                                warnings.span.clone(),
+                                "the configuration in warnings is ignored",
+                            )
+                            .secondary(
+                                errors.span.clone(),
+                                "because the configuration in errors takes precedence",
+                            );
+                            self.diagnostics.push(d);
+                        }
+                        configured.push((kebab.clone(), name, key.value.as_str()));
+                        self.check_rule_options(&kebab, options);
+                    }
+                }
+                "project" => {
+                    for (name, options) in self.table(value) {
+                        let kebab = normalize_rule_name(&name.value);
+                        if self.check_project_rule_name(name) {
+                            self.check_project_rule_options(&kebab, options);
+                        }
+                    }
+                }
+                "groups" => {
+                    for group in self.strings(value) {
+                        if get_group_rules_by_name(&group.value).is_none() {
+                            self.unknown_value(&group, &GROUPS, "rule group", Severity::Warning);
+                        }
+                    }
+                }
+                "allowed" => {
+                    for rule in self.strings(value) {
+                        self.check_rule_name(&rule, true);
+                    }
+                }
+                "levels" => {
+                    for (name, level) in self.table(value) {
+                        self.check_rule_name(name, true);
+                        if let Value::String(string) = &level.value {
+                            if !LEVELS.contains(&string.as_str()) {
+                                let spanned = Spanned {
+                                    span: level.span.clone(),
+                                    value: string.to_owned(),
+                                };
+                                self.unknown_value(
+                                    &spanned,
+                                    &LEVELS,
+                                    "rule level",
+                                    Severity::Error,
+                                );
+                            }
+                        } else {
+                            self.wrong_type(level, "a string");
+                        }
+                    }
+                }
+                _ => self.unknown_key(key, &RULES_KEYS, "rules"),
+            }
+        }
+    }
+
+    /// Check that a rule exists, returns `false` if it does not.
+    /// Project rules and custom rules, which have no options, are only accepted if `allow_project` is true.
+    fn check_rule_name(&mut self, name: &Spanned<String>, allow_project: bool) -> bool {
+        let kebab = normalize_rule_name(&name.value);
+        let is_project = get_project_rule_by_name(&kebab).is_some();
+        let is_custom = self.custom_rules.contains(&kebab);
+        if get_rule_by_name(&kebab).is_some() || (allow_project && (is_project || is_custom)) {
+            return true;
+        }
+
+        let mut d = Diagnostic::error(1, "config", format!("unknown rule '{}'", name.value))
+            .primary(name.span.clone(), "");
+        if is_project {
+            d = d.footer_note("project rules are configured in the project table");
+        } else if is_custom {
+            d = d.footer_note(
+                "custom rules have no options, their level is set in the levels table",
+            );
+        } else if let Some(suggestion) = get_rule_suggestion(&kebab) {
+            d = d.footer_help(format!("did you mean '{}'?", suggestion));
+        }
+        self.diagnostics.push(d);
+        false
+    }
+
+    /// Check that a project rule exists, returns `false` if it does not.
+    fn check_project_rule_name(&mut self, name: &Spanned<String>) -> bool {
+        let kebab = normalize_rule_name(&name.value);
+        if get_project_rule_by_name(&kebab).is_some() {
+            return true;
+        }
+
+        let mut d = Diagnostic::error(
+            1,
+            "config",
+            format!("unknown project rule '{}'", name.value),
+        )
+        .primary(name.span.clone(), "");
+        if get_rule_by_name(&kebab).is_some() {
+            d = d
+                .footer_note("rules which check single files are configured in errors or warnings");
+        } else if let Some(suggestion) = get_project_rule_suggestion(&kebab) {
+            d = d.footer_help(format!("did you mean '{}'?", suggestion));
+        }
+        self.diagnostics.push(d);
+        false
+    }
+
+    fn check_rule_options(&mut self, name: &str, options: &Spanned<Value>) {
+        // the options of plugin rules are given to the plugin as they are
+        if name.contains('/') {
+            return;
+        }
+        let rule = match get_rule_by_name(name) {
+            Some(rule) => rule,
+            None => return,
+        };
+        let known = option_names(serde_json::to_value(&rule).ok());
+        self.check_options::<dyn CstRule>(name, &known, options);
+    }
+
+    fn check_project_rule_options(&mut self, name: &str, options: &Spanned<Value>) {
+        let rule = match get_project_rule_by_name(name) {
+            Some(rule) => rule,
+            None => return,
+        };
+        let known = option_names(serde_json::to_value(&rule).ok());
+        self.check_options::<dyn ProjectRule>(name, &known, options);
+    }
+
+    /// Check the options of a rule by deserializing each option on its own.
+    fn check_options<T>(&mut self, name: &str, known: &[String], options: &Spanned<Value>)
+    where
+        T: ?Sized,
+        Box<T>: serde::de::DeserializeOwned,
+    {
+        let tag = name.to_camel_case();
+
+        for (key, value) in self.table(options) {
+            if !known.iter().any(|option| *option == key.value) {
+                let expected = known.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+                self.unknown_key(key, &expected, &format!("the options of '{}'", name));
+                continue;
+            }
+
+            let mut object = serde_json::Map::new();
+            object.insert(key.value.clone(), value.value.to_json());
+            let mut tagged = serde_json::Map::new();
+            tagged.insert(tag.clone(), object.into());
+
+            if let Err(err) = serde_json::from_value::<Box<T>>(tagged.into()) {
+                let d = Diagnostic::error(
+                    1,
+                    "config",
+                    format!("invalid value for '{}': {}", key.value, err),
+                )
+                .primary(value.span.clone(), "");
+                self.diagnostics.push(d);
+            }
+        }
+    }
+
+    /// Check the names and paths of plugins, plugins which fail to load are reported when they are loaded.
+    fn check_plugins(&mut self, plugins: &Spanned<Value>) {
+        for (name, path) in self.table(plugins) {
+            if name.value.is_empty() || name.value.contains('/') {
+                let d = Diagnostic::error(
+                    1,
+                    "config",
+                    format!("'{}' is not a valid plugin name", name.value),
+                )
+                .primary(name.span.clone(), "")
+                .footer_note(
+                    "plugin names are used as the prefix of their rules, such as 'plugin/rule'",
+                );
+                self.diagnostics.push(d);
+            }
+            if !matches!(path.value, Value::String(_)) {
+                self.wrong_type(path, "a path to a .wasm file");
+            }
+        }
+    }
+
+    /// Check every custom rule, compiling the rule to check its pattern or kind and its templates.
+    fn check_custom_rules(&mut self, rules: &Spanned<Value>) {
+        'rules: for rule in self.array(rules) {
+            let mut fields = vec![];
+            for (key, value) in self.table(rule) {
+                if !CUSTOM_RULE_KEYS.contains(&key.value.as_str()) {
+                    self.unknown_key(key, &CUSTOM_RULE_KEYS, "a custom rule");
+                } else if let Value::String(string) = &value.value {
+                    fields.push((key.value.as_str(), value.span.clone(), string.as_str()));
+                } else {
+                    self.wrong_type(value, "a string");
+                    continue 'rules;
+                }
+            }
+            let field = |name: &str| fields.iter().find(|(key, ..)| *key == name);
+
+            let missing = ["name", "message"]
+                .iter()
+                .filter(|key| field(key).is_none())
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                for key in missing {
+                    let d = Diagnostic::error(
+                        1,
+                        "config",
+                        format!("a custom rule must have a '{}'", key),
+                    )
+                    .primary(rule.span.clone(), "");
+                    self.diagnostics.push(d);
+                }
+                continue;
+            }
+
+            let (_, name_span, name) = field("name").unwrap();
+            let (name_span, name) = (name_span.clone(), name.to_string());
+            let severity = match field("severity") {
+                Some((_, span, level)) => {
+                    match serde_json::from_value::<RuleLevel>((*level).into()) {
+                        Ok(level) => Some(level),
+                        Err(_) => {
+                            let spanned = Spanned {
+                                span: span.clone(),
+                                value: level.to_string(),
+                            };
+                            self.unknown_value(&spanned, &LEVELS, "rule level", Severity::Error);
+                            continue;
+                        }
+                    }
+                }
+                None => None,
+            };
+            let config = CustomRuleConfig {
+                name: name.clone(),
+                message: field("message").unwrap().2.to_string(),
+                severity,
+                pattern: field("pattern").map(|(.., pattern)| pattern.to_string()),
+                kind: field("kind").map(|(.., kind)| kind.to_string()),
+                rewrite: field("rewrite").map(|(.., rewrite)| rewrite.to_string()),
+            };
+
+            if let Err(err) = CustomRule::new(config) {
+                let span = match &err {
+                    CustomRuleError::InvalidName(_) => Some(name_span),
+                    CustomRuleError::Matcher => None,
+                    CustomRuleError::Pattern(_) => {
+                        field("pattern").map(|(_, span, _)| span.clone())
+                    }
+                    CustomRuleError::UnknownKind(_) => {
+                        field("kind").map(|(_, span, _)| span.clone())
+                    }
+                    CustomRuleError::UnknownVariable { field: name, .. } => {
+                        field(name).map(|(_, span, _)| span.clone())
+                    }
+                };
+                let d = Diagnostic::error(1, "config", format!("invalid custom rule: {}", err))
+                    .primary(span.unwrap_or_else(|| rule.span.clone()), "");
+                self.diagnostics.push(d);
+                continue;
+            }
+
+            let taken =
+                if get_rule_by_name(&name).is_some() || get_project_rule_by_name(&name).is_some() {
+                    Some("a built in rule")
+                } else if self.custom_rules.contains(&name) {
+                    Some("another custom rule")
+                } else {
+                    None
+                };
+            if let Some(taken) = taken {
+                let d = Diagnostic::error(
+                    1,
+                    "config",
+                    format!("the name '{}' is already used by {}", name, taken),
+                )
+                .primary(name_span, "");
+                self.diagnostics.push(d);
+                continue;
+            }
+            self.custom_rules.push(name);
+        }
+    }
+
+    fn check_errors(&mut self, errors: &Spanned<Value>) {
+        for (key, value) in self.table(errors) {
+            match key.value.as_str() {
+                "formatter" => {
+                    if let Value::String(string) = &value.value {
+                        if !FORMATTERS.contains(&string.as_str()) {
+                            let spanned = Spanned {
+                                span: value.span.clone(),
+                                value: string.to_owned(),
+                            };
+                            self.unknown_value(
+                                &spanned,
+                                &FORMATTERS,
+                                "formatter",
+                                Severity::Warning,
+                            );
+                        }
+                    } else {
+                        self.wrong_type(value, "a string");
+                    }
+                }
+                _ => self.unknown_key(key, &ERRORS_KEYS, "errors"),
+            }
+        }
+    }
+
+    fn check_parser(&mut self, parser: &Spanned<Value>) {
+        for (key, value) in self.table(parser) {
+            match key.value.as_str() {
+                "overrides" => {
+                    for over in self.array(value) {
+                        for (key, value) in self.table(over) {
+                            match key.value.as_str() {
+                                "files" => {
+                                    for pat in self.strings(value) {
+                                        if let Err(err) = glob::Pattern::new(&pat.value) {
+                                            let d = Diagnostic::warning(
+                                                1,
+                                                "config",
+                                                format!("invalid glob pattern: {}", err),
+                                            )
+                                            .primary(pat.span.clone(), "this pattern is ignored");
+                                            self.diagnostics.push(d);
+                                        }
+                                    }
+                                }
+                                _ => self.check_parser_option(key, value, &["files"]),
+                            }
+                        }
+                    }
+                }
+                _ => self.check_parser_option(key, value, &["overrides"]),
+            }
+        }
+    }
+
+    fn check_parser_option(
+        &mut self,
+        key: &Spanned<String>,
+        value: &Spanned<Value>,
+        other_keys: &[&str],
+    ) {
+        if PARSER_OPTIONS.contains(&key.value.as_str()) {
+            if !matches!(value.value, Value::Boolean(_)) {
+                self.wrong_type(value, "a boolean");
+            }
+        } else {
+            let expected = PARSER_OPTIONS
+                .iter()
+                .chain(other_keys)
+                .copied()
+                .collect::<Vec<_>>();
+            self.unknown_key(key, &expected, "parser");
+        }
+    }
+
+    /// Get the entries of a table or report that the value is not a table.
+    fn table<'a>(&mut self, value: &'a Spanned<Value>) -> &'a [(Spanned<String>, Spanned<Value>)] {
+        match &value.value {
+            Value::Table(entries) => entries,
+            _ => {
+                self.wrong_type(value, "a table");
+                &[]
+            }
+        }
+    }
+
+    /// Get the items of an array or report that the value is not an array.
+    fn array<'a>(&mut self, value: &'a Spanned<Value>) -> &'a [Spanned<Value>] {
+        match &value.value {
+            Value::Array(items) => items,
+            _ => {
+                self.wrong_type(value, "an array");
+                &[]
+            }
+        }
+    }
+
+    /// Get the strings of an array of strings, reporting any items which are not strings.
+    fn strings(&mut self, value: &Spanned<Value>) -> Vec<Spanned<String>> {
+        let mut strings = vec![];
+        for item in self.array(value) {
+            match &item.value {
+                Value::String(string) => strings.push(Spanned {
+                    span: item.span.clone(),
+                    value: string.to_owned(),
+                }),
+                _ => self.wrong_type(item, "a string"),
+            }
+        }
+        strings
+    }
+
+    fn wrong_type(&mut self, value: &Spanned<Value>, expected: &str) {
+        let d = Diagnostic::error(
+            1,
+            "config",
+            format!(
+                "expected {}, but found {}",
+                expected,
+                value.value.type_name()
+            ),
+        )
+        .primary(value.span.clone(), "");
+        self.diagnostics.push(d);
+    }
+
+    fn unknown_key(&mut self, key: &Spanned<String>, expected: &[&str], parent: &str) {
+        let mut d = Diagnostic::warning(
+            1,
+            "config",
+            format!("unknown key '{}' in {}", key.value, parent),
+        )
+        .primary(key.span.clone(), "this key is ignored");
+        if let Some(suggestion) =
+            find_best_match_for_name(expected.iter().copied(), &key.value, None)
+        {
+            d = d.footer_help(format!("did you mean '{}'?", suggestion));
+        }
+        self.diagnostics.push(d);
+    }
+
+    fn unknown_value(
+        &mut self,
+        value: &Spanned<String>,
+        expected: &[&str],
+        kind: &str,
+        severity: Severity,
+    ) {
+        let mut d = Diagnostic::new_with_code(
+            1,
+            severity,
+            format!("unknown {} '{}'", kind, value.value),
+            Some("config".to_string()),
+        )
+        .primary(value.span.clone(), "");
+        if let Some(suggestion) =
+            find_best_match_for_name(expected.iter().copied(), &value.value, None)
+        {
+            d = d.footer_help(format!("did you mean '{}'?", suggestion));
+        }
+        self.diagnostics.push(d);
+    }
+}
+
+/// The names of the options a rule accepts.
+/// The option names of a rule serialized with its tag.
+fn option_names(tagged: Option<serde_json::Value>) -> Vec<String> {
+    tagged
+        .and_then(|tagged| {
+            let options = tagged.as_object()?.values().next()?.as_object()?;
+            Some(options.keys().cloned().collect())
+        })
+        .unwrap_or_default()
+}
+
+fn from_toml_spanned(spanned: toml::Spanned<Value>) -> Spanned<Value> {
+    Spanned {
+        span: spanned.start()..spanned.end(),
+        value: spanned.into_inner(),
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ValueVisitor;
+
+        impl<'de> Visitor<'de> for ValueVisitor {
+            type Value = Value;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("any config value")
+            }
+
+            fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+                Ok(Value::Boolean(value))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Value, E> {
+                Ok(Value::Integer(value))
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+                Ok(Value::Integer(value as i64))
+            }
+
+            fn visit_f64<E>(self, value: f64) -> Result<Value, E> {
+                Ok(Value::Float(value))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Value, E> {
+                Ok(Value::String(value.to_owned()))
+            }
+
+            fn visit_string<E>(self, value: String) -> Result<Value, E> {
+                Ok(Value::String(value))
+            }
+
+            fn visit_unit<E>(self) -> Result<Value, E> {
+                Ok(Value::Null)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut items = vec![];
+                while let Some(item) = seq.next_element::<toml::Spanned<Value>>()? {
+                    items.push(from_toml_spanned(item));
+                }
+                Ok(Value::Array(items))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut entries = vec![];
+                while let Some(key) = map.next_key::<toml::Spanned<String>>()? {
+                    let value = map.next_value::<toml::Spanned<Value>>()?;
+                    let key = Spanned {
+                        span: key.start()..key.end(),
+                        value: key.into_inner(),
+                    };
+                    entries.push((key, from_toml_spanned(value)));
+                }
+                Ok(Value::Table(entries))
+            }
+        }
+
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+/// A minimal JSON parser which keeps the spans of keys and values.
+struct JsonParser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    fn parse(mut self) -> Option<Spanned<Value>> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        if self.pos == self.src.len() {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(
+            self.peek(),
+            Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r')
+        ) {
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self) -> Option<Spanned<Value>> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let value = match self.peek()? {
+            b'{' => {
+                self.pos += 1;
+                let mut entries = vec![];
+                self.skip_whitespace();
+                if !self.eat(b'}') {
+                    loop {
+                        self.skip_whitespace();
+                        let key_start = self.pos;
+                        let key = Spanned {
+                            value: self.string()?,
+                            span: key_start..self.pos,
+                        };
+                        self.skip_whitespace();
+                        if !self.eat(b':') {
+                            return None;
+                        }
+                        entries.push((key, self.value()?));
+                        self.skip_whitespace();
+                        if self.eat(b'}') {
+                            break;
+                        }
+                        if !self.eat(b',') {
+                            return None;
+                        }
+                    }
+                }
+                Value::Table(entries)
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut items = vec![];
+                self.skip_whitespace();
+                if !self.eat(b']') {
+                    loop {
+                        items.push(self.value()?);
+                        self.skip_whitespace();
+                        if self.eat(b']') {
+                            break;
+                        }
+                        if !self.eat(b',') {
+                            return None;
+                        }
+                    }
+                }
+                Value::Array(items)
+            }
+            b'"' => Value::String(self.string()?),
+            _ => self.literal()?,
+        };
+        Some(Spanned {
+            span: start..self.pos,
+            value,
+        })
+    }
+
+    fn literal(&mut self) -> Option<Value> {
+        let rest = &self.src[self.pos..];
+        for (text, value) in &[
+            ("true", Value::Boolean(true)),
+            ("false", Value::Boolean(false)),
+            ("null", Value::Null),
+        ] {
+            if rest.starts_with(text) {
+                self.pos += text.len();
+                return Some(value.clone());
+            }
+        }
+
+        let len = rest
+            .find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+            .unwrap_or(rest.len());
+        let number = &rest[..len];
+        self.pos += len;
+        if let Ok(int) = number.parse::<i64>() {
+            Some(Value::Integer(int))
+        } else {
+            number.parse::<f64>().ok().map(Value::Float)
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        if !self.eat(b'"') {
+            return None;
+        }
+        let mut string = String::new();
+        let mut chars = self.src[self.pos..].char_indices();
+
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += idx + 1;
+                    return Some(string);
+                }
+                '\\' => {
+                    let escaped = match chars.next()?.1 {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'u' => {
+                            let hex = (0..4)
+                                .map(|_| chars.next().map(|(_, c)| c))
+                                .collect::<Option<String>>()?;
+                            let code = u32::from_str_radix(&hex, 16).ok()?;
+                            std::char::from_u32(code).unwrap_or(std::char::REPLACEMENT_CHARACTER)
+                        }
+                        c => c,
+                    };
+                    string.push(escaped);
+                }
+                c => string.push(c),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_errors::file::SimpleFile;
+
+    fn diagnostics(src: &str, style: ConfigStyle) -> Vec<Diagnostic> {
+        check(&parse(src, &style).expect("the config should parse"))
+    }
+
+    /// Assert the title, severity, and the text of the primary span of a diagnostic.
+    #[track_caller]
+    fn assert_diagnostic(
+        d: &Diagnostic,
+        src: &str,
+        severity: Severity,
+        title: &str,
+        spanned: &str,
+    ) {
+        assert_eq!(d.title, title);
+        assert_eq!(d.severity, severity);
+        let range = d.primary.as_ref().unwrap().span.range.clone();
+        assert_eq!(&src[range], spanned);
+    }
+
+    fn footers(d: &Diagnostic) -> Vec<&str> {
+        d.footers.iter().map(|footer| footer.msg.as_str()).collect()
+    }
+
+    #[test]
+    fn unknown_rules() {
+        let src = "[rules.errors]\nno-emty = {}\nno-empty = {}\n";
+        let d = diagnostics(src, ConfigStyle::Toml);
+        assert_eq!(d.len(), 1);
+        assert_diagnostic(
+            &d[0],
+            src,
+            Severity::Error,
+            "unknown rule 'no-emty'",
+            "no-emty",
+        );
+        assert_eq!(footers(&d[0]), ["did you mean 'no-empty'?"]);
+
+        let src = r#"{ "rules": { "project": { "no-empty": {} } } }"#;
+        let d = diagnostics(src, ConfigStyle::Json);
+        assert_eq!(d.len(), 1);
+        assert_diagnostic(
+            &d[0],
+            src,
+            Severity::Error,
+            "unknown project rule 'no-empty'",
+            "\"no-empty\"",
+        );
+        assert_eq!(
+            footers(&d[0]),
+            ["rules which check single files are configured in errors or warnings"]
+        );
+    }
+
+    #[test]
+    fn unknown_option_keys() {
+        let src = "[rules.errors]\nno-empty = { disallowEmptyFunction = true }\n";
+        let d = diagnostics(src, ConfigStyle::Toml);
+        assert_eq!(d.len(), 1);
+        assert_diagnostic(
+            &d[0],
+            src,
+            Severity::Warning,
+            "unknown key 'disallowEmptyFunction' in the options of 'no-empty'",
+            "disallowEmptyFunction",
+        );
+        assert_eq!(footers(&d[0]), ["did you mean 'disallowEmptyFunctions'?"]);
+
+        let src = "[rules]\nerror = {}\n";
+        let d = diagnostics(src, ConfigStyle::Toml);
+        assert_eq!(d.len(), 1);
+        assert_diagnostic(
+            &d[0],
+            src,
+            Severity::Warning,
+            "unknown key 'error' in rules",
+            "error",
+        );
+        assert_eq!(footers(&d[0]), ["did you mean 'errors'?"]);
+    }
+
+    #[test]
+    fn wrong_value_types() {
+        let src = "[rules.errors]\nno-empty = { disallowEmptyFunctions = \"yes\" }\n";
+        let d = diagnostics(src, ConfigStyle::Toml);
+        assert_eq!(d.len(), 1);
+        assert_eq!(d[0].severity, Severity::Error);
+        assert!(
+            d[0].title
+                .starts_with("invalid value for 'disallowEmptyFunctions': invalid type: string"),
+            "{}",
+            d[0].title
+        );
+        assert_eq!(
+            &src[d[0].primary.as_ref().unwrap().span.range.clone()],
+            "\"yes\""
+        );
+
+        let src = r#"{ "rules": { "errors": [] }, "parser": { "decorators": 1 } }"#;
+        let d = diagnostics(src, ConfigStyle::Json);
+        assert_eq!(d.len(), 2);
+        assert_diagnostic(
+            &d[0],
+            src,
+            Severity::Error,
+            "expected a table, but found an array",
+            "[]",
+        );
+        assert_diagnostic(
+            &d[1],
+            src,
+            Severity::Error,
+            "expected a boolean, but found an integer",
+            "1",
+        );
+    }
+
+    #[test]
+    fn unknown_groups_and_levels() {
+        let src = "[rules]\ngroups = [\"erors\"]\nlevels = { no-empty = \"warm\" }\n";
+        let d = diagnostics(src, ConfigStyle::Toml);
+        assert_eq!(d.len(), 2);
+        assert_diagnostic(
+            &d[0],
+            src,
+            Severity::Warning,
+            "unknown rule group 'erors'",
+            "\"erors\"",
+        );
+        assert_eq!(footers(&d[0]), ["did you mean 'errors'?"]);
+        assert_diagnostic(
+            &d[1],
+            src,
+            Severity::Error,
+            "unknown rule level 'warm'",
+            "\"warm\"",
+        );
+        assert_eq!(footers(&d[1]), ["did you mean 'warn'?"]);
+    }
+
+    #[test]
+    fn rules_in_both_errors_and_warnings() {
+        let src = r#"{ "rules": { "errors": { "no-empty": {} }, "warnings": { "noEmpty": {} } } }"#;
+        let d = diagnostics(src, ConfigStyle::Json);
+        assert_eq!(d.len(), 1);
+        assert_diagnostic(
+            &d[0],
+            src,
+            Severity::Warning,
+            "'no-empty' is configured in both errors and warnings",
+            "\"noEmpty\"",
+        );
+        assert_eq!(
+            d[0].primary.as_ref().unwrap().msg,
+            "the configuration in warnings is ignored"
+        );
+        let secondary = &d[0].children[0];
+        assert_eq!(&src[secondary.span.range.clone()], "\"no-empty\"");
+    }
+
+    #[test]
+    fn malformed_json() {
+        for src in &[
+            "{ \"rules\": { } ",
+            "{ \"rules\" { } }",
+            "{ \"rules\": {}, }",
+            "{ \"rules\": {} } {}",
+            "{ \"rules: {} }",
+        ] {
+            assert_eq!(parse(src, &ConfigStyle::Json), None, "{}", src);
+        }
+
+        let src = "{\n  \"rules\": {\n    \"errors\": { \"no-empty\" {} }\n  }\n}\n";
+        let file = SimpleFile::new("rslintrc.json".into(), src.into());
+        let d = crate::deserialize(src, &ConfigStyle::Json, &file).unwrap_err();
+        let range = d.primary.unwrap().span.range;
+        assert_eq!(&src[range.start..], "{} }\n  }\n}\n");
+    }
+
+    #[test]
+    fn json_spans_and_escapes() {
+        let src = r#"{ "a\"b": [1, -2.5e1, "A\n", null, true] }"#;
+        let root = parse(src, &ConfigStyle::Json).unwrap();
+        assert_eq!(root.span, 0..src.len());
+        let entries = match root.value {
+            Value::Table(entries) => entries,
+            _ => panic!("expected a table"),
+        };
+        let (key, value) = &entries[0];
+        assert_eq!(key.value, "a\"b");
+        assert_eq!(&src[key.span.clone()], r#""a\"b""#);
+        let items = match &value.value {
+            Value::Array(items) => items,
+            _ => panic!("expected an array"),
+        };
+        let values = items
+            .iter()
+            .map(|item| item.value.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [
+                Value::Integer(1),
+                Value::Float(-25.0),
+                Value::String("A\n".to_string()),
+                Value::Null,
+                Value::Boolean(true),
+            ]
+        );
+        let spans = items
+            .iter()
+            .map(|item| &src[item.span.clone()])
+            .collect::<Vec<_>>();
+        assert_eq!(spans, ["1", "-2.5e1", r#""A\n""#, "null", "true"]);
+    }
+
+    #[test]
+    fn package_json_is_checked_under_the_rslint_key() {
+        let src = r#"{ "name": "app", "rules": 5, "rslint": { "rules": { "errors": { "no-emty": {} } } } }"#;
+        let d = diagnostics(src, ConfigStyle::PackageJson);
+        assert_eq!(d.len(), 1);
+        assert_diagnostic(
+            &d[0],
+            src,
+            Severity::Error,
+            "unknown rule 'no-emty'",
+            "\"no-emty\"",
+        );
+
+        assert_eq!(
+            parse(r#"{ "name": "app" }"#, &ConfigStyle::PackageJson),
+            None
+        );
+        assert_eq!(parse("[]", &ConfigStyle::PackageJson), None);
+    }
+}
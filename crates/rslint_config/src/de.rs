@@ -1,7 +1,11 @@
 //! Deserialization of rules objects.
 
-use heck::{CamelCase, KebabCase};
-use rslint_core::{get_rule_by_name, get_rule_suggestion, CstRule};
+use crate::normalize_rule_name;
+use heck::CamelCase;
+use rslint_core::{
+    get_plugin_rule, get_project_rule_by_name, get_project_rule_suggestion, get_rule_by_name,
+    get_rule_suggestion, CstRule, ProjectRule,
+};
 use serde::{
     de::{
         value::MapAccessDeserializer, DeserializeSeed, Error, IntoDeserializer, MapAccess, Visitor,
@@ -14,12 +18,65 @@ pub(crate) fn from_rule_objects<'de, D>(deserializer: D) -> Result<Vec<Box<dyn C
 where
     D: Deserializer<'de>,
 {
-    struct TypetagObjects<T> {
-        _type: PhantomData<T>,
+    rule_objects(
+        deserializer,
+        |name| {
+            get_rule_by_name(name)
+                .map(|_| ())
+                .ok_or_else(|| get_rule_suggestion(name).map(str::to_string))
+        },
+        |name, options| {
+            get_plugin_rule(name)
+                .map(|rule| Box::new(rule.with_options(options)) as Box<dyn CstRule>)
+        },
+    )
+}
+
+pub(crate) fn from_project_rule_objects<'de, D>(
+    deserializer: D,
+) -> Result<Vec<Box<dyn ProjectRule>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    rule_objects(
+        deserializer,
+        |name| {
+            get_project_rule_by_name(name)
+                .map(|_| ())
+                .ok_or_else(|| get_project_rule_suggestion(name).map(str::to_string))
+        },
+        |_, _| None,
+    )
+}
+
+type PluginRuleFn<T> = fn(&str, serde_json::Map<String, serde_json::Value>) -> Option<Box<T>>;
+
+/// Deserialize rule-to-config pairs into rule trait objects.
+///
+/// `lookup` returns `Err` with an optional suggestion if a kebab-case rule name is unknown.
+/// Rules of plugins (named `plugin/rule`) are not known to typetag, `plugin_rule` makes them from their options instead.
+fn rule_objects<'de, D, T>(
+    deserializer: D,
+    lookup: fn(&str) -> Result<(), Option<String>>,
+    plugin_rule: PluginRuleFn<T>,
+) -> Result<Vec<Box<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ?Sized,
+    Box<T>: Deserialize<'de>,
+{
+    struct TypetagObjects<T: ?Sized> {
+        lookup: fn(&str) -> Result<(), Option<String>>,
+        plugin_rule: PluginRuleFn<T>,
+        _type: PhantomData<Box<T>>,
     }
 
-    impl<'de> Visitor<'de> for TypetagObjects<Box<dyn CstRule>> {
-        type Value = Vec<Box<dyn CstRule>>;
+    impl<'de, T> Visitor<'de> for TypetagObjects<T>
+    where
+        T: ?Sized,
+        Box<T>: Deserialize<'de>,
+    {
+        type Value = Vec<Box<T>>;
 
         fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
             formatter.write_str("zero or more rule-to-config pairs")
@@ -31,21 +88,31 @@ where
         {
             let mut vec = Vec::new();
             while let Some(key) = map.next_key::<String>()? {
+                let name = normalize_rule_name(&key);
+                if name.contains('/') && (self.lookup)(&name).is_ok() {
+                    let options = map.next_value()?;
+                    match (self.plugin_rule)(&name, options) {
+                        Some(rule) => vec.push(rule),
+                        None => return Err(M::Error::custom(format!("Unknown rule '{}'", key))),
+                    }
+                    continue;
+                }
+
                 let de = MapAccessDeserializer::new(Entry {
                     key: Some(key.to_camel_case().into_deserializer()),
                     value: &mut map,
                 });
-                if get_rule_by_name(&key.to_kebab_case()).is_none() {
-                    if let Some(suggestion) = get_rule_suggestion(&key.to_kebab_case()) {
+                match (self.lookup)(&name) {
+                    Err(Some(suggestion)) => {
                         return Err(M::Error::custom(format!(
                             "Unknown rule '{}'. did you mean '{}'?",
                             key, suggestion
                         )));
-                    } else {
+                    }
+                    Err(None) => {
                         return Err(M::Error::custom(format!("Unknown rule '{}'", key)));
                     }
-                } else {
-                    vec.push(Box::<dyn CstRule>::deserialize(de)?);
+                    Ok(()) => vec.push(Box::<T>::deserialize(de)?),
                 }
             }
             Ok(vec)
@@ -79,5 +146,9 @@ where
         }
     }
 
-    deserializer.deserialize_map(TypetagObjects { _type: PhantomData })
+    deserializer.deserialize_map(TypetagObjects {
+        lookup,
+        plugin_rule,
+        _type: PhantomData,
+    })
 }
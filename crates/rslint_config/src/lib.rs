@@ -3,20 +3,33 @@
 // FIXME: Workaround for https://github.com/GREsau/schemars/pull/65
 #![allow(clippy::field_reassign_with_default)]
 
+mod check;
 mod de;
+mod parser;
+mod plugins;
+
+pub use parser::{ParserConfig, ParserOptions};
+
 use dirs_next::config_dir;
-use rslint_core::{get_group_rules_by_name, CstRule, CstRuleStore, Diagnostic, RuleLevel};
+use heck::KebabCase;
+use rslint_core::{
+    get_group_rules_by_name, get_project_rule_by_name, get_rule_by_name, CstRule, CstRuleStore,
+    CustomRule, Diagnostic, ProjectRule, ProjectRuleStore, Rule, RuleLevel, Severity,
+};
 use rslint_errors::file::{Files, SimpleFile};
 use serde::{Deserialize, Serialize};
 use std::{
     cell::RefCell,
+    collections::HashMap,
     env,
     fs::read_to_string,
     path::{Path, PathBuf},
 };
 
 /// The name of the config files to search for.
-pub const CONFIG_NAMES: [&str; 2] = ["rslintrc.json", "rslintrc.toml"];
+///
+/// `package.json` is only used if it has an `rslint` key.
+pub const CONFIG_NAMES: [&str; 3] = ["rslintrc.json", "rslintrc.toml", "package.json"];
 
 /// A list of boxed rule implementations.
 pub type RuleList = Vec<Box<dyn CstRule>>;
@@ -27,6 +40,18 @@ pub struct ConfigRepr {
     rules: Option<RulesConfigRepr>,
     #[serde(default)]
     errors: ErrorsConfigRepr,
+    #[serde(default)]
+    parser: ParserConfig,
+    /// WebAssembly plugins by name, the paths are relative to the config.
+    #[serde(default)]
+    plugins: HashMap<String, String>,
+    /// Rules which report code matching a pattern, or nodes of a kind.
+    #[serde(default)]
+    #[cfg_attr(
+        feature = "schema",
+        schemars(with = "Vec<rslint_core::CustomRuleConfig>")
+    )]
+    custom_rules: Vec<CustomRule>,
 }
 
 impl Default for ConfigRepr {
@@ -34,10 +59,19 @@ impl Default for ConfigRepr {
         Self {
             rules: None,
             errors: Default::default(),
+            parser: Default::default(),
+            plugins: Default::default(),
+            custom_rules: Default::default(),
         }
     }
 }
 
+/// The parts of a `package.json` relevant to the config.
+#[derive(Debug, Deserialize)]
+struct PackageJsonRepr {
+    rslint: ConfigRepr,
+}
+
 #[serde(default)]
 #[derive(Debug, Deserialize, Serialize, Default)]
 struct RulesConfigRepr {
@@ -47,8 +81,12 @@ struct RulesConfigRepr {
     #[serde(deserialize_with = "de::from_rule_objects")]
     warnings: RuleList,
 
+    #[serde(deserialize_with = "de::from_project_rule_objects")]
+    project: Vec<Box<dyn ProjectRule>>,
+
     groups: Vec<String>,
     allowed: Vec<String>,
+    levels: HashMap<String, RuleLevel>,
 }
 
 #[cfg(feature = "schema")]
@@ -130,11 +168,53 @@ impl schemars::JsonSchema for RulesConfigRepr {
             ..Default::default()
         });
 
+        let level_schema = Schema::Object(SchemaObject {
+            enum_values: Some(
+                ["off", "hint", "info", "warn", "error"]
+                    .iter()
+                    .map(|level| serde_json::Value::from(*level))
+                    .collect(),
+            ),
+            ..Default::default()
+        });
+        let project_rules = ProjectRuleStore::new().builtins().rules;
+        let mut project_obj_items = Map::new();
+        for rule in &project_rules {
+            if let Some(schema) = rule.schema() {
+                project_obj_items.insert(rule.name().to_string(), Schema::Object(schema.schema));
+            }
+        }
+        let project_schema = Schema::Object(SchemaObject {
+            object: Some(Box::new(ObjectValidation {
+                properties: project_obj_items,
+                ..Default::default()
+            })),
+            ..Default::default()
+        });
+
+        let mut level_items = Map::new();
+        for name in rules
+            .iter()
+            .map(|rule| rule.name())
+            .chain(project_rules.iter().map(|rule| rule.name()))
+        {
+            level_items.insert(name.to_string(), level_schema.clone());
+        }
+        let levels_schema = Schema::Object(SchemaObject {
+            object: Some(Box::new(ObjectValidation {
+                properties: level_items,
+                ..Default::default()
+            })),
+            ..Default::default()
+        });
+
         let mut map = Map::new();
         map.insert("groups".to_string(), groups_schema);
         map.insert("allowed".to_string(), rule_items_schema);
         map.insert("errors".to_string(), rules_schema.clone());
         map.insert("warnings".to_string(), rules_schema);
+        map.insert("project".to_string(), project_schema);
+        map.insert("levels".to_string(), levels_schema);
 
         Schema::Object(SchemaObject {
             object: Some(Box::new(ObjectValidation {
@@ -173,6 +253,7 @@ pub struct Config {
 enum ConfigStyle {
     Toml,
     Json,
+    PackageJson,
 }
 
 impl Config {
@@ -180,79 +261,132 @@ impl Config {
     /// dir and all of it ancestors, and if `no_global_config` is `false`,
     /// look in the systems config directory.
     ///
+    /// The config is checked before it is used, every problem found is emitted
+    /// and the default config is used if any of them is an error.
+    ///
     /// # Returns
     ///
-    /// The config or an `Err` if the toml inside the config is invalid.
+    /// The config or the default config if the config is invalid.
     /// The `Diagnostic` can be emitted by using the `SimpleFile` as a file database.
     pub fn new(no_global_config: bool, emit_diagnostic: fn(SimpleFile, Diagnostic)) -> Self {
-        let path = Self::find_config(no_global_config);
-        let (source, (path, style)) = match path
-            .as_ref()
-            .and_then(|(path, _)| read_to_string(path).ok())
-        {
-            Some(source) => (source, path.unwrap()),
+        match env::current_dir() {
+            Ok(dir) => Self::new_in(&dir, no_global_config, emit_diagnostic),
+            Err(_) => Default::default(),
+        }
+    }
+
+    /// Creates a new config like [`Config::new`], but searches `dir` and its ancestors
+    /// instead of the current dir.
+    pub fn new_in(
+        dir: &Path,
+        no_global_config: bool,
+        emit_diagnostic: fn(SimpleFile, Diagnostic),
+    ) -> Self {
+        let (path, style, source) = match Self::load_in(dir, no_global_config) {
+            Some(loaded) => loaded,
             None => return Default::default(),
         };
+        let config_file = || SimpleFile::new(path.to_string_lossy().into(), source.clone());
+
+        if let Some(tree) = check::parse(&source, &style) {
+            let mut diagnostics = plugins::load(&tree, plugin_dir(&path));
+            diagnostics.extend(check::check(&tree));
+            let invalid = diagnostics.iter().any(|d| d.severity == Severity::Error);
+            for d in diagnostics {
+                emit_diagnostic(config_file(), d);
+            }
+            if invalid {
+                return Default::default();
+            }
+        }
+
+        match deserialize(&source, &style, &config_file()) {
+            Ok(repr) => Self::from_repr(repr, &path),
+            Err(d) => {
+                emit_diagnostic(config_file(), *d);
+                Default::default()
+            }
+        }
+    }
+
+    /// Search for a config like [`Config::new`] and check it, without using it.
+    ///
+    /// # Returns
+    ///
+    /// `None` if there is no config, otherwise the config file and every problem found in it.
+    pub fn check(no_global_config: bool) -> Option<(SimpleFile, Vec<Diagnostic>)> {
+        let (path, style, source) = Self::load(no_global_config)?;
+        let config_file = SimpleFile::new(path.to_string_lossy().into(), source.clone());
+
+        let mut diagnostics = check::parse(&source, &style)
+            .map(|tree| {
+                let mut diagnostics = plugins::load(&tree, plugin_dir(&path));
+                diagnostics.extend(check::check(&tree));
+                diagnostics
+            })
+            .unwrap_or_default();
+        if !diagnostics.iter().any(|d| d.severity == Severity::Error) {
+            if let Err(d) = deserialize(&source, &style, &config_file) {
+                diagnostics.push(*d);
+            }
+        }
+        Some((config_file, diagnostics))
+    }
+
+    /// Search for a config like [`Config::new`] and only load the plugins declared in it,
+    /// so their rules are known to commands which do not lint files.
+    pub fn load_plugins(no_global_config: bool, emit_diagnostic: fn(SimpleFile, Diagnostic)) {
+        let (path, style, source) = match Self::load(no_global_config) {
+            Some(loaded) => loaded,
+            None => return,
+        };
+        if let Some(tree) = check::parse(&source, &style) {
+            for d in plugins::load(&tree, plugin_dir(&path)) {
+                emit_diagnostic(
+                    SimpleFile::new(path.to_string_lossy().into(), source.clone()),
+                    d,
+                );
+            }
+        }
+    }
 
-        match style {
-            ConfigStyle::Json => match serde_json::from_str::<ConfigRepr>(&source) {
-                Ok(repr) => Self {
-                    repr,
-                    warnings: Default::default(),
-                },
-                Err(err) => {
-                    let config_file = SimpleFile::new(path.to_string_lossy().into(), source);
-                    let (line, col) = (err.line() - 1, err.column() - 1);
-                    let idx = config_file
-                        .line_range(0, line)
-                        .expect("serde_json yielded an invalid line range")
-                        .start
-                        + col;
-
-                    let diag =
-                        Diagnostic::error(1, "config", err.to_string()).primary(idx..idx, "");
-                    emit_diagnostic(config_file, diag);
-                    Default::default()
-                }
-            },
-            ConfigStyle::Toml => match toml::from_str::<ConfigRepr>(&source) {
-                Ok(repr) => Self {
-                    repr,
-                    warnings: Default::default(),
-                },
-
-                Err(err) => {
-                    let config_file = SimpleFile::new(path.to_string_lossy().into(), source);
-                    let d = if let Some(idx) = err
-                        .line_col()
-                        .and_then(|(line, col)| Some(config_file.line_range(0, line)?.start + col))
-                    {
-                        let pos_regex = regex::Regex::new(" at line \\d+ column \\d+$").unwrap();
-                        let msg = err.to_string();
-                        let msg = pos_regex.replace(&msg, "");
-                        Diagnostic::error(1, "config", msg).primary(idx..idx, "")
-                    } else {
-                        Diagnostic::error(1, "config", err.to_string())
-                    };
-                    emit_diagnostic(config_file, d);
-                    Default::default()
-                }
-            },
+    /// Find the config from the current dir and read it.
+    fn load(no_global_config: bool) -> Option<(PathBuf, ConfigStyle, String)> {
+        Self::load_in(&env::current_dir().ok()?, no_global_config)
+    }
+
+    /// Find the config from a dir and read it.
+    fn load_in(dir: &Path, no_global_config: bool) -> Option<(PathBuf, ConfigStyle, String)> {
+        let (path, style) = Self::find_config(dir, no_global_config)?;
+        let source = read_to_string(&path).ok()?;
+        Some((path, style, source))
+    }
+
+    fn from_repr(mut repr: ConfigRepr, path: &Path) -> Self {
+        repr.parser.root = path.parent().map(|dir| dir.to_owned());
+        Self {
+            repr,
+            warnings: Default::default(),
         }
     }
 
-    fn find_config(global_config: bool) -> Option<(PathBuf, ConfigStyle)> {
-        let path = env::current_dir().ok()?;
+    fn find_config(path: &Path, global_config: bool) -> Option<(PathBuf, ConfigStyle)> {
         fn search_path(path: &Path) -> Option<(PathBuf, ConfigStyle)> {
             for config_name in CONFIG_NAMES.iter() {
                 let new_path = path.join(config_name);
-                let style = if config_name.ends_with("json") {
+                let style = if *config_name == "package.json" {
+                    ConfigStyle::PackageJson
+                } else if config_name.ends_with("json") {
                     ConfigStyle::Json
                 } else {
                     ConfigStyle::Toml
                 };
 
-                if new_path.exists() {
+                if let ConfigStyle::PackageJson = style {
+                    if has_rslint_key(&new_path) {
+                        return Some((new_path, style));
+                    }
+                } else if new_path.exists() {
                     return Some((new_path, style));
                 }
             }
@@ -283,6 +417,11 @@ impl Config {
         self.repr.errors.formatter.clone()
     }
 
+    /// Returns the parser options, used to get the syntax each file is parsed with.
+    pub fn parser(&self) -> &ParserConfig {
+        &self.repr.parser
+    }
+
     pub fn warning_rule_names(&self) -> impl Iterator<Item = &str> {
         self.repr
             .rules
@@ -291,24 +430,61 @@ impl Config {
             .map(|rule| rule.name())
     }
 
+    /// The level explicitly set for a rule in the `levels` table, if any.
+    fn explicit_level(&self, rule_name: &str) -> Option<RuleLevel> {
+        self.repr
+            .rules
+            .iter()
+            .flat_map(|rules| &rules.levels)
+            .find(|(name, _)| normalize_rule_name(name) == rule_name)
+            .map(|(_, level)| *level)
+    }
+
+    /// Get the level a rule should run at, a level in the `levels` table takes precedence over
+    /// the table the rule's options were declared in, or the severity a custom rule was declared with.
     pub fn rule_level_by_name(&self, rule_name: &str) -> RuleLevel {
-        if self.warning_rule_names().any(|name| name == rule_name) {
+        if let Some(level) = self.explicit_level(rule_name) {
+            level
+        } else if let Some(rule) = self.custom_rule(rule_name) {
+            rule.level()
+        } else if self.warning_rule_names().any(|name| name == rule_name) {
             RuleLevel::Warning
         } else {
             RuleLevel::Error
         }
     }
 
+    fn custom_rule(&self, rule_name: &str) -> Option<&CustomRule> {
+        self.repr
+            .custom_rules
+            .iter()
+            .find(|rule| rule.name() == rule_name)
+    }
+
     /// Collects all rules and creates a `CstRuleStore`.
     ///
+    /// Custom rules are always run unless they are explicitly allowed or turned `off`.
     /// This method may add warnings to the warning list of this `Config`.
     pub fn rules_store(&self) -> CstRuleStore {
+        let custom_rules = self
+            .repr
+            .custom_rules
+            .iter()
+            .map(|rule| Box::new(rule.clone()) as Box<dyn CstRule>)
+            .filter(|rule| self.rule_level_by_name(rule.name()) != RuleLevel::Off);
         let rule_cfg = match &self.repr.rules {
             Some(rules) => rules,
-            None => return CstRuleStore::new().recommended(),
+            None => {
+                let mut store = CstRuleStore::new().recommended();
+                store.load_rules(custom_rules);
+                return store;
+            }
         };
 
-        let rules = unique_rules(rule_cfg.errors.clone(), rule_cfg.warnings.clone());
+        let rules: RuleList =
+            unique_rules(rule_cfg.errors.clone(), rule_cfg.warnings.clone()).collect();
+        let level_only = self.level_only_rules(&rules);
+        let rules = unique_rules(rules, level_only);
         let mut rules = self.intersect_allowed(rules).collect::<Vec<_>>();
 
         for group in &rule_cfg.groups {
@@ -316,18 +492,70 @@ impl Config {
                 let list = self.intersect_allowed(group_rules.into_iter());
                 let list = list.collect::<Vec<_>>();
                 rules = unique_rules(rules, list).collect();
-            } else {
-                let d = Diagnostic::warning(1, "config", format!("unknown rule group '{}'", group));
-                self.warnings.borrow_mut().push(d);
             }
         }
+        let custom_rules = self.intersect_allowed(custom_rules).collect();
+        let rules = unique_rules(rules, custom_rules);
 
         let mut store = CstRuleStore::new();
         store.load_rules(rules);
         store
     }
 
-    /// Remove any rules which are explicitly allowed by the `allowed` field.
+    /// Collects all project rules and creates a `ProjectRuleStore`.
+    ///
+    /// Project rules are configured in the `project` table, and like other rules they can
+    /// be given a level in the `levels` table or be explicitly allowed.
+    pub fn project_rules_store(&self) -> ProjectRuleStore {
+        let rule_cfg = match &self.repr.rules {
+            Some(rules) => rules,
+            None => return ProjectRuleStore::new().recommended(),
+        };
+
+        let mut rules = rule_cfg.project.clone();
+        for (name, level) in &rule_cfg.levels {
+            let name = normalize_rule_name(name);
+            if *level == RuleLevel::Off || rules.iter().any(|rule| rule.name() == name) {
+                continue;
+            }
+            rules.extend(get_project_rule_by_name(&name));
+        }
+
+        rules.retain(|rule| {
+            !rule_cfg
+                .allowed
+                .iter()
+                .any(|allowed| allowed == rule.name())
+                && self.explicit_level(rule.name()) != Some(RuleLevel::Off)
+        });
+
+        let mut store = ProjectRuleStore::new();
+        store.load_rules(rules);
+        store
+    }
+
+    /// Rules which are given a level in the `levels` table but have no options in `errors` or `warnings`,
+    /// these are run with their default options.
+    fn level_only_rules(&self, configured: &[Box<dyn CstRule>]) -> RuleList {
+        let levels = match self.repr.rules.as_ref() {
+            Some(rule_cfg) => &rule_cfg.levels,
+            None => return vec![],
+        };
+
+        let mut rules = vec![];
+        for (name, level) in levels {
+            let name = normalize_rule_name(name);
+            if *level == RuleLevel::Off || configured.iter().any(|rule| rule.name() == name) {
+                continue;
+            }
+
+            // unknown rules are reported when the config is checked
+            rules.extend(get_rule_by_name(&name));
+        }
+        rules
+    }
+
+    /// Remove any rules which are explicitly allowed by the `allowed` field or turned `off` by the `levels` field.
     ///
     /// This method may add warnings to the warning list of this `Config`.
     fn intersect_allowed<'s>(
@@ -357,12 +585,302 @@ impl Config {
                 self.warnings.borrow_mut().push(d)
             }
 
-            !res
+            !res && self.explicit_level(rule.name()) != Some(RuleLevel::Off)
         })
     }
 }
 
+/// The kebab-case name of a rule written in the config, rules of plugins are named `plugin/rule`
+/// and each part is converted on its own.
+pub(crate) fn normalize_rule_name(name: &str) -> String {
+    name.split('/')
+        .map(|part| part.to_kebab_case())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The directory plugin paths are relative to.
+fn plugin_dir(path: &Path) -> &Path {
+    path.parent().unwrap_or_else(|| Path::new(""))
+}
+
+/// Whether a `package.json` exists at the path and contains an `rslint` key.
+fn has_rslint_key(path: &Path) -> bool {
+    read_to_string(path)
+        .ok()
+        .and_then(|source| serde_json::from_str::<serde_json::Value>(&source).ok())
+        .map_or(false, |value| value.get("rslint").is_some())
+}
+
+/// Deserialize a config, returning a diagnostic pointing to the location of the error if it is invalid.
+fn deserialize(
+    source: &str,
+    style: &ConfigStyle,
+    config_file: &SimpleFile,
+) -> Result<ConfigRepr, Box<Diagnostic>> {
+    let res = match style {
+        ConfigStyle::Json => serde_json::from_str::<ConfigRepr>(source),
+        ConfigStyle::PackageJson => {
+            serde_json::from_str::<PackageJsonRepr>(source).map(|package| package.rslint)
+        }
+        ConfigStyle::Toml => {
+            return toml::from_str::<ConfigRepr>(source).map_err(|err| {
+                if let Some(idx) = err
+                    .line_col()
+                    .and_then(|(line, col)| Some(config_file.line_range(0, line)?.start + col))
+                {
+                    let pos_regex = regex::Regex::new(" at line \\d+ column \\d+$").unwrap();
+                    let msg = err.to_string();
+                    let msg = pos_regex.replace(&msg, "");
+                    Box::new(Diagnostic::error(1, "config", msg).primary(idx..idx, ""))
+                } else {
+                    Box::new(Diagnostic::error(1, "config", err.to_string()))
+                }
+            })
+        }
+    };
+
+    res.map_err(|err| {
+        let (line, col) = (err.line() - 1, err.column() - 1);
+        let idx = config_file
+            .line_range(0, line)
+            .expect("serde_json yielded an invalid line range")
+            .start
+            + col;
+
+        Box::new(Diagnostic::error(1, "config", err.to_string()).primary(idx..idx, ""))
+    })
+}
+
 fn unique_rules(first: RuleList, mut second: RuleList) -> impl Iterator<Item = Box<dyn CstRule>> {
     second.retain(|rule| !first.iter().any(|prev| prev.name() == rule.name()));
     first.into_iter().chain(second)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A temporary directory with some files in it, which is removed when it is dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(files: &[(&str, &str)]) -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let dir = env::temp_dir().join(format!(
+                "rslint-config-test-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::SeqCst)
+            ));
+            for (path, source) in files {
+                let path = dir.join(path);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(path, source).unwrap();
+            }
+            Self(dir)
+        }
+
+        fn find(&self, dir: &str) -> Option<(PathBuf, ConfigStyle)> {
+            Config::find_config(&self.0.join(dir), false)
+                .map(|(path, style)| (path.strip_prefix(&self.0).unwrap().to_owned(), style))
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn config(src: &str) -> Config {
+        let file = SimpleFile::new("rslintrc.toml".into(), src.into());
+        let repr = deserialize(src, &ConfigStyle::Toml, &file).unwrap();
+        Config::from_repr(repr, Path::new("/project/rslintrc.toml"))
+    }
+
+    fn rule_names(config: &Config) -> Vec<&'static str> {
+        config
+            .rules_store()
+            .rules
+            .iter()
+            .map(|rule| rule.name())
+            .collect()
+    }
+
+    #[test]
+    fn off_removes_configured_rules() {
+        let config = config(
+            r#"
+            [rules.errors]
+            no-empty = {}
+            no-debugger = {}
+
+            [rules.levels]
+            no-empty = "off"
+            "#,
+        );
+        assert_eq!(rule_names(&config), ["no-debugger"]);
+        assert_eq!(config.rule_level_by_name("no-empty"), RuleLevel::Off);
+    }
+
+    #[test]
+    fn level_only_rules_use_default_options() {
+        let config = config(
+            r#"
+            [rules.levels]
+            no-cond-assign = "info"
+            "#,
+        );
+        let store = config.rules_store();
+        assert_eq!(store.rules.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&store.rules[0]).unwrap(),
+            serde_json::to_value(get_rule_by_name("no-cond-assign").unwrap()).unwrap()
+        );
+        assert_eq!(config.rule_level_by_name("no-cond-assign"), RuleLevel::Info);
+    }
+
+    #[test]
+    fn levels_take_precedence_over_tables() {
+        let config = config(
+            r#"
+            [rules.errors]
+            no-empty = {}
+
+            [rules.warnings]
+            no-debugger = {}
+            no-sparse-arrays = {}
+
+            [rules.levels]
+            no-empty = "warn"
+            no-debugger = "error"
+            "#,
+        );
+        assert_eq!(config.rule_level_by_name("no-empty"), RuleLevel::Warning);
+        assert_eq!(config.rule_level_by_name("no-debugger"), RuleLevel::Error);
+        assert_eq!(
+            config.rule_level_by_name("no-sparse-arrays"),
+            RuleLevel::Warning
+        );
+        assert_eq!(config.rule_level_by_name("no-dupe-keys"), RuleLevel::Error);
+    }
+
+    #[test]
+    fn warning_is_an_alias_of_warn() {
+        let config = config(
+            r#"
+            [rules.levels]
+            noEmpty = "warning"
+            "#,
+        );
+        assert_eq!(rule_names(&config), ["no-empty"]);
+        assert_eq!(config.rule_level_by_name("no-empty"), RuleLevel::Warning);
+    }
+
+    #[test]
+    fn level_only_project_rules() {
+        let project_rules = |src: &str| -> Vec<&'static str> {
+            config(src)
+                .project_rules_store()
+                .rules
+                .iter()
+                .map(|rule| rule.name())
+                .collect()
+        };
+        assert_eq!(
+            project_rules(
+                r#"
+                [rules.levels]
+                no-duplicate-module-names = "warn"
+                "#
+            ),
+            ["no-duplicate-module-names"]
+        );
+        assert!(project_rules(
+            r#"
+            [rules.project]
+            no-duplicate-module-names = {}
+
+            [rules.levels]
+            no-duplicate-module-names = "off"
+            "#
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn unknown_rules_in_levels_are_reported() {
+        let src = r#"
+[rules.levels]
+no-emty = "warn"
+"#;
+        let diagnostics = check::check(&check::parse(src, &ConfigStyle::Toml).unwrap());
+        assert_eq!(diagnostics.len(), 1);
+        let d = &diagnostics[0];
+        assert_eq!(d.title, "unknown rule 'no-emty'");
+        assert_eq!(
+            &src[d.primary.as_ref().unwrap().span.range.clone()],
+            "no-emty"
+        );
+        assert_eq!(d.footers[0].msg, "did you mean 'no-empty'?");
+
+        // the config skips the rule instead of failing
+        assert!(rule_names(&config(src)).is_empty());
+    }
+
+    #[test]
+    fn package_json_without_rslint_key_is_skipped() {
+        let dir = TempDir::new(&[
+            ("rslintrc.toml", ""),
+            ("app/package.json", r#"{ "name": "app" }"#),
+            ("app/src/index.js", ""),
+        ]);
+        let (path, style) = dir.find("app/src").unwrap();
+        assert_eq!(path, Path::new("rslintrc.toml"));
+        assert!(matches!(style, ConfigStyle::Toml));
+
+        let dir = TempDir::new(&[("app/package.json", r#"{ "name": "app", "rslint": {} }"#)]);
+        let (path, style) = dir.find("app").unwrap();
+        assert_eq!(path, Path::new("app/package.json"));
+        assert!(matches!(style, ConfigStyle::PackageJson));
+    }
+
+    #[test]
+    fn rslintrc_takes_precedence_over_package_json() {
+        let dir = TempDir::new(&[
+            ("package.json", r#"{ "rslint": { "rules": {} } }"#),
+            ("rslintrc.toml", ""),
+        ]);
+        let (path, style) = dir.find("").unwrap();
+        assert_eq!(path, Path::new("rslintrc.toml"));
+        assert!(matches!(style, ConfigStyle::Toml));
+
+        let dir = TempDir::new(&[
+            ("package.json", r#"{ "rslint": { "rules": {} } }"#),
+            ("rslintrc.json", "{}"),
+        ]);
+        let (path, style) = dir.find("").unwrap();
+        assert_eq!(path, Path::new("rslintrc.json"));
+        assert!(matches!(style, ConfigStyle::Json));
+    }
+
+    #[test]
+    fn invalid_rslint_key_points_to_the_error() {
+        let src = "{\n  \"name\": \"app\",\n  \"rslint\": { \"rules\": 5 }\n}\n";
+        let file = SimpleFile::new("package.json".into(), src.into());
+        let d = deserialize(src, &ConfigStyle::PackageJson, &file).unwrap_err();
+        assert!(
+            d.title.starts_with("invalid type: integer `5`"),
+            "{}",
+            d.title
+        );
+        let span = d.primary.unwrap().span.range;
+        assert_eq!(span.start, span.end);
+        assert_eq!(
+            &src[..span.start],
+            "{\n  \"name\": \"app\",\n  \"rslint\": { \"rules\": "
+        );
+        assert!(src[span.start..].starts_with('5'));
+    }
+}
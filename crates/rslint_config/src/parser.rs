@@ -0,0 +1,211 @@
+//! Configuration of the syntax features accepted by the parser.
+
+use rslint_parser::{FileKind, Syntax};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The `parser` table of the config, which toggles syntax features on top of
+/// the syntax implied by a file's kind.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ParserConfig {
+    #[serde(flatten)]
+    options: ParserOptions,
+    /// Options which only apply to files matching a list of glob patterns.
+    overrides: Vec<ParserOverride>,
+    /// The directory the config was loaded from, override globs are relative to it.
+    #[serde(skip)]
+    pub(crate) root: Option<PathBuf>,
+}
+
+/// Syntax features which can be toggled, features which are not set keep
+/// the default for the file's kind.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ParserOptions {
+    /// Allow decorators on classes and class members.
+    pub decorators: Option<bool>,
+    /// Allow class field declarations.
+    pub class_fields: Option<bool>,
+    /// Allow `return` statements outside of functions.
+    pub global_return: Option<bool>,
+    /// Allow `await` outside of async functions in modules.
+    pub top_level_await: Option<bool>,
+}
+
+impl ParserOptions {
+    /// Apply these options to a syntax, leaving unset features untouched.
+    pub fn apply(&self, mut syntax: Syntax) -> Syntax {
+        if let Some(decorators) = self.decorators {
+            syntax.decorators = decorators;
+        }
+        if let Some(class_fields) = self.class_fields {
+            syntax.class_fields = class_fields;
+        }
+        if let Some(global_return) = self.global_return {
+            syntax.global_return = global_return;
+        }
+        if let Some(top_level_await) = self.top_level_await {
+            syntax.top_level_await = top_level_await;
+        }
+        syntax
+    }
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct ParserOverride {
+    /// Glob patterns of the files these options apply to.
+    files: Vec<String>,
+    #[serde(flatten)]
+    options: ParserOptions,
+}
+
+impl ParserConfig {
+    /// Get the syntax a file should be parsed with.
+    ///
+    /// The top level options are applied first, then the options of every override
+    /// whose patterns match `path`, in the order they were declared.
+    pub fn syntax(&self, path: Option<&Path>, kind: FileKind) -> Syntax {
+        let mut syntax = self.options.apply(Syntax::new(kind));
+        let path = match path {
+            Some(path) => self.relative_path(path),
+            None => return syntax,
+        };
+
+        for over in &self.overrides {
+            let matches = over
+                .files
+                .iter()
+                .any(|pat| glob::Pattern::new(pat).map_or(false, |pat| pat.matches_path(&path)));
+            if matches {
+                syntax = over.options.apply(syntax);
+            }
+        }
+        syntax
+    }
+
+    fn relative_path(&self, path: &Path) -> PathBuf {
+        let absolute = if path.is_relative() {
+            std::env::current_dir()
+                .map(|dir| dir.join(path))
+                .unwrap_or_else(|_| path.to_owned())
+        } else {
+            path.to_owned()
+        };
+
+        self.root
+            .as_ref()
+            .and_then(|root| absolute.strip_prefix(root).ok())
+            .map(|path| path.to_owned())
+            .unwrap_or_else(|| path.components().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{check, ConfigStyle};
+
+    fn config(src: &str) -> ParserConfig {
+        let mut config = toml::from_str::<ParserConfig>(src).unwrap();
+        config.root = Some(PathBuf::from("/project"));
+        config
+    }
+
+    #[test]
+    fn top_level_options() {
+        let config = config("decorators = true\nclassFields = false");
+        let syntax = config.syntax(None, FileKind::Module);
+        assert_eq!(
+            syntax,
+            Syntax {
+                decorators: true,
+                ..Syntax::new(FileKind::Module)
+            }
+        );
+
+        // options which are not set keep the default of the file's kind
+        let syntax = config.syntax(None, FileKind::TypeScript);
+        assert_eq!(
+            syntax,
+            Syntax {
+                class_fields: false,
+                ..Syntax::new(FileKind::TypeScript)
+            }
+        );
+    }
+
+    #[test]
+    fn overrides_are_relative_to_the_root() {
+        let config = config(
+            r#"
+            [[overrides]]
+            files = ["src/legacy/**"]
+            globalReturn = true
+            "#,
+        );
+        let global_return = |path: &str| {
+            config
+                .syntax(Some(Path::new(path)), FileKind::Script)
+                .global_return
+        };
+        assert!(global_return("/project/src/legacy/a.js"));
+        assert!(global_return("/project/src/legacy/nested/b.js"));
+        assert!(!global_return("/project/src/a.js"));
+        assert!(!global_return("/other/src/legacy/a.js"));
+        assert!(!config.syntax(None, FileKind::Script).global_return);
+    }
+
+    #[test]
+    fn later_overrides_win() {
+        let config = config(
+            r#"
+            decorators = true
+
+            [[overrides]]
+            files = ["src/**"]
+            decorators = false
+            topLevelAwait = true
+
+            [[overrides]]
+            files = ["src/decorated/**"]
+            decorators = true
+            "#,
+        );
+        let syntax = |path: &str| config.syntax(Some(Path::new(path)), FileKind::Module);
+        assert!(syntax("/project/lib/a.js").decorators);
+        assert!(!syntax("/project/src/a.js").decorators);
+        assert!(syntax("/project/src/decorated/a.js").decorators);
+        // options the later override does not set are kept from the earlier one
+        assert!(syntax("/project/src/decorated/a.js").top_level_await);
+    }
+
+    #[test]
+    fn invalid_globs_are_reported_and_ignored() {
+        let src = r#"
+[[parser.overrides]]
+files = ["src/[", "lib/**"]
+decorators = true
+"#;
+        let diagnostics = check::check(&check::parse(src, &ConfigStyle::Toml).unwrap());
+        assert_eq!(diagnostics.len(), 1);
+        let d = &diagnostics[0];
+        assert_eq!(d.severity, rslint_core::Severity::Warning);
+        assert!(d.title.starts_with("invalid glob pattern: "), "{}", d.title);
+        let span = d.primary.as_ref().unwrap().span.range.clone();
+        assert_eq!(&src[span], "\"src/[\"");
+
+        let config = config(&src.replace("parser.", ""));
+        let decorators = |path: &str| {
+            config
+                .syntax(Some(Path::new(path)), FileKind::Module)
+                .decorators
+        };
+        assert!(!decorators("/project/src/[a.js"));
+        assert!(decorators("/project/lib/a.js"));
+    }
+}
@@ -0,0 +1,54 @@
+//! Loading of the WebAssembly plugins declared in the `plugins` table of a config.
+//!
+//! Plugins are loaded from the spanned config tree before the config is checked or deserialized,
+//! so rules of plugins can be used anywhere built in rules can.
+
+use crate::check::{Spanned, Value};
+use rslint_core::{register_plugin, Diagnostic, Plugin};
+use std::path::Path;
+
+/// Load and register every plugin of a config, paths are relative to the directory of the config.
+///
+/// Invalid names and values which are not strings are skipped, they are reported when the config is checked.
+pub(crate) fn load(root: &Spanned<Value>, dir: &Path) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for (name, path) in plugins(root) {
+        let path_str = match &path.value {
+            Value::String(path) if !name.value.is_empty() && !name.value.contains('/') => path,
+            _ => continue,
+        };
+
+        match Plugin::load(&name.value, &dir.join(path_str)) {
+            Ok(plugin) => {
+                register_plugin(plugin);
+            }
+            Err(err) => {
+                let d = Diagnostic::error(
+                    1,
+                    "config",
+                    format!("failed to load the plugin '{}'", name.value),
+                )
+                .primary(path.span.clone(), err.to_string());
+                diagnostics.push(d);
+            }
+        }
+    }
+    diagnostics
+}
+
+fn plugins(root: &Spanned<Value>) -> &[(Spanned<String>, Spanned<Value>)] {
+    let entries = match &root.value {
+        Value::Table(entries) => entries,
+        _ => return &[],
+    };
+    match entries.iter().find(|(key, _)| key.value == "plugins") {
+        Some((
+            _,
+            Spanned {
+                value: Value::Table(plugins),
+                ..
+            },
+        )) => plugins,
+        _ => &[],
+    }
+}
@@ -1,7 +1,10 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
-use rslint_core::{CstRuleStore, File};
+use rslint_core::{
+    run_rule_with_semantic, semantic::LazySemanticModel, CstRuleStore, File, LintJob,
+};
 use rslint_lexer::Lexer;
 use rslint_parser::parse_text;
+use std::sync::Arc;
 
 const ENGINE_262_URL: &str = "https://engine262.js.org/engine262/engine262.js";
 
@@ -13,8 +16,45 @@ fn tokenize(source: &str) {
     Lexer::from_str(source, 0).for_each(drop);
 }
 
-fn lint(file: &File) {
-    let _ = rslint_core::lint_file(file, &CstRuleStore::new().builtins(), false);
+fn lint(file: &File, store: &CstRuleStore) {
+    let _ = rslint_core::lint_file(file, store, false);
+}
+
+/// Lint a file by walking it once for every rule, which is how files were linted before
+/// every rule was run in a single traversal.
+fn lint_per_rule(file: &File, store: &CstRuleStore) {
+    let root = file.parse();
+    let src: Arc<str> = Arc::from(file.source.as_str());
+    let semantic = LazySemanticModel::new(root.clone());
+    for rule in &store.rules {
+        let _ = run_rule_with_semantic(
+            &**rule,
+            file.id,
+            root.clone(),
+            false,
+            &[],
+            src.clone(),
+            semantic.clone(),
+        );
+    }
+}
+
+/// Lint a file by running batches of its rules on different threads.
+fn lint_parallel(file: &File, store: &CstRuleStore) {
+    let job = LintJob::new(file, store, false);
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let results = std::thread::scope(|scope| {
+        let handles = job
+            .batches(threads)
+            .into_iter()
+            .map(|batch| scope.spawn(|| job.run(batch)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    let _ = job.finish(results);
 }
 
 fn bench_source(c: &mut Criterion, file: &File) {
@@ -23,7 +63,14 @@ fn bench_source(c: &mut Criterion, file: &File) {
     group.throughput(Throughput::Bytes(file.source.len() as u64));
     group.bench_function("tokenize", |b| b.iter(|| tokenize(black_box(&file.source))));
     group.bench_function("parse", |b| b.iter(|| parse(black_box(&file.source))));
-    group.bench_function("lint", |b| b.iter(|| lint(black_box(file))));
+    let store = CstRuleStore::new().builtins();
+    group.bench_function("lint", |b| b.iter(|| lint(black_box(file), &store)));
+    group.bench_function("lint parallel", |b| {
+        b.iter(|| lint_parallel(black_box(file), &store))
+    });
+    group.bench_function("lint per rule", |b| {
+        b.iter(|| lint_per_rule(black_box(file), &store))
+    });
     group.finish();
 }
 
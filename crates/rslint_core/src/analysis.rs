@@ -0,0 +1,131 @@
+//! A cache of data derived from a file which is shared by every rule run on the file.
+//!
+//! Rules often need the same derived data, such as the parsed pattern of a regex or the value of a constant
+//! expression. Each kind of data is an [`Analysis`], its values are computed the first time a rule asks for
+//! them through [`RuleCtx::analysis`](crate::RuleCtx::analysis) and reused by every other rule.
+//! The cache belongs to a single linting of a single file, it is dropped with the file's rule contexts.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+/// A kind of data derived from a file, such as parsed regexes, which is cached by an [`AnalysisCache`].
+///
+/// The analysis is only a marker type, its values are stored by key, usually the node they are derived from.
+pub trait Analysis: 'static {
+    type Key: Hash + Eq + Send + 'static;
+    type Value: Clone + Send + 'static;
+}
+
+/// The values of every analysis of a file, clones share the same values.
+///
+/// The cache can be used from many threads at once, a value which two threads ask for at the same time
+/// may be computed twice but only the first one is kept.
+#[derive(Clone, Default)]
+pub struct AnalysisCache {
+    analyses: Arc<Mutex<HashMap<TypeId, Box<dyn Any + Send>>>>,
+}
+
+impl AnalysisCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the value of a key of an analysis, computing it with `compute` if it is not in the cache.
+    pub fn get_or_insert_with<A: Analysis>(
+        &self,
+        key: A::Key,
+        compute: impl FnOnce() -> A::Value,
+    ) -> A::Value {
+        if let Some(value) = self.get::<A>(&key) {
+            return value;
+        }
+        // the value is computed without holding the lock so computing it can use the cache
+        let value = compute();
+        let mut analyses = self.analyses.lock().unwrap();
+        Self::values::<A>(&mut analyses)
+            .entry(key)
+            .or_insert(value)
+            .clone()
+    }
+
+    /// Get the value of a key of an analysis if it was already computed.
+    pub fn get<A: Analysis>(&self, key: &A::Key) -> Option<A::Value> {
+        let mut analyses = self.analyses.lock().unwrap();
+        Self::values::<A>(&mut analyses).get(key).cloned()
+    }
+
+    fn values<A: Analysis>(
+        analyses: &mut HashMap<TypeId, Box<dyn Any + Send>>,
+    ) -> &mut HashMap<A::Key, A::Value> {
+        analyses
+            .entry(TypeId::of::<A>())
+            .or_insert_with(|| Box::<HashMap<A::Key, A::Value>>::default())
+            .downcast_mut()
+            .expect("the values of an analysis are always stored under its type id")
+    }
+}
+
+impl fmt::Debug for AnalysisCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let analyses = self.analyses.lock().unwrap();
+        f.debug_struct("AnalysisCache")
+            .field("analyses", &analyses.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::regex::NoInvalidRegexp;
+    use crate::{lint_file, CstRule, CstRuleStore, File};
+    use rslint_parser::FileKind;
+
+    struct Lengths;
+
+    impl Analysis for Lengths {
+        type Key = &'static str;
+        type Value = usize;
+    }
+
+    struct Doubled;
+
+    impl Analysis for Doubled {
+        type Key = &'static str;
+        type Value = usize;
+    }
+
+    #[test]
+    fn values_are_computed_once_per_analysis() {
+        let cache = AnalysisCache::new();
+        let shared = cache.clone();
+        assert_eq!(cache.get_or_insert_with::<Lengths>("abc", || 3), 3);
+        assert_eq!(
+            shared.get_or_insert_with::<Lengths>("abc", || unreachable!()),
+            3
+        );
+        assert_eq!(cache.get::<Doubled>(&"abc"), None);
+        assert_eq!(cache.get_or_insert_with::<Doubled>("abc", || 6), 6);
+        assert_eq!(cache.get::<Lengths>(&"abc"), Some(3));
+    }
+
+    #[test]
+    fn files_do_not_share_analyses() {
+        let mut store = CstRuleStore::new();
+        store.load_rules(vec![Box::<NoInvalidRegexp>::default() as Box<dyn CstRule>]);
+        // both regexes are at the same range of their file
+        let invalid = File::from_string("/a(/", FileKind::Script, "invalid.js");
+        let valid = File::from_string("/ab/", FileKind::Script, "valid.js");
+
+        let count = |file: &File| {
+            lint_file(file, &store, false).rule_results["no-invalid-regexp"]
+                .diagnostics
+                .len()
+        };
+        assert_eq!(count(&invalid), 1);
+        assert_eq!(count(&valid), 0);
+    }
+}
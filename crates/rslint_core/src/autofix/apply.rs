@@ -1,82 +1,375 @@
-use crate::{lint_file_inner, File, LintResult, RuleResult};
+use crate::{lint_file_inner, Diagnostic, File, LintResult, RuleResult, Severity};
+use rslint_errors::Fix;
 use rslint_parser::*;
 use rslint_text_edit::{apply_indels, Indel};
 use std::collections::HashMap;
 
 pub const MAX_FIX_ITERATIONS: usize = 10;
 
-#[derive(Debug, Clone)]
-struct TaggedIndel {
-    tag: &'static str,
-    indel: Indel,
+/// Merge fixes into one list of sorted indels, skipping every fix which overlaps a fix before it.
+///
+/// Fixes are skipped as a whole so a fix is never partially applied, skipped fixes will hopefully
+/// be applied in the next iteration of fixing a file.
+pub fn merge_fixes<'a>(fixes: impl IntoIterator<Item = &'a Fix>) -> Vec<Indel> {
+    let fixes = fixes.into_iter().map(|fix| ("", fix)).collect();
+    indels_of(&select_fixes(fixes))
 }
 
-fn get_runnable_indels(mut tagged: Vec<TaggedIndel>) -> Vec<TaggedIndel> {
-    tagged.sort_by_key(|TaggedIndel { indel, .. }| (indel.delete.start(), indel.delete.end()));
+/// Select the fixes which do not overlap a fix before them, with the rule each fix is from.
+fn select_fixes<'a>(mut fixes: Vec<(&'static str, &'a Fix)>) -> Vec<(&'static str, &'a Fix)> {
+    fixes.retain(|(_, fix)| !fix.indels.is_empty());
+    fixes.sort_by_key(|(_, fix)| {
+        fix.indels
+            .iter()
+            .map(|indel| (indel.delete.start(), indel.delete.end()))
+            .min()
+    });
 
-    // We need to throw out any overlapping indels, but we can't just throw out individual indels, we
-    // must throw out the entire fixer's indels or else we risk getting partial fixes which dont work well.
-    // any fixer indels thrown out will hopefully be applied in the next recursive lint run, if not then ¯\_(ツ)_/¯
-    let mut excluded_ids = vec![];
-    tagged.iter().zip(tagged.iter().skip(1)).for_each(|(l, r)| {
-        if l.indel.delete.end() > r.indel.delete.start() {
-            excluded_ids.push(r.tag);
+    let mut selected: Vec<(&'static str, &'a Fix)> = vec![];
+    for (rule, fix) in fixes {
+        let overlaps = fix.indels.iter().any(|new| {
+            selected.iter().flat_map(|(_, fix)| &fix.indels).any(|old| {
+                old.delete.start() < new.delete.end() && new.delete.start() < old.delete.end()
+            })
+        });
+        if !overlaps {
+            selected.push((rule, fix));
         }
-    });
+    }
+    selected
+}
+
+fn indels_of(fixes: &[(&'static str, &Fix)]) -> Vec<Indel> {
+    let mut indels = fixes
+        .iter()
+        .flat_map(|(_, fix)| fix.indels.iter().cloned())
+        .collect::<Vec<_>>();
+    indels.sort_by_key(|indel| (indel.delete.start(), indel.delete.end()));
+    indels
+}
+
+/// Why applying fixes was rejected.
+enum Rejection {
+    /// The fixed code has a syntax error which the code did not have before.
+    SyntaxError(Box<Diagnostic>),
+    /// The tokens outside of the edited ranges changed, this is only checked in debug builds.
+    TokensChanged,
+}
+
+/// Apply indels to code and check that the fixed code does not have more syntax errors than `errors`,
+/// and in debug builds that the tokens outside of the edited ranges did not change.
+fn verify(
+    text: &str,
+    indels: &[Indel],
+    file: &File,
+    errors: usize,
+) -> Result<(String, Parse<()>), Rejection> {
+    let mut fixed = text.to_string();
+    apply_indels(indels, &mut fixed);
+    let res = parse_with_syntax(&fixed, file.id, file.syntax);
+
+    let new_errors = res
+        .errors()
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .collect::<Vec<_>>();
+    if new_errors.len() > errors {
+        return Err(Rejection::SyntaxError(Box::new(new_errors[0].clone())));
+    }
+    if cfg!(debug_assertions) && !tokens_unchanged(text, &fixed, indels, file.id) {
+        return Err(Rejection::TokensChanged);
+    }
+    Ok((fixed, res))
+}
+
+/// Whether the tokens which are not touched by any indel are the same before and after applying the indels.
+///
+/// Whitespace and comments are ignored because deleting code between two of them merges them.
+fn tokens_unchanged(old: &str, new: &str, indels: &[Indel], file_id: usize) -> bool {
+    let old_edits = indels.iter().map(|indel| indel.delete).collect::<Vec<_>>();
+    let mut new_edits = vec![];
+    let mut delta = 0i64;
+    for indel in indels {
+        let start = (i64::from(u32::from(indel.delete.start())) + delta) as u32;
+        new_edits.push(TextRange::at(start.into(), TextSize::of(&*indel.insert)));
+        delta += i64::from(u32::from(TextSize::of(&*indel.insert)))
+            - i64::from(u32::from(indel.delete.len()));
+    }
+
+    untouched_tokens(old, &old_edits, file_id) == untouched_tokens(new, &new_edits, file_id)
+}
 
-    tagged
-        .into_iter()
-        .filter(|TaggedIndel { tag, .. }| !excluded_ids.contains(&tag))
-        .collect()
+fn untouched_tokens<'a>(
+    text: &'a str,
+    edits: &[TextRange],
+    file_id: usize,
+) -> Vec<(SyntaxKind, &'a str)> {
+    let mut offset = TextSize::from(0);
+    let mut tokens = vec![];
+    for token in tokenize(text, file_id).0 {
+        let range = TextRange::at(offset, TextSize::from(token.len as u32));
+        offset = range.end();
+        let touched = edits
+            .iter()
+            .any(|edit| edit.start() < range.end() && range.start() < edit.end());
+        if !touched && !token.kind.is_trivia() {
+            tokens.push((token.kind, &text[range]));
+        }
+    }
+    tokens
 }
 
-pub fn recursively_apply_fixes(result: &mut LintResult, file: &File) -> String {
-    let script = result.parsed.kind() == SyntaxKind::SCRIPT;
+/// Apply the fixes of a result and relint the fixed code until there is nothing left to fix,
+/// returning the fixed code.
+///
+/// Only [`Applicability::Always`](rslint_errors::Applicability::Always) fixes are applied unless
+/// `unsafe_fixes` is true, in which case fixes which may be incorrect are applied too.
+///
+/// Every round of fixes is verified before it is applied. If a rule's fixes introduce a syntax error,
+/// none of the rule's fixes are applied and the rule is reported with a [`Severity::Bug`] diagnostic.
+/// If the fixes of rules only break the code together, the fixes of the rules which work together are
+/// applied and the fixes of the other rules are made again on the fixed code.
+pub fn recursively_apply_fixes(result: &mut LintResult, file: &File, unsafe_fixes: bool) -> String {
     let mut parsed = result.parsed.clone();
-    let file_id = result.file_id;
     let mut cur_results = result.rule_results.clone();
+    let mut rejected = HashMap::new();
 
     for _ in 0..=MAX_FIX_ITERATIONS {
-        let indels = get_runnable_indels(rule_results_to_tagged_indels(&cur_results));
-
-        if indels.is_empty() {
-            break;
-        }
-        let mut string = parsed.text().to_string();
-        apply_indels(
-            &indels.iter().map(|x| x.indel.clone()).collect::<Vec<_>>(),
-            &mut string,
+        let text = parsed.text().to_string();
+        let errors = result
+            .parser_diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count();
+        let mut fixes = select_fixes(
+            cur_results
+                .iter()
+                .filter(|(rule, _)| !rejected.contains_key(*rule))
+                .flat_map(|(rule, res)| res.diagnostics.iter().map(move |d| (*rule, d)))
+                .filter_map(|(rule, d)| Some((rule, d.fix.as_ref()?)))
+                .filter(|(_, fix)| fix.is_applied(unsafe_fixes))
+                .collect(),
         );
-        parsed = if script {
-            let res = parse_text(&string, file_id);
-            // this needs to be updated for when fixes are applied "dirty" (when there are parser errors)
-            result.parser_diagnostics = res.errors().to_owned();
-            res.syntax()
-        } else {
-            let res = parse_module(&string, file_id);
-            result.parser_diagnostics = res.errors().to_owned();
-            res.syntax()
+
+        let verified = loop {
+            if fixes.is_empty() {
+                break None;
+            }
+            if let Ok(verified) = verify(&text, &indels_of(&fixes), file, errors) {
+                break Some(verified);
+            }
+
+            // find the rules which break the code on their own and roll back their fixes
+            let mut rules = fixes.iter().map(|(rule, _)| *rule).collect::<Vec<_>>();
+            rules.sort_unstable();
+            rules.dedup();
+            let own = |rule: &str| {
+                fixes
+                    .iter()
+                    .filter(|(other, _)| *other == rule)
+                    .copied()
+                    .collect::<Vec<_>>()
+            };
+            let mut found = false;
+            for rule in &rules {
+                if let Err(rejection) = verify(&text, &indels_of(&own(rule)), file, errors) {
+                    rejected.insert(*rule, rejection);
+                    found = true;
+                }
+            }
+
+            // the fixes of every rule work on their own but break the code together, so the fixes of the
+            // rules which work together are applied and the others are made again on the fixed code
+            if !found {
+                let mut subset = vec![];
+                let mut verified = None;
+                for rule in &rules {
+                    let mut candidate = subset.clone();
+                    candidate.extend(own(rule));
+                    if let Ok(res) = verify(&text, &indels_of(&candidate), file, errors) {
+                        subset = candidate;
+                        verified = Some(res);
+                    }
+                }
+                break verified;
+            }
+            fixes.retain(|(rule, _)| !rejected.contains_key(rule));
+        };
+
+        let (fixed, res) = match verified {
+            Some(verified) => verified,
+            None => break,
         };
+        debug_assert_eq!(fixed, res.syntax().text().to_string());
+        result.parser_diagnostics = res.errors().to_owned();
+        parsed = res.syntax();
 
-        // TODO: should we panic on Err? autofix causing the linter to fail should always be incorrect
         let res = lint_file_inner(parsed.clone(), vec![], file, result.store, result.verbose);
         cur_results = res.rule_results;
     }
+
+    for (rule, rejection) in rejected {
+        let reason = match &rejection {
+            Rejection::SyntaxError(_) => "they introduce a syntax error",
+            Rejection::TokensChanged => "they change code outside of the ranges they edit",
+        };
+        let mut d = Diagnostic::new_with_code(
+            result.file_id,
+            Severity::Bug,
+            format!(
+                "the fixes of `{}` were not applied because {}",
+                rule, reason
+            ),
+            Some(rule.to_string()),
+        )
+        .primary(0usize..0, "while fixing this file");
+        if let Rejection::SyntaxError(err) = rejection {
+            d = d.footer_note(format!("the syntax error is: {}", err.title));
+        }
+        d = d.footer_help("this is a bug in the rule, please report it");
+        cur_results
+            .entry(rule)
+            .or_insert_with(|| RuleResult::new(vec![]))
+            .diagnostics
+            .push(d);
+    }
+
     result.rule_results = cur_results;
+    result.parsed = parsed.clone();
     parsed.text().to_string()
 }
 
-fn rule_results_to_tagged_indels(results: &HashMap<&'static str, RuleResult>) -> Vec<TaggedIndel> {
-    results
-        .iter()
-        .filter_map(|(tag, res)| Some((tag, res.fixer.clone()?)))
-        .flat_map(|(tag, fixer)| {
-            fixer
-                .indels
-                .into_iter()
-                .map(|indel| TaggedIndel { tag, indel })
-                .collect::<Vec<_>>()
-                .into_iter()
-        })
-        .collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::errors::NoExtraSemi;
+    use crate::{lint_file, CstRule, CstRuleStore, CustomRule, CustomRuleConfig};
+
+    /// Fix a TypeScript file and check that the fixed code is still parsed as TypeScript.
+    fn check(src: &str, expected: &str) {
+        let store = CstRuleStore::new().builtins();
+        let file = File::from_string(src, FileKind::TypeScript, "test.ts");
+        let mut result = lint_file(&file, &store, false);
+        assert!(result.parser_diagnostics.is_empty());
+
+        let fixed = recursively_apply_fixes(&mut result, &file, false);
+        assert_eq!(fixed, expected);
+        assert!(
+            result.parser_diagnostics.is_empty(),
+            "{:#?}",
+            result.parser_diagnostics
+        );
+        assert_eq!(result.parsed.to_string(), expected);
+    }
+
+    #[test]
+    fn type_annotations() {
+        check(
+            "let a: number = 5;;\nfunction foo(b: string): void {return;};\n",
+            "let a: number = 5;\nfunction foo(b: string): void { return; }\n",
+        );
+    }
+
+    #[test]
+    fn generics() {
+        check(
+            "function id<T>(a: T): T {return a;}\nconst b = id<Array<string>>([]);;\nclass Box<T extends object> {}\n",
+            "function id<T>(a: T): T { return a; }\nconst b = id<Array<string>>([]);\nclass Box<T extends object> {}\n",
+        );
+    }
+
+    #[test]
+    fn interfaces() {
+        check(
+            "interface Foo {\n  a: number;\n  b?: Bar<string>;\n};\ntype Baz = Foo | {c: 1};\nif (x) {y();};\n",
+            "interface Foo {\n  a: number;\n  b?: Bar<string>;\n}\ntype Baz = Foo | {c: 1};\nif (x) { y(); }\n",
+        );
+    }
+
+    /// A rule which replaces every node of a kind with broken code.
+    fn bad_fix(kind: &str, rewrite: &str) -> Box<dyn CstRule> {
+        rewrite_rule("bad-fix", kind, rewrite)
+    }
+
+    /// A rule which replaces every node of a kind.
+    fn rewrite_rule(name: &str, kind: &str, rewrite: &str) -> Box<dyn CstRule> {
+        Box::new(
+            CustomRule::new(CustomRuleConfig {
+                name: name.to_string(),
+                message: "bad fix".to_string(),
+                severity: None,
+                pattern: None,
+                kind: Some(kind.to_string()),
+                rewrite: Some(rewrite.to_string()),
+            })
+            .unwrap(),
+        )
+    }
+
+    /// Fix a module with a rule which makes bad fixes, returning the fixed code and the bugs reported for the rule.
+    fn check_rejected(src: &str, rule: Box<dyn CstRule>) -> (String, Vec<Diagnostic>) {
+        let mut store = CstRuleStore::new();
+        store.load_rules(vec![rule, Box::new(NoExtraSemi::new()) as Box<dyn CstRule>]);
+        let file = File::from_string(src, FileKind::Module, "test.js");
+        let mut result = lint_file(&file, &store, false);
+
+        let fixed = recursively_apply_fixes(&mut result, &file, false);
+        let bugs = result.rule_results["bad-fix"]
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Bug)
+            .cloned()
+            .collect();
+        (fixed, bugs)
+    }
+
+    #[test]
+    fn rejects_syntax_errors() {
+        let (fixed, bugs) = check_rejected("debugger;;\n", bad_fix("DEBUGGER_STMT", "let ="));
+        // the fixes of other rules are still applied
+        assert_eq!(fixed, "debugger;\n");
+        assert_eq!(bugs.len(), 1);
+        assert_eq!(
+            bugs[0].title,
+            "the fixes of `bad-fix` were not applied because they introduce a syntax error"
+        );
+    }
+
+    #[test]
+    fn rejects_changed_tokens() {
+        // `x=-1` becomes `x==1`, which merges the `=` before the edit with the inserted `=`
+        let (fixed, bugs) = check_rejected("x=-1;;\n", bad_fix("UNARY_EXPR", "=1"));
+        assert_eq!(fixed, "x=-1;\n");
+        assert_eq!(bugs.len(), 1);
+        assert!(bugs[0]
+            .title
+            .ends_with("they change code outside of the ranges they edit"));
+    }
+
+    #[test]
+    fn fixes_which_only_break_together() {
+        // a `with` statement is fine in a script, but not once the script is in strict mode
+        let mut store = CstRuleStore::new();
+        store.load_rules(vec![
+            rewrite_rule("a-strict", "LITERAL", "\"use strict\""),
+            rewrite_rule("b-with", "NAME_REF", "with (a) {}"),
+        ]);
+        let file = File::from_string("\"a\";\nfoo\n", FileKind::Script, "test.js");
+        let mut result = lint_file(&file, &store, false);
+
+        let fixed = recursively_apply_fixes(&mut result, &file, false);
+        assert_eq!(fixed, "\"use strict\";\nfoo\n");
+        assert!(result.parser_diagnostics.is_empty());
+        // the fix of `b-with` breaks the fixed code on its own, so it is reported
+        let bugs = result.rule_results["b-with"]
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Bug)
+            .collect::<Vec<_>>();
+        assert_eq!(bugs.len(), 1);
+        assert_eq!(
+            bugs[0].footers[0].msg,
+            "the syntax error is: `with` statements are not allowed in strict mode"
+        );
+    }
 }
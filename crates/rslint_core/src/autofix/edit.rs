@@ -0,0 +1,245 @@
+//! Edits of syntax nodes, which are used with the node constructors of [`make`](rslint_parser::ast::make).
+//!
+//! The edits only touch the trimmed range of nodes, so the whitespace and comments around them are kept.
+
+use super::Fixer;
+use rslint_parser::ast::{
+    make::{needs_parens, ExprPosition},
+    Expr,
+};
+use rslint_parser::{AstNode, SyntaxElement, SyntaxNode, SyntaxNodeExt, TextRange, T};
+use std::borrow::Borrow;
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+// the next or previous sibling token which is not trivia
+fn sibling_token(node: &SyntaxNode, next: bool) -> Option<SyntaxElement> {
+    let mut elem = SyntaxElement::Node(node.clone());
+    loop {
+        elem = if next {
+            elem.next_sibling_or_token()?
+        } else {
+            elem.prev_sibling_or_token()?
+        };
+        if !elem.kind().is_trivia() {
+            return Some(elem).filter(|elem| elem.as_token().is_some());
+        }
+    }
+}
+
+impl Fixer {
+    /// Replace a node with another node, the new node is put in the trimmed range of the old one.
+    pub fn replace_node(&mut self, old: impl Borrow<SyntaxNode>, new: impl AstNode) -> &mut Self {
+        self.replace_range(old.borrow().trimmed_range(), new.text())
+    }
+
+    /// Replace an expression with another expression, which is wrapped in parentheses if its precedence
+    /// is too low for the place of the old expression.
+    pub fn replace_expr(&mut self, old: &Expr, new: Expr) -> &mut Self {
+        let text = if needs_parens(&new, ExprPosition::of(old.syntax())) {
+            format!("({})", new.text())
+        } else {
+            new.text()
+        };
+        self.replace_range(old.syntax().trimmed_range(), text)
+    }
+
+    // replace a range, adding spaces so the new code is not joined with the code around it, such as `typeof(a)`
+    // being replaced with `typeofb`
+    fn replace_range(&mut self, range: TextRange, mut text: String) -> &mut Self {
+        let before = self.src[..usize::from(range.start())].chars().next_back();
+        let after = self.src[usize::from(range.end())..].chars().next();
+        if before.map_or(false, is_ident_char) && text.starts_with(is_ident_char) {
+            text.insert(0, ' ');
+        }
+        if after.map_or(false, is_ident_char) && text.ends_with(is_ident_char) {
+            text.push(' ');
+        }
+        self.replace(range, text)
+    }
+
+    /// Remove a node with the comma or semicolon after it, or with the comma before it if it is the last
+    /// element of a list. The whole lines of the node are removed if nothing else is on them.
+    pub fn remove_node(&mut self, node: impl Borrow<SyntaxNode>) -> &mut Self {
+        let node = node.borrow();
+        let range = node.trimmed_range();
+        let (mut start, mut end) = (usize::from(range.start()), usize::from(range.end()));
+
+        // the separator is removed apart from the node if comments are between them so the comments are kept
+        let next = sibling_token(node, true);
+        let separator = match next.as_ref().map(|elem| elem.kind()) {
+            Some(T![,]) | Some(T![;]) => next,
+            _ => sibling_token(node, false).filter(|elem| elem.kind() == T![,]),
+        };
+        let mut separator = separator
+            .map(|elem| elem.text_range())
+            .map(|range| (usize::from(range.start()), usize::from(range.end())));
+        if let Some((sep_start, sep_end)) = separator {
+            let between = if sep_start < start {
+                &self.src[sep_end..start]
+            } else {
+                &self.src[end..sep_start]
+            };
+            if between.trim().is_empty() {
+                start = start.min(sep_start);
+                end = end.max(sep_end);
+                separator = None;
+            }
+        }
+        let trailing = start == usize::from(range.start()) || separator.is_some();
+
+        let is_blank = |s: &str| s.chars().all(|c| c == ' ' || c == '\t');
+        let line_start = self.src[..start].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = self.src[end..]
+            .find('\n')
+            .map_or(self.src.len(), |idx| end + idx + 1);
+        if is_blank(&self.src[line_start..start]) && is_blank(self.src[end..line_end].trim_end()) {
+            start = line_start;
+            end = line_end;
+        } else if trailing {
+            // `a, b, c` becomes `a, c` and not `a,  c`
+            end += self.src[end..]
+                .chars()
+                .take_while(|c| *c == ' ' || *c == '\t')
+                .count();
+        }
+        match separator {
+            Some((sep_start, sep_end)) if sep_start < start => {
+                self.delete(sep_start..sep_end).delete(start..end)
+            }
+            Some((sep_start, sep_end)) => self.delete(start..end).delete(sep_start..sep_end),
+            None => self.delete(start..end),
+        }
+    }
+
+    /// Insert a node in a list such as the arguments of a call, the elements of an array or the properties
+    /// of an object. The node is inserted before the element at `index`, or at the end if there is no such
+    /// element. Lists with every element on its own line stay that way, and a trailing comma is kept.
+    pub fn insert_into_list(
+        &mut self,
+        list: impl Borrow<SyntaxNode>,
+        index: usize,
+        item: impl AstNode,
+    ) -> &mut Self {
+        let list = list.borrow();
+        let text = match item.syntax().try_to::<Expr>() {
+            Some(expr) if needs_parens(&expr, ExprPosition::Element) => {
+                format!("({})", expr.text())
+            }
+            _ => item.text(),
+        };
+        let elements = list.children().collect::<Vec<_>>();
+        let open = list
+            .children_with_tokens()
+            .filter_map(|elem| elem.into_token())
+            .find(|token| matches!(token.kind(), T!['('] | T!['['] | T!['{']));
+
+        let first = match elements.first() {
+            Some(first) => first,
+            None => {
+                let offset = open.map_or(list.trimmed_range().start(), |token| {
+                    token.text_range().end()
+                });
+                return self.insert(usize::from(offset), text);
+            }
+        };
+
+        let first_start = usize::from(first.trimmed_range().start());
+        let before_first = open.map_or(first_start, |token| usize::from(token.text_range().end()));
+        let separator = if self.src[before_first..first_start].contains('\n') {
+            let line_start = self.src[..first_start].rfind('\n').unwrap() + 1;
+            format!(",\n{}", &self.src[line_start..first_start])
+        } else {
+            ", ".to_string()
+        };
+
+        match elements.get(index) {
+            Some(elem) => self.insert_before(elem.trimmed_range(), text + &separator),
+            None => self.insert_after(elements.last().unwrap().trimmed_range(), separator + &text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_parser::ast::{
+        make, BinOp, CallExpr, Declarator, ExprStmt, GroupingExpr, UnaryExpr,
+    };
+    use rslint_parser::parse_text;
+
+    /// Apply an edit of the first node of a kind in some code.
+    fn edit<N: AstNode>(src: &str, f: impl FnOnce(&mut Fixer, N)) -> String {
+        let root = parse_text(src, 0).syntax();
+        let node = root
+            .descendants()
+            .find_map(|node| node.try_to::<N>())
+            .unwrap();
+        let mut fixer = Fixer::new(src.into());
+        f(&mut fixer, node);
+        fixer.apply()
+    }
+
+    #[test]
+    fn replace_expr_adds_parens() {
+        let sum = || make::bin_expr(make::ident("b"), BinOp::Plus, make::ident("c"));
+        let replace = |src: &str| {
+            edit(src, |fixer, unary: UnaryExpr| {
+                fixer.replace_expr(&Expr::UnaryExpr(unary), sum());
+            })
+        };
+        assert_eq!(replace("a * /* x */ !b;"), "a * /* x */ (b + c);");
+        assert_eq!(replace("let a = !b;"), "let a = b + c;");
+        assert_eq!(
+            edit("typeof(a);", |fixer, grouping: GroupingExpr| {
+                fixer.replace_expr(&Expr::GroupingExpr(grouping), make::ident("b"));
+            }),
+            "typeof b;"
+        );
+    }
+
+    #[test]
+    fn remove_node_with_separators() {
+        let remove_first = |src: &str| {
+            edit(src, |fixer, decl: Declarator| {
+                fixer.remove_node(decl.syntax());
+            })
+        };
+        assert_eq!(remove_first("let a = 1, b = 2;"), "let b = 2;");
+        assert_eq!(remove_first("let a = 1 /* a */, b;"), "let /* a */ b;");
+        assert_eq!(
+            edit("f(a, b);", |fixer, call: CallExpr| {
+                fixer.remove_node(call.arguments().unwrap().args().last().unwrap().syntax());
+            }),
+            "f(a);"
+        );
+        assert_eq!(
+            edit("f(\n  a,\n  b,\n);", |fixer, call: CallExpr| {
+                fixer.remove_node(call.arguments().unwrap().args().next().unwrap().syntax());
+            }),
+            "f(\n  b,\n);"
+        );
+        assert_eq!(
+            edit("a();\n  b();\nc();", |fixer, stmt: ExprStmt| {
+                fixer.remove_node(stmt.syntax().next_sibling().unwrap());
+            }),
+            "a();\nc();"
+        );
+    }
+
+    #[test]
+    fn insert_into_list_keeps_layout() {
+        let insert = |src: &str, index: usize| {
+            edit(src, |fixer, call: CallExpr| {
+                let seq = make::sequence_expr(vec![make::ident("x"), make::ident("y")]);
+                fixer.insert_into_list(call.arguments().unwrap().syntax(), index, seq);
+            })
+        };
+        assert_eq!(insert("f();", 0), "f((x, y));");
+        assert_eq!(insert("f(a, b);", 1), "f(a, (x, y), b);");
+        assert_eq!(insert("f(a, b);", 2), "f(a, b, (x, y));");
+        assert_eq!(insert("f(\n  a,\n);", 1), "f(\n  a,\n  (x, y),\n);");
+    }
+}
@@ -1,8 +1,10 @@
 //! Automatic rule fixing utilities
 
 mod apply;
+mod edit;
 
 use crate::{Span, SyntaxKind};
+use rslint_errors::Applicability;
 use rslint_lexer::{Lexer, Token};
 use rslint_parser::{ast, AstNode, SyntaxNode, SyntaxNodeExt};
 use rslint_text_edit::apply_indels;
@@ -10,13 +12,15 @@ use rslint_text_edit::Indel;
 use std::borrow::Borrow;
 use std::sync::Arc;
 
-pub use apply::{recursively_apply_fixes, MAX_FIX_ITERATIONS};
+pub use apply::{merge_fixes, recursively_apply_fixes, MAX_FIX_ITERATIONS};
 
 /// A simple interface for applying changes to source code
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Fixer {
     pub indels: Vec<Indel>,
     pub src: Arc<str>,
+    /// Whether the fix is safe to apply, fixes are [`Applicability::Always`] by default.
+    pub applicability: Applicability,
 }
 
 impl Fixer {
@@ -24,9 +28,17 @@ impl Fixer {
         Self {
             indels: vec![],
             src,
+            applicability: Applicability::Always,
         }
     }
 
+    /// Set whether the fix is safe to apply, fixes which may change the behavior of the code
+    /// should be [`Applicability::MaybeIncorrect`] so they are only applied when asked for.
+    pub fn applicability(&mut self, applicability: Applicability) -> &mut Self {
+        self.applicability = applicability;
+        self
+    }
+
     /// Apply this fixer to its source code
     pub fn apply(&self) -> String {
         let mut new = (&*self.src).to_string();
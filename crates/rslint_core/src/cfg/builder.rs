@@ -0,0 +1,570 @@
+//! Building of a control flow graph from a syntax tree.
+
+use super::*;
+use crate::util::simple_bool_coerce;
+use rslint_parser::{ast::*, AstNode, SmolStr, SyntaxNodeExt};
+use SyntaxKind::*;
+
+pub(super) fn build(node: &SyntaxNode) -> ControlFlowGraph {
+    let mut builder = Builder {
+        blocks: vec![BasicBlock::default()],
+        current: BlockId(0),
+        node_blocks: HashMap::new(),
+        escaped_finalizers: HashMap::new(),
+        contexts: vec![],
+    };
+
+    match node.kind() {
+        SCRIPT | MODULE | BLOCK_STMT => builder.stmts(node.children()),
+        _ => {
+            // the body of an arrow function may be an expression
+            if let Some(body) = node
+                .children()
+                .filter(|child| child.kind() == BLOCK_STMT || child.is::<Expr>())
+                .last()
+            {
+                builder.stmt(&body);
+            }
+        }
+    }
+
+    let end = builder.new_block();
+    builder.edge(builder.current, end);
+
+    let mut cfg = ControlFlowGraph {
+        reachable: vec![false; builder.blocks.len()],
+        blocks: builder.blocks,
+        end,
+        node_blocks: builder.node_blocks,
+        escaped_finalizers: builder.escaped_finalizers,
+    };
+
+    let mut stack = vec![cfg.entry()];
+    while let Some(id) = stack.pop() {
+        if !cfg.reachable[id.0] {
+            cfg.reachable[id.0] = true;
+            stack.extend(cfg.blocks[id.0].successors.iter().copied());
+        }
+    }
+    cfg
+}
+
+#[derive(Debug, Clone)]
+enum Context {
+    /// A loop, `switch`, or labelled statement which can be the target of a `break`.
+    Breakable {
+        labels: Vec<SmolStr>,
+        /// Whether an unlabelled `break` targets this statement, which is false for labelled blocks.
+        unlabelled: bool,
+        break_target: BlockId,
+        /// The block a `continue` jumps to, only loops have one.
+        continue_target: Option<BlockId>,
+    },
+    /// A `try` block with a `catch` clause.
+    Catch { entry: BlockId },
+    /// A `try` or `catch` block with a `finally` block which must run before jumping out of it.
+    Finally {
+        try_stmt: SyntaxNode,
+        finalizer: SyntaxNode,
+    },
+    /// The `finally` block of a `try` statement.
+    InFinalizer { try_stmt: SyntaxNode },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JumpKind {
+    Break,
+    Continue,
+    Return,
+    Throw,
+}
+
+struct Builder {
+    blocks: Vec<BasicBlock>,
+    current: BlockId,
+    node_blocks: HashMap<SyntaxNode, Vec<BlockId>>,
+    escaped_finalizers: HashMap<SyntaxNode, Vec<SyntaxNode>>,
+    contexts: Vec<Context>,
+}
+
+impl Builder {
+    fn new_block(&mut self) -> BlockId {
+        self.blocks.push(BasicBlock::default());
+        BlockId(self.blocks.len() - 1)
+    }
+
+    fn edge(&mut self, from: BlockId, to: BlockId) {
+        let successors = &mut self.blocks[from.0].successors;
+        if !successors.contains(&to) {
+            successors.push(to);
+        }
+    }
+
+    /// Start a new block which is entered from the current block.
+    fn next_block(&mut self) -> BlockId {
+        let next = self.new_block();
+        self.edge(self.current, next);
+        self.current = next;
+        next
+    }
+
+    /// Record the block a node starts in.
+    fn mark(&mut self, node: &SyntaxNode) {
+        let blocks = self.node_blocks.entry(node.clone()).or_default();
+        if !blocks.contains(&self.current) {
+            blocks.push(self.current);
+        }
+    }
+
+    /// Add a node which is executed to the current block.
+    fn push(&mut self, node: &SyntaxNode) {
+        self.mark(node);
+        self.blocks[self.current.0].nodes.push(node.clone());
+    }
+
+    fn stmts(&mut self, stmts: impl Iterator<Item = SyntaxNode>) {
+        for stmt in stmts {
+            self.stmt(&stmt);
+        }
+    }
+
+    fn stmt(&mut self, node: &SyntaxNode) {
+        match node.kind() {
+            BLOCK_STMT => {
+                self.mark(node);
+                self.stmts(node.children());
+            }
+            IF_STMT => self.if_stmt(node, node.to()),
+            WHILE_STMT => self.while_stmt(node, node.to(), vec![]),
+            DO_WHILE_STMT => self.do_while_stmt(node, node.to(), vec![]),
+            FOR_STMT => self.for_stmt(node, node.to(), vec![]),
+            FOR_IN_STMT | FOR_OF_STMT => self.for_each_stmt(node, vec![]),
+            SWITCH_STMT => self.switch_stmt(node, node.to(), vec![]),
+            TRY_STMT => self.try_stmt(node, node.to()),
+            LABELLED_STMT => self.labelled_stmt(node),
+            WITH_STMT => {
+                let stmt = node.to::<WithStmt>();
+                self.mark(node);
+                if let Some(cond) = stmt.condition() {
+                    self.push(cond.syntax());
+                }
+                if let Some(cons) = stmt.cons() {
+                    self.stmt(cons.syntax());
+                }
+            }
+            RETURN_STMT => {
+                self.push(node);
+                self.jump(node, JumpKind::Return, None);
+            }
+            THROW_STMT => {
+                self.push(node);
+                self.jump(node, JumpKind::Throw, None);
+            }
+            BREAK_STMT => {
+                self.push(node);
+                self.jump(node, JumpKind::Break, label(node));
+            }
+            CONTINUE_STMT => {
+                self.push(node);
+                self.jump(node, JumpKind::Continue, label(node));
+            }
+            _ => self.push(node),
+        }
+    }
+
+    fn if_stmt(&mut self, node: &SyntaxNode, stmt: IfStmt) {
+        self.mark(node);
+        let cond = stmt.condition().and_then(|cond| cond.condition());
+        let constant = cond.clone().and_then(simple_bool_coerce);
+        if let Some(cond) = stmt.condition() {
+            self.push(cond.syntax());
+        }
+        let test = self.current;
+        let after = self.new_block();
+
+        let cons = self.new_block();
+        if constant != Some(false) {
+            self.edge(test, cons);
+        }
+        self.current = cons;
+        if let Some(cons) = stmt.cons() {
+            self.stmt(cons.syntax());
+        }
+        self.edge(self.current, after);
+
+        let alt = self.new_block();
+        if constant != Some(true) {
+            self.edge(test, alt);
+        }
+        self.current = alt;
+        if let Some(alt) = stmt.alt() {
+            self.stmt(alt.syntax());
+        }
+        self.edge(self.current, after);
+        self.current = after;
+    }
+
+    fn while_stmt(&mut self, node: &SyntaxNode, stmt: WhileStmt, labels: Vec<SmolStr>) {
+        let test = self.next_block();
+        self.mark(node);
+        let cond = stmt.condition();
+        if let Some(cond) = cond.as_ref() {
+            self.push(cond.syntax());
+        }
+        let constant = cond
+            .and_then(|cond| cond.condition())
+            .and_then(simple_bool_coerce);
+        self.loop_body(test, test, constant, stmt.cons(), labels);
+    }
+
+    fn do_while_stmt(&mut self, node: &SyntaxNode, stmt: DoWhileStmt, labels: Vec<SmolStr>) {
+        let body = self.next_block();
+        self.mark(node);
+        let test = self.new_block();
+        let after = self.new_block();
+
+        self.contexts.push(Context::Breakable {
+            labels,
+            unlabelled: true,
+            break_target: after,
+            continue_target: Some(test),
+        });
+        if let Some(cons) = stmt.cons() {
+            self.stmt(cons.syntax());
+        }
+        self.contexts.pop();
+
+        self.edge(self.current, test);
+        self.current = test;
+        let cond = stmt.condition();
+        if let Some(cond) = cond.as_ref() {
+            self.push(cond.syntax());
+        }
+        let constant = cond
+            .and_then(|cond| cond.condition())
+            .and_then(simple_bool_coerce);
+        if constant != Some(false) {
+            self.edge(test, body);
+        }
+        if constant != Some(true) {
+            self.edge(test, after);
+        }
+        self.current = after;
+    }
+
+    fn for_stmt(&mut self, node: &SyntaxNode, stmt: ForStmt, labels: Vec<SmolStr>) {
+        self.mark(node);
+        if let Some(init) = stmt.init() {
+            self.push(init.syntax());
+        }
+
+        let test = self.next_block();
+        let constant = match stmt.test() {
+            Some(cond) => {
+                self.push(cond.syntax());
+                cond.expr().and_then(simple_bool_coerce)
+            }
+            // `for (;;)` loops forever
+            None => Some(true),
+        };
+
+        // the update runs after the body and before the test
+        let update = self.new_block();
+        if let Some(expr) = stmt.update() {
+            let body_end = self.current;
+            self.current = update;
+            self.push(expr.syntax());
+            self.current = body_end;
+        }
+        self.edge(update, test);
+
+        self.loop_body(test, update, constant, stmt.cons(), labels);
+    }
+
+    fn for_each_stmt(&mut self, node: &SyntaxNode, labels: Vec<SmolStr>) {
+        self.mark(node);
+        let (left, right, cons) = match node.kind() {
+            FOR_IN_STMT => {
+                let stmt = node.to::<ForInStmt>();
+                (stmt.left(), stmt.right(), stmt.cons())
+            }
+            _ => {
+                let stmt = node.to::<ForOfStmt>();
+                (stmt.left(), stmt.right(), stmt.cons())
+            }
+        };
+        if let Some(right) = right {
+            self.push(right.syntax());
+        }
+
+        let head = self.next_block();
+        if let Some(left) = left {
+            self.push(left.syntax());
+        }
+        self.loop_body(head, head, None, cons, labels);
+    }
+
+    /// Build the body of a loop whose test is the current block.
+    fn loop_body(
+        &mut self,
+        test: BlockId,
+        continue_target: BlockId,
+        constant: Option<bool>,
+        body: Option<Stmt>,
+        labels: Vec<SmolStr>,
+    ) {
+        let after = self.new_block();
+        let entry = self.new_block();
+        if constant != Some(false) {
+            self.edge(test, entry);
+        }
+        if constant != Some(true) {
+            self.edge(test, after);
+        }
+
+        self.contexts.push(Context::Breakable {
+            labels,
+            unlabelled: true,
+            break_target: after,
+            continue_target: Some(continue_target),
+        });
+        self.current = entry;
+        if let Some(body) = body {
+            self.stmt(body.syntax());
+        }
+        self.contexts.pop();
+
+        self.edge(self.current, continue_target);
+        self.current = after;
+    }
+
+    fn switch_stmt(&mut self, node: &SyntaxNode, stmt: SwitchStmt, labels: Vec<SmolStr>) {
+        self.mark(node);
+        if let Some(test) = stmt.test() {
+            self.push(test.syntax());
+        }
+        let cases = stmt.cases().collect::<Vec<_>>();
+        for case in &cases {
+            if let SwitchCase::CaseClause(clause) = case {
+                if let Some(test) = clause.test() {
+                    self.push(test.syntax());
+                }
+            }
+        }
+
+        let dispatch = self.current;
+        let after = self.new_block();
+        if !cases
+            .iter()
+            .any(|case| matches!(case, SwitchCase::DefaultClause(_)))
+        {
+            self.edge(dispatch, after);
+        }
+
+        self.contexts.push(Context::Breakable {
+            labels,
+            unlabelled: true,
+            break_target: after,
+            continue_target: None,
+        });
+        // the end of a case falls through to the next case
+        let mut fallthrough = None;
+        for case in cases {
+            let entry = self.new_block();
+            self.edge(dispatch, entry);
+            if let Some(prev) = fallthrough {
+                self.edge(prev, entry);
+            }
+            self.current = entry;
+            self.mark(case.syntax());
+
+            let cons = match &case {
+                SwitchCase::CaseClause(clause) => clause.cons(),
+                SwitchCase::DefaultClause(clause) => clause.cons(),
+            };
+            self.stmts(cons.map(|stmt| stmt.syntax().clone()));
+            fallthrough = Some(self.current);
+        }
+        self.contexts.pop();
+
+        if let Some(last) = fallthrough {
+            self.edge(last, after);
+        }
+        self.current = after;
+    }
+
+    fn labelled_stmt(&mut self, node: &SyntaxNode) {
+        self.mark(node);
+        // `a: b: while (true) {}` gives the loop two labels
+        let mut labels = vec![];
+        let mut stmt = node.clone();
+        while stmt.kind() == LABELLED_STMT {
+            let labelled = stmt.to::<LabelledStmt>();
+            if let Some(label) = labelled.label() {
+                labels.push(SmolStr::new(label.syntax().trimmed_text().to_string()));
+            }
+            stmt = match labelled.stmt() {
+                Some(inner) => inner.syntax().clone(),
+                None => return,
+            };
+            self.mark(&stmt);
+        }
+
+        match stmt.kind() {
+            FOR_STMT => self.for_stmt(&stmt, stmt.to(), labels),
+            FOR_IN_STMT | FOR_OF_STMT => self.for_each_stmt(&stmt, labels),
+            SWITCH_STMT => self.switch_stmt(&stmt, stmt.to(), labels),
+            WHILE_STMT => self.while_stmt(&stmt, stmt.to(), labels),
+            DO_WHILE_STMT => self.do_while_stmt(&stmt, stmt.to(), labels),
+            _ => {
+                let after = self.new_block();
+                self.contexts.push(Context::Breakable {
+                    labels,
+                    unlabelled: false,
+                    break_target: after,
+                    continue_target: None,
+                });
+                self.stmt(&stmt);
+                self.contexts.pop();
+                self.edge(self.current, after);
+                self.current = after;
+            }
+        }
+    }
+
+    fn try_stmt(&mut self, node: &SyntaxNode, stmt: TryStmt) {
+        self.mark(node);
+        let handler = stmt.handler();
+        let finalizer = stmt.finalizer().and_then(|fin| fin.cons());
+        let after = self.new_block();
+
+        if let Some(finalizer) = finalizer.as_ref() {
+            self.contexts.push(Context::Finally {
+                try_stmt: node.clone(),
+                finalizer: finalizer.syntax().clone(),
+            });
+        }
+
+        let catch_entry = handler.as_ref().map(|_| self.new_block());
+        if let Some(entry) = catch_entry {
+            self.contexts.push(Context::Catch { entry });
+        }
+        let try_entry = self.next_block();
+        // any statement in the try block may throw
+        if let Some(entry) = catch_entry {
+            self.edge(try_entry, entry);
+        }
+        if let Some(block) = stmt.test() {
+            self.stmt(block.syntax());
+        }
+        if catch_entry.is_some() {
+            self.contexts.pop();
+        }
+        let mut ends = vec![self.current];
+
+        if let (Some(handler), Some(entry)) = (handler, catch_entry) {
+            self.current = entry;
+            self.mark(handler.syntax());
+            if let Some(error) = handler.error() {
+                self.push(error.syntax());
+            }
+            if let Some(cons) = handler.cons() {
+                self.stmt(cons.syntax());
+            }
+            ends.push(self.current);
+        }
+
+        match finalizer {
+            Some(finalizer) => {
+                self.contexts.pop();
+                let entry = self.new_block();
+                for end in ends {
+                    self.edge(end, entry);
+                }
+                self.current = entry;
+                self.finalizer(node, finalizer.syntax());
+                self.edge(self.current, after);
+            }
+            None => {
+                for end in ends {
+                    self.edge(end, after);
+                }
+            }
+        }
+        self.current = after;
+    }
+
+    /// Build a copy of a `finally` block starting in the current block.
+    fn finalizer(&mut self, try_stmt: &SyntaxNode, finalizer: &SyntaxNode) {
+        self.contexts.push(Context::InFinalizer {
+            try_stmt: try_stmt.clone(),
+        });
+        self.stmt(finalizer);
+        self.contexts.pop();
+    }
+
+    /// Jump out of the current block to the target of a jump statement, running any `finally` blocks on the way.
+    fn jump(&mut self, node: &SyntaxNode, kind: JumpKind, label: Option<SmolStr>) {
+        let mut escaped = vec![];
+        let mut finalizers = vec![];
+        let mut target = None;
+
+        for (idx, context) in self.contexts.iter().enumerate().rev() {
+            match context {
+                Context::Breakable {
+                    labels,
+                    unlabelled,
+                    break_target,
+                    continue_target,
+                } => {
+                    let matches = match &label {
+                        Some(label) => labels.contains(label),
+                        None => *unlabelled,
+                    };
+                    let dest = match kind {
+                        JumpKind::Break if matches => Some(*break_target),
+                        JumpKind::Continue if matches => *continue_target,
+                        _ => None,
+                    };
+                    if dest.is_some() {
+                        target = dest;
+                        break;
+                    }
+                }
+                Context::Catch { entry } if kind == JumpKind::Throw => {
+                    target = Some(*entry);
+                    break;
+                }
+                Context::Finally {
+                    try_stmt,
+                    finalizer,
+                } => finalizers.push((idx, try_stmt.clone(), finalizer.clone())),
+                Context::InFinalizer { try_stmt } => escaped.push(try_stmt.clone()),
+                _ => {}
+            }
+        }
+
+        if !escaped.is_empty() {
+            self.escaped_finalizers.insert(node.clone(), escaped);
+        }
+
+        for (idx, try_stmt, finalizer) in finalizers {
+            let outer = self.contexts.split_off(idx);
+            self.next_block();
+            self.finalizer(&try_stmt, &finalizer);
+            self.contexts.extend(outer);
+        }
+
+        if let Some(target) = target {
+            self.edge(self.current, target);
+        }
+        // anything after a jump is unreachable
+        self.current = self.new_block();
+    }
+}
+
+/// The label of a `break` or `continue` statement.
+fn label(node: &SyntaxNode) -> Option<SmolStr> {
+    node.child_with_kind(NAME_REF)
+        .map(|name| SmolStr::new(name.trimmed_text().to_string()))
+}
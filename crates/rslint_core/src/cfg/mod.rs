@@ -0,0 +1,274 @@
+//! Control flow graphs of function bodies and programs.
+//!
+//! A [`ControlFlowGraph`] splits the statements of a body into basic blocks, which are sequences of
+//! statements and expressions that are always executed one after the other, connected by the jumps
+//! made by `if`, `switch`, loops, labels, `break`, `continue`, `return`, `throw` and `try` statements.
+//!
+//! The graph is an approximation made for linting:
+//! - Nested functions and classes are not part of the graph, they are treated as a single statement.
+//! - Any statement in a `try` block may throw, so the `catch` clause is reachable if the `try` block is.
+//! - Conditions which are always truthy or always falsey (such as `while (true)`) are taken into account,
+//!   other conditional expressions like `a && b` are not split into blocks.
+//! - `finally` blocks are copied for every way they can be entered (falling through, `return`, `throw`, etc.),
+//!   therefore a node in a `finally` block may be in more than one basic block.
+
+mod builder;
+
+use rslint_parser::{SyntaxKind, SyntaxNode};
+use std::collections::{HashMap, HashSet};
+
+/// The index of a [`BasicBlock`] in a [`ControlFlowGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockId(usize);
+
+/// A sequence of nodes which are executed one after the other without any jumps.
+#[derive(Debug, Clone, Default)]
+pub struct BasicBlock {
+    /// The simple statements and the conditions of compound statements executed by this block, in order.
+    /// Compound statements such as `if` and `while` statements are not included themselves.
+    pub nodes: Vec<SyntaxNode>,
+    /// The blocks which may be executed after this block.
+    pub successors: Vec<BlockId>,
+}
+
+/// The control flow graph of a function body or of a program.
+#[derive(Debug, Clone)]
+pub struct ControlFlowGraph {
+    blocks: Vec<BasicBlock>,
+    /// The block executed when the end of the body is reached without a `return` or `throw`.
+    end: BlockId,
+    /// The blocks every node (including compound statements) starts in.
+    node_blocks: HashMap<SyntaxNode, Vec<BlockId>>,
+    /// The `TRY_STMT`s whose `finally` block is exited by a jump statement inside of it.
+    escaped_finalizers: HashMap<SyntaxNode, Vec<SyntaxNode>>,
+    reachable: Vec<bool>,
+}
+
+impl ControlFlowGraph {
+    /// Build the graph of a function (a declaration, expression, arrow function, method, accessor or constructor),
+    /// a block statement, or the root of a program.
+    pub fn new(node: &SyntaxNode) -> Self {
+        builder::build(node)
+    }
+
+    /// The block execution starts in.
+    pub fn entry(&self) -> BlockId {
+        BlockId(0)
+    }
+
+    /// The block which is executed when the end of the body is reached without a `return` or `throw`.
+    /// This block never contains any nodes.
+    pub fn end(&self) -> BlockId {
+        self.end
+    }
+
+    pub fn block(&self, id: BlockId) -> &BasicBlock {
+        &self.blocks[id.0]
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = (BlockId, &BasicBlock)> {
+        self.blocks.iter().enumerate().map(|(i, b)| (BlockId(i), b))
+    }
+
+    /// Whether the block can be reached from the entry of the graph.
+    pub fn is_block_reachable(&self, id: BlockId) -> bool {
+        self.reachable[id.0]
+    }
+
+    /// Whether a node in the graph can be executed.
+    ///
+    /// Nodes which are not statements or conditions are reachable if the statement they are part of is,
+    /// this includes nodes in nested functions. Nodes outside of the graph are never reachable.
+    pub fn is_reachable(&self, node: &SyntaxNode) -> bool {
+        node.ancestors()
+            .find_map(|node| self.node_blocks.get(&node))
+            .map_or(false, |blocks| {
+                blocks.iter().any(|block| self.is_block_reachable(*block))
+            })
+    }
+
+    /// All nodes of reachable blocks, in the order of the blocks they are in.
+    ///
+    /// A node may be yielded more than once if it is in a `finally` block.
+    pub fn reachable_nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.blocks()
+            .filter(move |(id, _)| self.is_block_reachable(*id))
+            .flat_map(|(_, block)| block.nodes.iter())
+    }
+
+    /// Whether the end of the body can be reached without a `return` or `throw`.
+    pub fn can_fall_through(&self) -> bool {
+        self.is_block_reachable(self.end)
+    }
+
+    /// Whether every path through the body ends with a `return` or a `throw` statement.
+    pub fn all_paths_return(&self) -> bool {
+        !self.can_fall_through()
+    }
+
+    /// Whether there is a path from the entry to a `return` statement or to the end of the body which
+    /// does not execute a node matching `pred`.
+    ///
+    /// This can be used to check that something always happens before a function returns.
+    pub fn can_exit_without(&self, pred: impl Fn(&SyntaxNode) -> bool) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![self.entry()];
+
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            if id == self.end {
+                return true;
+            }
+
+            let block = self.block(id);
+            let mut blocked = false;
+            for node in &block.nodes {
+                if pred(node) {
+                    blocked = true;
+                    break;
+                }
+                if node.kind() == SyntaxKind::RETURN_STMT {
+                    return true;
+                }
+            }
+            if !blocked {
+                stack.extend(block.successors.iter().copied());
+            }
+        }
+        false
+    }
+
+    /// The `try` statements whose `finally` block is exited by a `return`, `throw`, `break` or `continue` statement
+    /// inside of it, from the innermost to the outermost.
+    ///
+    /// This is empty for jumps which are not in a `finally` block, and for jumps which stay inside of the
+    /// `finally` block such as a `break` out of a loop in the `finally` block.
+    pub fn escaped_finalizers(&self, jump: &SyntaxNode) -> &[SyntaxNode] {
+        self.escaped_finalizers
+            .get(jump)
+            .map_or(&[], |finalizers| finalizers.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_parser::parse_text;
+
+    fn build(src: &str) -> (SyntaxNode, ControlFlowGraph) {
+        let root = parse_text(src, 0).syntax();
+        let func = root
+            .descendants()
+            .find(|node| node.kind() == SyntaxKind::FN_DECL)
+            .unwrap();
+        let cfg = ControlFlowGraph::new(&func);
+        (root, cfg)
+    }
+
+    fn find(root: &SyntaxNode, text: &str) -> SyntaxNode {
+        root.descendants()
+            .filter(|node| node.kind() == SyntaxKind::EXPR_STMT)
+            .find(|node| node.text().to_string().trim() == text)
+            .unwrap()
+    }
+
+    #[test]
+    fn returns() {
+        assert!(build("function f() { return 1; }").1.all_paths_return());
+        assert!(
+            build("function f() { if (a) { return 1; } else { throw 2; } }")
+                .1
+                .all_paths_return()
+        );
+        assert!(build("function f() { if (a) { return 1; } }")
+            .1
+            .can_fall_through());
+        assert!(build("function f() { while (true) { if (a) return 1; } }")
+            .1
+            .all_paths_return());
+        assert!(build("function f() { while (true) { if (a) break; } }")
+            .1
+            .can_fall_through());
+        assert!(
+            build("function f() { switch (a) { case 1: return 1; default: return 2; } }")
+                .1
+                .all_paths_return()
+        );
+        assert!(build("function f() { switch (a) { case 1: return 1; } }")
+            .1
+            .can_fall_through());
+        assert!(build("function f() { try { return 1; } catch (e) {} }")
+            .1
+            .can_fall_through());
+        assert!(
+            build("function f() { try { foo(); } finally { return 1; } }")
+                .1
+                .all_paths_return()
+        );
+        assert!(
+            build("function f() { try { return 1; } finally { foo(); } }")
+                .1
+                .all_paths_return()
+        );
+    }
+
+    #[test]
+    fn reachability() {
+        let (root, cfg) = build("function f() { a; return; b; }");
+        assert!(cfg.is_reachable(&find(&root, "a;")));
+        assert!(!cfg.is_reachable(&find(&root, "b;")));
+
+        let (root, cfg) =
+            build("function f() { outer: for (;;) { for (;;) { continue outer; a; } b; } c; }");
+        assert!(!cfg.is_reachable(&find(&root, "a;")));
+        assert!(!cfg.is_reachable(&find(&root, "b;")));
+        assert!(!cfg.is_reachable(&find(&root, "c;")));
+
+        let (root, cfg) = build("function f() { try { throw 1; a; } catch (e) { b; } c; }");
+        assert!(!cfg.is_reachable(&find(&root, "a;")));
+        assert!(cfg.is_reachable(&find(&root, "b;")));
+        assert!(cfg.is_reachable(&find(&root, "c;")));
+
+        let (root, cfg) = build("function f() { if (false) { a; } else { b; } }");
+        assert!(!cfg.is_reachable(&find(&root, "a;")));
+        assert!(cfg.is_reachable(&find(&root, "b;")));
+    }
+
+    #[test]
+    fn exits_without() {
+        let is_call = |node: &SyntaxNode| node.text().to_string().trim() == "call();";
+        assert!(!build("function f() { call(); }")
+            .1
+            .can_exit_without(is_call));
+        assert!(build("function f() { if (a) call(); }")
+            .1
+            .can_exit_without(is_call));
+        assert!(
+            !build("function f() { if (a) { call(); } else { call(); } }")
+                .1
+                .can_exit_without(is_call)
+        );
+        assert!(build("function f() { if (a) return; call(); }")
+            .1
+            .can_exit_without(is_call));
+    }
+
+    #[test]
+    fn finalizers() {
+        let (root, cfg) =
+            build("function f() { try {} finally { for (;;) { break; } if (a) { return; } } }");
+        let jumps = root
+            .descendants()
+            .filter(|node| {
+                matches!(
+                    node.kind(),
+                    SyntaxKind::BREAK_STMT | SyntaxKind::RETURN_STMT
+                )
+            })
+            .collect::<Vec<_>>();
+        assert!(cfg.escaped_finalizers(&jumps[0]).is_empty());
+        assert_eq!(cfg.escaped_finalizers(&jumps[1]).len(), 1);
+    }
+}
@@ -0,0 +1,351 @@
+//! Rules declared in a config with a code pattern or a node kind.
+//!
+//! A custom rule reports every node which matches its [`Pattern`](crate::pattern::Pattern), or every node of
+//! a kind such as `DEBUGGER_STMT`. Metavariables bound by the pattern can be used in the message and in
+//! an optional rewrite, which replaces the matched node when fixing. Custom rules are in the `custom` group
+//! and are otherwise run like any other [`CstRule`].
+
+use crate::pattern::{template_variables, Pattern, PatternError};
+use crate::{Applicability, CstRule, Rule, RuleCtx, RuleLevel};
+use rslint_parser::{SyntaxKind, SyntaxNode, SyntaxNodeExt};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// The group of every custom rule.
+pub const CUSTOM_GROUP: &str = "custom";
+
+/// A custom rule as it is declared in the `custom_rules` of a config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct CustomRuleConfig {
+    /// The kebab-case name of the rule.
+    pub name: String,
+    /// The message of the rule's diagnostics, metavariables of the pattern are replaced with the code bound to them.
+    pub message: String,
+    /// The level the rule runs at, `error` if it is not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<RuleLevel>,
+    /// Code with metavariables such as `$OBJ.innerHTML = $VAL` which nodes are matched against.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// The kind of node to match instead of a pattern, such as `DEBUGGER_STMT`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Code which replaces matched nodes when fixing, metavariables are replaced like in the message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rewrite: Option<String>,
+}
+
+/// An error which makes a [`CustomRuleConfig`] invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CustomRuleError {
+    /// The name is not kebab-case.
+    InvalidName(String),
+    /// Neither or both of a pattern and a kind were given.
+    Matcher,
+    Pattern(PatternError),
+    UnknownKind(String),
+    /// The message or the rewrite uses a metavariable the pattern does not bind.
+    UnknownVariable {
+        field: &'static str,
+        name: String,
+    },
+}
+
+impl fmt::Display for CustomRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CustomRuleError::InvalidName(name) => {
+                write!(
+                    f,
+                    "`{}` is not a valid rule name, names must be kebab-case",
+                    name
+                )
+            }
+            CustomRuleError::Matcher => {
+                write!(f, "a custom rule must have either a `pattern` or a `kind`")
+            }
+            CustomRuleError::Pattern(err) => write!(f, "{}", err),
+            CustomRuleError::UnknownKind(kind) => write!(f, "unknown node kind `{}`", kind),
+            CustomRuleError::UnknownVariable { field, name } => write!(
+                f,
+                "the {} uses `${}` but the pattern does not bind it",
+                field, name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CustomRuleError {}
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Pattern(Pattern),
+    Kind(SyntaxKind),
+}
+
+/// A compiled custom rule.
+///
+/// It is serialized as its [`CustomRuleConfig`].
+#[derive(Debug, Clone)]
+pub struct CustomRule {
+    // leaked once when the rule is compiled so it can be returned as `&'static str`
+    name: &'static str,
+    config: CustomRuleConfig,
+    matcher: Matcher,
+    // the kind of nodes the matcher can match
+    kind: SyntaxKind,
+}
+
+impl CustomRule {
+    /// Compile a custom rule, checking its name, its pattern or kind, and the metavariables it uses.
+    pub fn new(config: CustomRuleConfig) -> Result<Self, CustomRuleError> {
+        let valid_name = !config.name.is_empty()
+            && !config.name.starts_with('-')
+            && config
+                .name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !valid_name {
+            return Err(CustomRuleError::InvalidName(config.name));
+        }
+
+        let matcher = match (&config.pattern, &config.kind) {
+            (Some(pattern), None) => {
+                Matcher::Pattern(Pattern::new(pattern).map_err(CustomRuleError::Pattern)?)
+            }
+            (None, Some(kind)) => Matcher::Kind(
+                syntax_kind_by_name(kind)
+                    .ok_or_else(|| CustomRuleError::UnknownKind(kind.clone()))?,
+            ),
+            _ => return Err(CustomRuleError::Matcher),
+        };
+
+        let bound = match &matcher {
+            Matcher::Pattern(pattern) => pattern.variables(),
+            Matcher::Kind(_) => vec![],
+        };
+        let templates = std::iter::once(("message", Some(&config.message)))
+            .chain(std::iter::once(("rewrite", config.rewrite.as_ref())));
+        for (field, template) in templates {
+            let template = match template {
+                Some(template) => template,
+                None => continue,
+            };
+            if let Some(name) = template_variables(template)
+                .into_iter()
+                .find(|name| !bound.contains(name))
+            {
+                return Err(CustomRuleError::UnknownVariable {
+                    field,
+                    name: name.to_string(),
+                });
+            }
+        }
+
+        let kind = match &matcher {
+            Matcher::Pattern(pattern) => pattern.kind(),
+            Matcher::Kind(kind) => *kind,
+        };
+        Ok(Self {
+            name: Box::leak(config.name.clone().into_boxed_str()),
+            config,
+            matcher,
+            kind,
+        })
+    }
+
+    pub fn config(&self) -> &CustomRuleConfig {
+        &self.config
+    }
+
+    /// The level the rule was declared with, `error` if it was not declared with one.
+    pub fn level(&self) -> RuleLevel {
+        self.config.severity.unwrap_or(RuleLevel::Error)
+    }
+}
+
+impl Serialize for CustomRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.config.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = CustomRuleConfig::deserialize(deserializer)?;
+        Self::new(config).map_err(D::Error::custom)
+    }
+}
+
+/// Find a syntax kind by its name such as `CALL_EXPR`, ignoring case.
+pub fn syntax_kind_by_name(name: &str) -> Option<SyntaxKind> {
+    (0..SyntaxKind::__LAST as u16)
+        .map(SyntaxKind::from)
+        .find(|kind| format!("{:?}", kind).eq_ignore_ascii_case(name))
+}
+
+impl Rule for CustomRule {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn group(&self) -> &'static str {
+        CUSTOM_GROUP
+    }
+
+    fn fixes(&self) -> &'static [Applicability] {
+        if self.config.rewrite.is_some() {
+            &[Applicability::Always]
+        } else {
+            &[]
+        }
+    }
+}
+
+#[typetag::serde]
+impl CstRule for CustomRule {
+    fn check_node(&self, node: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
+        let (message, rewrite) = match &self.matcher {
+            Matcher::Pattern(pattern) => {
+                let found = pattern.matches(node)?;
+                (
+                    found.substitute(&self.config.message, &ctx.src),
+                    self.config
+                        .rewrite
+                        .as_ref()
+                        .map(|rewrite| found.substitute(rewrite, &ctx.src)),
+                )
+            }
+            Matcher::Kind(_) => (self.config.message.clone(), self.config.rewrite.clone()),
+        };
+
+        let range = node.trimmed_range();
+        let err = ctx.err(self.name, message).primary(range, "");
+        ctx.add_err(err);
+        if let Some(rewrite) = rewrite {
+            ctx.fix().replace(range, rewrite);
+        }
+        None
+    }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(std::slice::from_ref(&self.kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_rule, Severity};
+    use rslint_parser::parse_module;
+    use std::sync::Arc;
+
+    fn config(
+        pattern: Option<&str>,
+        kind: Option<&str>,
+        rewrite: Option<&str>,
+    ) -> CustomRuleConfig {
+        CustomRuleConfig {
+            name: "no-inner-html".to_string(),
+            message: "do not set `$OBJ.innerHTML`".to_string(),
+            severity: None,
+            pattern: pattern.map(str::to_string),
+            kind: kind.map(str::to_string),
+            rewrite: rewrite.map(str::to_string),
+        }
+    }
+
+    fn check(rule: &CustomRule, src: &str) -> crate::RuleResult {
+        let root = parse_module(src, 0).syntax();
+        run_rule(rule, 0, root, false, &[], Arc::from(src))
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let mut invalid_name = config(Some("$OBJ.innerHTML = $VAL"), None, None);
+        invalid_name.name = "noInnerHtml".to_string();
+        assert!(matches!(
+            CustomRule::new(invalid_name),
+            Err(CustomRuleError::InvalidName(_))
+        ));
+        assert_eq!(
+            CustomRule::new(config(None, None, None)).unwrap_err(),
+            CustomRuleError::Matcher
+        );
+        assert!(matches!(
+            CustomRule::new(config(Some("a ="), None, None)),
+            Err(CustomRuleError::Pattern(_))
+        ));
+        assert!(matches!(
+            CustomRule::new(config(None, Some("NOT_A_KIND"), None)),
+            Err(CustomRuleError::UnknownKind(_))
+        ));
+        assert!(matches!(
+            CustomRule::new(config(Some("$OBJ.innerHTML = $VAL"), None, Some("$X"))),
+            Err(CustomRuleError::UnknownVariable {
+                field: "rewrite",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn patterns() {
+        let rule = CustomRule::new(config(
+            Some("$OBJ.innerHTML = $VAL"),
+            None,
+            Some("$OBJ.textContent = $VAL"),
+        ))
+        .unwrap();
+        assert_eq!(rule.name(), "no-inner-html");
+        assert_eq!(rule.group(), "custom");
+
+        let result = check(&rule, "foo.bar.innerHTML = x;");
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(
+            result.diagnostics[0].title,
+            "do not set `foo.bar.innerHTML`"
+        );
+        assert_eq!(result.diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            result.fix("foo.bar.innerHTML = x;").as_deref(),
+            Some("foo.bar.textContent = x;")
+        );
+
+        assert!(check(&rule, "foo.innerHTML; foo.textContent = x;")
+            .diagnostics
+            .is_empty());
+    }
+
+    #[test]
+    fn kinds() {
+        let mut config = config(None, Some("debugger_stmt"), Some(""));
+        config.message = "unexpected debugger".to_string();
+        let rule = CustomRule::new(config).unwrap();
+        assert_eq!(rule.fixes(), &[Applicability::Always]);
+
+        let result = check(&rule, "debugger; foo()");
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.fix("debugger; foo()").as_deref(), Some(" foo()"));
+
+        // every diagnostic has its own fix
+        let result = check(&rule, "debugger; foo(); debugger;");
+        assert_eq!(result.diagnostics.len(), 2);
+        assert!(result.diagnostics.iter().all(|d| d.fix.is_some()));
+        assert_eq!(
+            result.fix("debugger; foo(); debugger;").as_deref(),
+            Some(" foo(); ")
+        );
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        let rule: Box<dyn CstRule> =
+            Box::new(CustomRule::new(config(Some("$OBJ.innerHTML = $VAL"), None, None)).unwrap());
+        let json = serde_json::to_string(&rule).unwrap();
+        let rule: Box<dyn CstRule> = serde_json::from_str(&json).unwrap();
+        assert_eq!(rule.name(), "no-inner-html");
+    }
+}
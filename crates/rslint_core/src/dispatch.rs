@@ -0,0 +1,232 @@
+//! Running many rules on a file in a single traversal of its syntax tree.
+//!
+//! Rather than walking the whole tree once per rule, the tree is walked once and each node is only given to
+//! the rules which declared its kind in [`CstRule::node_kinds`], tokens are only given to the rules which
+//! [`need tokens`](CstRule::needs_tokens). Nodes ignored by a directive are skipped for the rules they ignore.
+
+use crate::isolation::{catch_panic, panic_diagnostic};
+use crate::{skip_node, CstRule, Directive, RuleCtx, RuleResult};
+use rslint_parser::{NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, WalkEvent};
+
+/// A rule being run on a file, with the diagnostics it emitted so far.
+///
+/// A rule which panics is reported with a bug diagnostic and is not run on the rest of the file.
+pub(crate) struct RuleRun<'r> {
+    pub rule: &'r dyn CstRule,
+    pub ctx: RuleCtx,
+    /// The range of the node or token being checked when each diagnostic was emitted.
+    pub origins: Vec<TextRange>,
+    // the node ignored by a directive for this rule which the traversal is currently in
+    skipped: Option<SyntaxNode>,
+    panicked: bool,
+}
+
+impl<'r> RuleRun<'r> {
+    pub fn new(rule: &'r dyn CstRule, ctx: RuleCtx) -> Self {
+        Self {
+            rule,
+            ctx,
+            origins: vec![],
+            skipped: None,
+            panicked: false,
+        }
+    }
+
+    /// Whether the rule checks nodes of a kind.
+    pub fn handles(&self, kind: SyntaxKind) -> bool {
+        self.rule
+            .node_kinds()
+            .map_or(true, |kinds| kinds.contains(&kind))
+    }
+
+    pub fn check_root(&mut self, root: &SyntaxNode) {
+        self.check(root.text_range(), |rule, ctx| rule.check_root(root, ctx));
+    }
+
+    /// Check a node, the caller must make sure the rule [`handles`](Self::handles) its kind.
+    pub fn check_node(&mut self, node: &SyntaxNode) {
+        self.check(node.text_range(), |rule, ctx| rule.check_node(node, ctx));
+    }
+
+    fn check_token(&mut self, token: &SyntaxToken) {
+        self.check(token.text_range(), |rule, ctx| rule.check_token(token, ctx));
+    }
+
+    fn check(
+        &mut self,
+        range: TextRange,
+        check: impl FnOnce(&dyn CstRule, &mut RuleCtx) -> Option<()>,
+    ) {
+        if self.panicked {
+            return;
+        }
+        let (rule, ctx) = (self.rule, &mut self.ctx);
+        if let Err(panic) = catch_panic(|| check(rule, ctx)) {
+            let d = panic_diagnostic(self.ctx.file_id, Some(rule.name()), &panic);
+            self.ctx.diagnostics.push(d);
+            self.panicked = true;
+        }
+        self.origins.resize(self.ctx.diagnostics.len(), range);
+    }
+
+    pub fn finish(mut self) -> RuleResult {
+        let mut result = RuleResult::new(self.ctx.take_diagnostics());
+        result.origins = self.origins;
+        result
+    }
+}
+
+/// Check every node and token in a node, but not the node itself, with each rule in one traversal.
+///
+/// `ERROR` nodes are skipped for every rule and nodes ignored by directives are skipped for the rules they ignore.
+pub(crate) fn check_subtree(runs: &mut [RuleRun], node: &SyntaxNode, directives: &[Directive]) {
+    let mut by_kind = vec![vec![]; SyntaxKind::__LAST as usize];
+    let mut token_rules = vec![];
+    for (idx, run) in runs.iter().enumerate() {
+        match run.rule.node_kinds() {
+            Some(kinds) => kinds
+                .iter()
+                .for_each(|kind| by_kind[*kind as usize].push(idx)),
+            None => by_kind.iter_mut().for_each(|rules| rules.push(idx)),
+        }
+        if run.rule.needs_tokens() {
+            token_rules.push(idx);
+        }
+    }
+
+    let mut error: Option<SyntaxNode> = None;
+    let mut skipped = 0;
+    for event in node.preorder_with_tokens() {
+        match event {
+            WalkEvent::Enter(NodeOrToken::Node(child)) => {
+                if error.is_some() || child == *node {
+                    continue;
+                }
+                if child.kind() == SyntaxKind::ERROR {
+                    error = Some(child);
+                    continue;
+                }
+                if !directives.is_empty() {
+                    for run in runs.iter_mut().filter(|run| run.skipped.is_none()) {
+                        if skip_node(directives, &child, run.rule) {
+                            run.skipped = Some(child.clone());
+                            skipped += 1;
+                        }
+                    }
+                }
+                for idx in &by_kind[child.kind() as usize] {
+                    let run = &mut runs[*idx];
+                    if run.skipped.is_none() {
+                        run.check_node(&child);
+                    }
+                }
+            }
+            WalkEvent::Leave(NodeOrToken::Node(child)) => {
+                if error.as_ref() == Some(&child) {
+                    error = None;
+                } else if skipped > 0 {
+                    for run in runs.iter_mut() {
+                        if run.skipped.as_ref() == Some(&child) {
+                            run.skipped = None;
+                            skipped -= 1;
+                        }
+                    }
+                }
+            }
+            WalkEvent::Enter(NodeOrToken::Token(token)) => {
+                if error.is_some() {
+                    continue;
+                }
+                for idx in &token_rules {
+                    let run = &mut runs[*idx];
+                    if run.skipped.is_none() {
+                        run.check_token(&token);
+                    }
+                }
+            }
+            WalkEvent::Leave(NodeOrToken::Token(_)) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lint_file, CstRule, CstRuleStore, CustomRule, CustomRuleConfig, File, LintJob,
+        PARALLEL_LINT_THRESHOLD,
+    };
+    use rslint_parser::FileKind;
+
+    fn rule(name: &str) -> Box<dyn CstRule> {
+        Box::new(
+            CustomRule::new(CustomRuleConfig {
+                name: name.to_string(),
+                message: "unexpected debugger".to_string(),
+                severity: None,
+                pattern: None,
+                kind: Some("DEBUGGER_STMT".to_string()),
+                rewrite: None,
+            })
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn directives_skip_nodes_per_rule() {
+        let src = "debugger;\n// rslint-ignore first\nif (a) { debugger; }\ndebugger;\n";
+        let mut store = CstRuleStore::new();
+        store.load_rules(vec![rule("first"), rule("second")]);
+        let file = File::from_string(src, FileKind::Module, "test.js");
+        let result = lint_file(&file, &store, false);
+
+        let starts = |name: &str| {
+            result.rule_results[name]
+                .origins
+                .iter()
+                .map(|range| u32::from(range.start()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(starts("first"), vec![0, 54]);
+        assert_eq!(starts("second"), vec![0, 42, 54]);
+    }
+
+    #[test]
+    fn batches_on_threads() {
+        let snippet =
+            "if (a === -0) { debugger; }\nlet b = [1,, 2];;\n// rslint-ignore\nif (c) {}\n";
+        let src = snippet.repeat(PARALLEL_LINT_THRESHOLD / snippet.len() + 1);
+        let store = CstRuleStore::new().builtins();
+        let file = File::from_string(src, FileKind::Script, "test.js");
+
+        let job = LintJob::new(&file, &store, false);
+        let batches = job.batches(4);
+        assert_eq!(batches.len(), 4);
+        assert_eq!(batches.last().unwrap().end, store.rules.len());
+        let results = std::thread::scope(|scope| {
+            let handles = batches
+                .into_iter()
+                .map(|batch| scope.spawn(|| job.run(batch)))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        let parallel = job.finish(results);
+
+        let full = lint_file(&file, &store, false);
+        assert_eq!(parallel.rule_results.len(), full.rule_results.len());
+        for (name, result) in &full.rule_results {
+            assert_eq!(parallel.rule_results[name].diagnostics, result.diagnostics);
+        }
+        assert!(full.rule_results["no-debugger"].diagnostics.len() > 1);
+    }
+
+    #[test]
+    fn small_files_have_one_batch() {
+        let store = CstRuleStore::new().builtins();
+        let file = File::from_string("debugger;", FileKind::Module, "test.js");
+        let job = LintJob::new(&file, &store, false);
+        assert_eq!(job.batches(4), vec![0..store.rules.len()]);
+    }
+}
@@ -1,6 +1,6 @@
 //! Representation of a file for the linter
 
-use rslint_parser::{parse_with_syntax, FileKind, ParserError, SyntaxNode};
+use rslint_parser::{parse_with_syntax, FileKind, ParserError, Syntax, SyntaxNode};
 use std::ops::Range;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -20,6 +20,9 @@ pub struct File {
     pub id: usize,
     /// The kind of file this is.
     pub kind: FileKind,
+    /// The syntax the file is parsed with. This is derived from `kind` by default,
+    /// but it may enable extra features such as decorators.
+    pub syntax: Syntax,
     /// The cached line start locations in this file.
     pub line_starts: Vec<usize>,
 }
@@ -47,6 +50,7 @@ impl File {
             path: Some(path),
             id,
             kind,
+            syntax: kind.into(),
             line_starts,
         }
     }
@@ -62,6 +66,7 @@ impl File {
             path: None,
             id,
             kind,
+            syntax: kind.into(),
             line_starts,
         }
     }
@@ -71,6 +76,19 @@ impl File {
         self.source = new;
     }
 
+    /// A copy of the file without its source, which keeps which file it is without keeping its code in memory.
+    pub fn metadata(&self) -> Self {
+        Self {
+            source: String::new(),
+            name: self.name.clone(),
+            path: self.path.clone(),
+            id: self.id,
+            kind: self.kind,
+            syntax: self.syntax,
+            line_starts: vec![0],
+        }
+    }
+
     // TODO: Needs to work correctly for \u2028, \u2029, and \r line endings
     pub fn line_starts(source: &str) -> impl Iterator<Item = usize> + '_ {
         std::iter::once(0).chain(source.match_indices('\n').map(|(i, _)| i + 1))
@@ -107,11 +125,11 @@ impl File {
 
     /// Parse this file into a syntax node, ignoring any errors produced. This
     pub fn parse(&self) -> SyntaxNode {
-        parse_with_syntax(&self.source, self.id, self.kind.into()).syntax()
+        parse_with_syntax(&self.source, self.id, self.syntax).syntax()
     }
 
     pub fn parse_with_errors(&self) -> (Vec<ParserError>, SyntaxNode) {
-        let parse = parse_with_syntax(&self.source, self.id, self.kind.into());
+        let parse = parse_with_syntax(&self.source, self.id, self.syntax);
         (parse.errors().to_vec(), parse.syntax())
     }
 }
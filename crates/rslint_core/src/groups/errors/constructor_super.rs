@@ -1,5 +1,7 @@
+use crate::cfg::ControlFlowGraph;
 use crate::rule_prelude::*;
-use ast::{ClassDecl, ClassElement, Expr, Stmt};
+use ast::{ClassDecl, ClassElement};
+use SyntaxKind::*;
 
 declare_lint! {
     /**
@@ -89,48 +91,61 @@ impl CstRule for ConstructorSuper {
                 ClassElement::Constructor(c) => Some(c),
                 _ => None,
             })?;
-        let super_call = constructor
-            // get only the expression statements for the constructor's body
-            .body()?
-            .stmts()
-            .filter_map(|x| match x {
-                Stmt::ExprStmt(expr) => expr.expr(),
-                _ => None,
-            })
-            // find a super call expression. there should only be one.
-            .find(|x| matches!(x, Expr::SuperCall(_)));
-
-        // if it's a subclass xor it's constructor calls super, show error.
-        match (superclass, super_call) {
-            (Some(class), None) => {
+        let cfg = ControlFlowGraph::new(constructor.syntax());
+        let reachable_call = super_calls(constructor.syntax()).find(|call| cfg.is_reachable(call));
+
+        match superclass {
+            Some(class) if cfg.can_exit_without(|node| super_calls(node).next().is_some()) => {
+                let label = if reachable_call.is_some() {
+                    "super is not called on every path through this constructor"
+                } else {
+                    "no call to super found within constructor"
+                };
                 let diagnostic = ctx
                     .err(self.name(), "constructor of derived class must call super")
-                    .primary(
-                        constructor.syntax(),
-                        "no call to super found within constructor",
-                    )
+                    .primary(constructor.syntax(), label)
                     .secondary(class.syntax(), "superclass specified here");
 
                 ctx.add_err(diagnostic);
             }
-            (None, Some(call)) => {
-                let diagnostic = ctx
-                    .err(
-                        self.name(),
-                        "cannot call super in constructor of base class",
-                    )
-                    .primary(
-                        call.syntax(),
-                        "called super here, but no superclass was specified",
-                    );
-
-                ctx.add_err(diagnostic);
+            None => {
+                if let Some(call) = reachable_call {
+                    let diagnostic = ctx
+                        .err(
+                            self.name(),
+                            "cannot call super in constructor of base class",
+                        )
+                        .primary(call, "called super here, but no superclass was specified");
+
+                    ctx.add_err(diagnostic);
+                }
             }
             _ => {}
         }
 
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[CLASS_DECL])
+    }
+}
+
+/// Calls to `super()` in a node which are not inside of a nested function or class.
+/// Arrow functions are included because they use the `super` of their enclosing function.
+fn super_calls(node: &SyntaxNode) -> impl Iterator<Item = SyntaxNode> + '_ {
+    node.descendants().filter(move |call| {
+        call.kind() == SUPER_CALL
+            && !call
+                .ancestors()
+                .take_while(|ancestor| ancestor != node)
+                .any(|ancestor| {
+                    matches!(
+                        ancestor.kind(),
+                        FN_DECL | FN_EXPR | CLASS_DECL | CLASS_EXPR | METHOD | GETTER | SETTER
+                    )
+                })
+    })
 }
 
 rule_tests! {
@@ -138,9 +153,17 @@ rule_tests! {
     err: {
         "class A { constructor() { super(); } }",
         "class A extends B { constructor() { } }",
+        "class A { constructor() { if (a) { super(); } } }",
+        "class A extends B { constructor() { if (a) { super(); } } }",
+        "class A extends B { constructor() { if (a) { return; } super(); } }",
     },
     ok: {
         "class A { constructor() { } }",
         "class A extends B { constructor() { super(); } }",
+        "class A extends B { constructor() { if (a) { super(a); } else { super(); } } }",
+        "class A extends B { constructor() { switch (a) { case 1: super(1); break; default: super(); } } }",
+        "class A extends B { constructor() { const a = () => {}; super(); } }",
+        "class A extends B { constructor() { throw new Error(); } }",
+        "class A { constructor() { return; super(); } }",
     }
 }
@@ -78,6 +78,10 @@ impl CstRule for ForDirection {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[FOR_STMT])
+    }
 }
 
 fn update_direction(for_stmt: &ForStmt, counter: &NameRef) -> Option<i8> {
@@ -1,3 +1,4 @@
+use crate::cfg::ControlFlowGraph;
 use crate::rule_prelude::*;
 use ast::*;
 use SyntaxKind::*;
@@ -91,21 +92,15 @@ impl CstRule for GetterReturn {
                                 }
                                 match literal_prop.value()? {
                                     Expr::FnExpr(decl) => {
-                                        self.check_stmts(
+                                        self.check_body(
                                             args[1].syntax(),
                                             decl.body()?.syntax(),
-                                            decl.body()?.stmts(),
                                             ctx,
                                         );
                                     }
                                     Expr::ArrowExpr(arrow) => {
                                         if let ExprOrBlock::Block(block) = arrow.body()? {
-                                            self.check_stmts(
-                                                args[1].syntax(),
-                                                block.syntax(),
-                                                block.stmts(),
-                                                ctx,
-                                            );
+                                            self.check_body(args[1].syntax(), block.syntax(), ctx);
                                         }
                                     }
                                     _ => {}
@@ -119,7 +114,7 @@ impl CstRule for GetterReturn {
                 let getter = node.to::<Getter>();
                 if let Some(body) = getter.body() {
                     if let Some(key) = getter.key() {
-                        self.check_stmts(key.syntax(), body.syntax(), body.stmts(), ctx);
+                        self.check_body(key.syntax(), body.syntax(), ctx);
                     }
                 }
             }
@@ -127,18 +122,24 @@ impl CstRule for GetterReturn {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[CALL_EXPR, GETTER])
+    }
 }
 
 impl GetterReturn {
-    fn check_stmts(
-        &self,
-        key: &SyntaxNode,
-        body: &SyntaxNode,
-        mut stmts: impl Iterator<Item = Stmt>,
-        ctx: &mut RuleCtx,
-    ) {
-        if !stmts.any(|stmt| self.check_stmt(&stmt)) {
-            let err = ctx
+    fn check_body(&self, key: &SyntaxNode, body: &SyntaxNode, ctx: &mut RuleCtx) {
+        let cfg = ControlFlowGraph::new(body);
+        let implicit_return = cfg.reachable_nodes().find(|node| {
+            !self.allow_implicit
+                && node
+                    .try_to::<ReturnStmt>()
+                    .map_or(false, |stmt| stmt.value().is_none())
+        });
+
+        if cfg.can_fall_through() || implicit_return.is_some() {
+            let mut err = ctx
                 .err(
                     self.name(),
                     format!(
@@ -146,43 +147,22 @@ impl GetterReturn {
                         key.trimmed_text()
                     ),
                 )
-                .secondary(key, "this key is sometimes or always undefined...")
-                .primary(
+                .secondary(key, "this key is sometimes or always undefined...");
+
+            err = match implicit_return {
+                Some(stmt) if !cfg.can_fall_through() => err.primary(
+                    stmt,
+                    "...because this return statement does not return a value",
+                ),
+                _ => err.primary(
                     body,
                     "...because this getter does not always return a value",
-                );
+                ),
+            };
 
             ctx.add_err(err);
         }
     }
-
-    fn check_stmt(&self, stmt: &Stmt) -> bool {
-        match stmt {
-            Stmt::IfStmt(if_stmt) => self.check_if(if_stmt),
-            Stmt::BlockStmt(block) => block.stmts().any(|stmt| self.check_stmt(&stmt)),
-            Stmt::ReturnStmt(stmt) => stmt.value().is_some() || self.allow_implicit,
-            Stmt::SwitchStmt(switch) => switch.cases().any(|case| match case {
-                SwitchCase::CaseClause(clause) => clause.cons().any(|s| self.check_stmt(&s)),
-                SwitchCase::DefaultClause(clause) => clause.cons().any(|s| self.check_stmt(&s)),
-            }),
-            _ => false,
-        }
-    }
-
-    /// Check if an if statement unconditionally returns from the statement.
-    fn check_if(&self, stmt: &IfStmt) -> bool {
-        if stmt.alt().is_none() {
-            return false;
-        }
-
-        if let Some(cons) = stmt.cons() {
-            if !self.check_stmt(&cons) {
-                return false;
-            }
-            return self.check_stmt(&stmt.alt().unwrap());
-        }
-        false
-    }
 }
 
 rule_tests! {
@@ -227,6 +207,37 @@ rule_tests! {
                 }
             }
         }
+        ",
+        "
+        let bar = {
+            get foo() {
+                switch (bar) {
+                    case 5:
+                        return 5;
+                    case 6:
+                        break;
+                }
+            }
+        }
+        ",
+        "
+        let bar = {
+            get foo() {
+                for (const a of b) {
+                    return a;
+                }
+            }
+        }
+        ",
+        "
+        let bar = {
+            get foo() {
+                if (bar) {
+                    return;
+                }
+                return 5;
+            }
+        }
         "
     },
     ok: {
@@ -251,6 +262,40 @@ rule_tests! {
                 }
             }
         }
+        ",
+        "
+        let bar = {
+            get foo() {
+                switch (bar) {
+                    case 5:
+                        return 5;
+                    default:
+                        throw new Error();
+                }
+            }
+        }
+        ",
+        "
+        let bar = {
+            get foo() {
+                while (true) {
+                    if (bar) {
+                        return 5;
+                    }
+                }
+            }
+        }
+        ",
+        "
+        let bar = {
+            get foo() {
+                try {
+                    return foo();
+                } catch (e) {
+                    return 5;
+                }
+            }
+        }
         "
     }
 }
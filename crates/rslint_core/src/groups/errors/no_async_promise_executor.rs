@@ -65,6 +65,10 @@ impl CstRule for NoAsyncPromiseExecutor {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[SyntaxKind::NEW_EXPR])
+    }
 }
 
 fn check_arg(arg: ast::Expr) -> Option<TextRange> {
@@ -76,6 +76,11 @@ impl CstRule for NoAwaitInLoop {
         }
         None
     }
+
+    // awaits can be in any kind of node, every node is checked for await expressions in it
+    fn needs_tokens(&self) -> bool {
+        false
+    }
 }
 
 rule_tests! {
@@ -1,4 +1,5 @@
 use crate::rule_prelude::*;
+use ast::make;
 
 declare_lint! {
     /**
@@ -34,6 +35,7 @@ declare_lint! {
     NoCompareNegZero,
     errors,
     tags(Recommended),
+    fixes(MaybeIncorrect),
     "no-compare-neg-zero"
 }
 
@@ -60,9 +62,17 @@ impl CstRule for NoCompareNegZero {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[SyntaxKind::BIN_EXPR])
+    }
 }
 
 fn issue_err(expr: ast::Expr, ctx: &mut RuleCtx, op: SyntaxToken, parent: &SyntaxNode) {
+    let object_is = make::call_expr(
+        make::dot_expr(make::ident("Object"), "is"),
+        vec![expr.clone(), make::number(-0.0)],
+    );
     let err = ctx
         .err(
             "no-compare-neg-zero",
@@ -78,14 +88,14 @@ fn issue_err(expr: ast::Expr, ctx: &mut RuleCtx, op: SyntaxToken, parent: &Synta
         .suggestion(
             parent,
             "try using `Object.is` instead",
-            format!("Object.is({}, -0)", expr.syntax().text()),
+            object_is.text(),
             Applicability::MaybeIncorrect,
         );
 
-    ctx.fix()
-        .replace(parent, format!("Object.is({}, -0)", expr.text()));
-
     ctx.add_err(err);
+    ctx.fix()
+        .replace_expr(&parent.to(), object_is)
+        .applicability(Applicability::MaybeIncorrect);
 }
 
 fn unsafe_comparison(expr: &ast::Expr) -> bool {
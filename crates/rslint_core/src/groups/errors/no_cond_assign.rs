@@ -84,6 +84,10 @@ impl CstRule for NoCondAssign {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&COND_CHECKED)
+    }
 }
 
 fn condition(node: &SyntaxNode) -> Option<Expr> {
@@ -65,6 +65,10 @@ impl CstRule for NoConfusingArrow {
 
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[SyntaxKind::ARROW_EXPR])
+    }
 }
 
 fn is_conditional(expr: &Expr) -> bool {
@@ -85,6 +85,10 @@ impl CstRule for NoConstantCondition {
 
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[IF_STMT, DO_WHILE_STMT, WHILE_STMT, COND_EXPR, FOR_STMT])
+    }
 }
 
 rule_tests! {
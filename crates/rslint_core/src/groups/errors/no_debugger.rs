@@ -36,6 +36,10 @@ impl CstRule for NoDebugger {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[SyntaxKind::DEBUGGER_STMT])
+    }
 }
 
 rule_tests! {
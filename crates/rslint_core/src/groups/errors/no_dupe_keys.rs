@@ -70,6 +70,10 @@ impl CstRule for NoDupeKeys {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[SyntaxKind::OBJECT_EXPR])
+    }
 }
 
 rule_tests! {
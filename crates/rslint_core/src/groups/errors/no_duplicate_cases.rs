@@ -74,6 +74,10 @@ impl CstRule for NoDuplicateCases {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[SyntaxKind::SWITCH_STMT])
+    }
 }
 
 rule_tests! {
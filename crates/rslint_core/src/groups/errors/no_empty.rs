@@ -98,6 +98,10 @@ impl CstRule for NoEmpty {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[BLOCK_STMT, SWITCH_STMT])
+    }
 }
 
 rule_tests! {
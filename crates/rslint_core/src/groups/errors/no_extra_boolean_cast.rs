@@ -81,6 +81,10 @@ impl CstRule for NoExtraBooleanCast {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[UNARY_EXPR, CALL_EXPR])
+    }
 }
 
 fn reason_labels(builder: Diagnostic, reason: Reason) -> Diagnostic {
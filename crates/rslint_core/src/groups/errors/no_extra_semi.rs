@@ -26,6 +26,7 @@ declare_lint! {
     NoExtraSemi,
     errors,
     tags(Recommended),
+    fixes(Always),
     "no-extra-semi"
 }
 
@@ -48,16 +49,20 @@ impl CstRule for NoExtraSemi {
                 .parent()
                 .map_or(true, |parent| !ALLOWED.contains(&parent.kind()))
         {
-            ctx.fix().delete(node);
             let err = ctx
                 .err(self.name(), "unnecessary semicolon")
                 .primary(node, "")
                 .suggestion(node, "delete the semicolon", "", Applicability::Always);
 
             ctx.add_err(err);
+            ctx.fix().delete(node);
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[EMPTY_STMT])
+    }
 }
 
 rule_tests! {
@@ -132,6 +132,10 @@ impl CstRule for NoInnerDeclarations {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[FN_DECL, VAR_DECL])
+    }
 }
 
 rule_tests! {
@@ -208,6 +208,10 @@ impl CstRule for NoIrregularWhitespace {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[])
+    }
 }
 
 impl NoIrregularWhitespace {
@@ -26,6 +26,7 @@ declare_lint! {
     NoNewSymbol,
     errors,
     tags(Recommended),
+    fixes(MaybeIncorrect),
     "no-new-symbol",
 }
 
@@ -49,11 +50,16 @@ impl CstRule for NoNewSymbol {
                 ctx.add_err(err);
                 ctx.fix()
                     .delete(new_expr.new_token()?)
-                    .eat_trailing_whitespace(new_expr.new_token()?);
+                    .eat_trailing_whitespace(new_expr.new_token()?)
+                    .applicability(Applicability::MaybeIncorrect);
             }
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[NEW_EXPR])
+    }
 }
 
 rule_tests! {
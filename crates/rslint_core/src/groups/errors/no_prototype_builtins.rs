@@ -67,6 +67,10 @@ impl CstRule for NoPrototypeBuiltins {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[SyntaxKind::CALL_EXPR])
+    }
 }
 
 fn suggestion(prop: String, object: String, expr: CallExpr, err: Diagnostic) -> Diagnostic {
@@ -1,3 +1,4 @@
+use crate::cfg::ControlFlowGraph;
 use crate::rule_prelude::*;
 use ast::*;
 use SyntaxKind::*;
@@ -170,7 +171,7 @@ impl CstRule for NoSetterReturn {
                 let setter = node.to::<Setter>();
                 if let Some(body) = setter.body() {
                     if let Some(key) = setter.key() {
-                        self.check_stmts(key.syntax(), body.syntax(), body.stmts(), ctx);
+                        self.check_body(key.syntax(), body.syntax(), ctx);
                     }
                 }
             }
@@ -178,6 +179,10 @@ impl CstRule for NoSetterReturn {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[CALL_EXPR, SETTER])
+    }
 }
 
 impl NoSetterReturn {
@@ -194,24 +199,24 @@ impl NoSetterReturn {
                 }
                 match literal_prop.value()? {
                     Expr::FnExpr(decl) => {
-                        self.check_stmts(key, decl.body()?.syntax(), decl.body()?.stmts(), ctx);
+                        self.check_body(key, decl.body()?.syntax(), ctx);
                     }
                     Expr::ArrowExpr(arrow) => {
                         if let ExprOrBlock::Block(block) = arrow.body()? {
-                            self.check_stmts(key, block.syntax(), block.stmts(), ctx);
+                            self.check_body(key, block.syntax(), ctx);
                         }
                     }
                     _ => {}
                 }
             }
             ObjectProp::Setter(setter) => {
-                self.check_stmts(key, setter.body()?.syntax(), setter.body()?.stmts(), ctx);
+                self.check_body(key, setter.body()?.syntax(), ctx);
             }
             ObjectProp::Method(method) => {
                 if method.name()?.text() != "set" {
                     return None;
                 }
-                self.check_stmts(key, method.body()?.syntax(), method.body()?.stmts(), ctx);
+                self.check_body(key, method.body()?.syntax(), ctx);
             }
             _ => {}
         }
@@ -219,14 +224,14 @@ impl NoSetterReturn {
         None
     }
 
-    fn check_stmts(
-        &self,
-        key: &SyntaxNode,
-        body: &SyntaxNode,
-        mut stmts: impl Iterator<Item = Stmt>,
-        ctx: &mut RuleCtx,
-    ) {
-        if stmts.any(|stmt| self.stmt_returns_value(&stmt)) {
+    fn check_body(&self, key: &SyntaxNode, body: &SyntaxNode, ctx: &mut RuleCtx) {
+        let cfg = ControlFlowGraph::new(body);
+        let returns_value = cfg.reachable_nodes().any(|node| {
+            node.try_to::<ReturnStmt>()
+                .map_or(false, |stmt| stmt.value().is_some())
+        });
+
+        if returns_value {
             let err = ctx
                 .err(
                     self.name(),
@@ -239,32 +244,6 @@ impl NoSetterReturn {
             ctx.add_err(err);
         }
     }
-
-    /// Return `true` if a stmt returns a value.
-    fn stmt_returns_value(&self, stmt: &Stmt) -> bool {
-        match stmt {
-            Stmt::IfStmt(if_stmt) => {
-                let cons_result = if_stmt
-                    .cons()
-                    .map_or(false, |cons| self.stmt_returns_value(&cons));
-                let alt_result = if_stmt
-                    .alt()
-                    .map_or(false, |alt| self.stmt_returns_value(&alt));
-                cons_result || alt_result
-            }
-            Stmt::BlockStmt(block) => block.stmts().any(|stmt| self.stmt_returns_value(&stmt)),
-            Stmt::ReturnStmt(stmt) => stmt.value().is_some(),
-            Stmt::SwitchStmt(switch) => switch.cases().any(|case| match case {
-                SwitchCase::CaseClause(clause) => {
-                    clause.cons().any(|s| self.stmt_returns_value(&s))
-                }
-                SwitchCase::DefaultClause(clause) => {
-                    clause.cons().any(|s| self.stmt_returns_value(&s))
-                }
-            }),
-            _ => false,
-        }
-    }
 }
 
 rule_tests! {
@@ -354,6 +333,19 @@ rule_tests! {
             }
         });
         ",
+        "
+        let bar = {
+            set foo(val) {
+                for (const a of b) {
+                    try {
+                        a();
+                    } finally {
+                        return a;
+                    }
+                }
+            }
+        };
+        ",
     },
     ok: {
         "({ set foo(val) { return; } })",
@@ -362,5 +354,8 @@ rule_tests! {
         "(class { set foo(val) { if (val) { return; } else { return; } return; } })",
         "class A { set foo(val) { try {} catch(e) { return; } } }",
         "Object.defineProperty(foo, 'bar', { set(val) { return; } })",
+        "({ set foo(val) { return; return 42; } })",
+        "({ set foo(val) { if (false) { return 42; } } })",
+        "({ set foo(val) { function bar() { return 42; } } })",
     },
 }
@@ -44,6 +44,10 @@ impl CstRule for NoSparseArrays {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[SyntaxKind::ARRAY_EXPR])
+    }
 }
 
 rule_tests! {
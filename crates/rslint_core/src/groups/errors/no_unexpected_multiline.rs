@@ -174,6 +174,10 @@ impl CstRule for NoUnexpectedMultiline {
 
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[CALL_EXPR, TEMPLATE, BRACKET_EXPR, BIN_EXPR])
+    }
 }
 
 fn has_linebreak_after(siblings: impl Iterator<Item = SyntaxElement>) -> bool {
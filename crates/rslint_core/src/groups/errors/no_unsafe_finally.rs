@@ -1,4 +1,6 @@
+use crate::cfg::ControlFlowGraph;
 use crate::rule_prelude::*;
+use std::collections::HashSet;
 use SyntaxKind::*;
 
 declare_lint! {
@@ -52,28 +54,66 @@ declare_lint! {
 
 pub const CONTROL_FLOW_STMT: [SyntaxKind; 4] = [BREAK_STMT, CONTINUE_STMT, THROW_STMT, RETURN_STMT];
 
+/// The nodes whose bodies have their own control flow graph.
+const FUNCTIONS: [SyntaxKind; 7] = [
+    FN_DECL,
+    FN_EXPR,
+    ARROW_EXPR,
+    METHOD,
+    GETTER,
+    SETTER,
+    CONSTRUCTOR,
+];
+
 #[typetag::serde]
 impl CstRule for NoUnsafeFinally {
-    fn check_node(&self, node: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
-        if CONTROL_FLOW_STMT.contains(&node.kind())
-            && node.parent()?.parent()?.is::<ast::Finalizer>()
-        {
-            self.output(node, ctx);
+    fn check_root(&self, root: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
+        // the graph of a body is only built once, and only if a `finally` block is directly in it
+        let mut seen = HashSet::new();
+        let bodies = root
+            .descendants()
+            .filter(|it| it.kind() == FINALIZER)
+            .map(|finalizer| {
+                finalizer
+                    .ancestors()
+                    .find(|it| FUNCTIONS.contains(&it.kind()))
+                    .unwrap_or_else(|| root.clone())
+            })
+            .filter(|body| seen.insert(body.clone()))
+            .collect::<Vec<_>>();
+
+        for body in bodies {
+            self.check_body(&body, ctx);
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[])
+    }
 }
 
 impl NoUnsafeFinally {
-    fn output(&self, node: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
-        let parent = if node.parent()?.kind() == FINALIZER {
-            node.parent()?
-        } else {
-            node.parent()?.parent()?
-        };
+    fn check_body(&self, node: &SyntaxNode, ctx: &mut RuleCtx) {
+        let cfg = ControlFlowGraph::new(node);
+        // statements in a finally block are in the graph once for every way the block is entered
+        let mut jumps = cfg
+            .reachable_nodes()
+            .filter(|it| {
+                CONTROL_FLOW_STMT.contains(&it.kind()) && !cfg.escaped_finalizers(it).is_empty()
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        jumps.sort_by_key(|it| it.text_range().start());
+        jumps.dedup();
 
-        let try_stmt = parent.parent()?.to::<ast::TryStmt>();
+        for jump in jumps {
+            let try_stmt = cfg.escaped_finalizers(&jump)[0].to::<ast::TryStmt>();
+            self.output(&jump, try_stmt, ctx);
+        }
+    }
 
+    fn output(&self, node: &SyntaxNode, try_stmt: ast::TryStmt, ctx: &mut RuleCtx) -> Option<()> {
         let err = if let Some(control) = try_stmt
             .test()?
             .syntax()
@@ -161,6 +201,49 @@ rule_tests! {
                 return 5;
             }
         }
+        ",
+        "
+        function foo() {
+            try {
+                return 1;
+            } finally {
+                if (bar) {
+                    return 2;
+                }
+            }
+        }
+        ",
+        "
+        outer: for (;;) {
+            try {} finally {
+                for (;;) {
+                    break outer;
+                }
+            }
+        }
+        ",
+        "
+        function foo() {
+            function bar() {
+                try {} finally {
+                    return 1;
+                }
+            }
+            try {} finally {
+                throw 2;
+            }
+        }
+        ",
+        "
+        class A {
+            get a() {
+                return () => {
+                    try {} finally {
+                        return;
+                    }
+                };
+            }
+        }
         "
     },
     ok: {
@@ -172,6 +255,16 @@ rule_tests! {
                 return true;
             }
         }
+        ",
+        "
+        try {} finally {
+            for (;;) {
+                break;
+            }
+            function foo() {
+                return 5;
+            }
+        }
         "
     }
 }
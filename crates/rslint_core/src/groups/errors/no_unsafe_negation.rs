@@ -30,6 +30,7 @@ declare_lint! {
     NoUnsafeNegation,
     errors,
     tags(Recommended),
+    fixes(MaybeIncorrect),
     "no-unsafe-negation"
 }
 
@@ -73,13 +74,19 @@ impl CstRule for NoUnsafeNegation {
                             vec![1..2, no_op_text.len() + 2..no_op_text.len() + 3],
                         );
 
-                    ctx.fix().wrap(node.add_start(1), Wrapping::Parens);
                     ctx.add_err(err);
+                    ctx.fix()
+                        .wrap(node.add_start(1), Wrapping::Parens)
+                        .applicability(Applicability::MaybeIncorrect);
                 }
             }
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[BIN_EXPR])
+    }
 }
 
 rule_tests! {
@@ -69,9 +69,9 @@ impl CstRule for UseIsnan {
                     return None;
                 }
 
-                let opposite = if expr.lhs().filter(|e| e.text() == "NaN").is_some() {
+                let opposite = if expr.lhs().filter(is_nan).is_some() {
                     expr.rhs()?
-                } else if expr.rhs().filter(|e| e.text() == "NaN").is_some() {
+                } else if expr.rhs().filter(is_nan).is_some() {
                     expr.lhs()?
                 } else {
                     return None;
@@ -130,7 +130,7 @@ impl CstRule for UseIsnan {
                 // TODO: a suggestion for this
                 let stmt = node.to::<SwitchStmt>();
                 let expr = stmt.test()?.condition()?;
-                if expr.text() == "NaN" {
+                if is_nan(&expr) {
                     let err = ctx
                         .err(
                             self.name(),
@@ -146,7 +146,7 @@ impl CstRule for UseIsnan {
                 // TODO: suggestion for this
                 let case = node.to::<CaseClause>();
                 let expr = case.test()?;
-                if expr.text() == "NaN" {
+                if is_nan(&expr) {
                     let err = ctx
                         .err(self.name(), "a case with a test of `NaN` will never match")
                         .primary(expr.range(), "")
@@ -170,12 +170,11 @@ impl CstRule for UseIsnan {
 
                 let second_arg_is_nan = expr
                     .arguments()
-                    .map(|a| a.args().nth(1).filter(|x| x.text() == "NaN"))
-                    .flatten()
+                    .and_then(|a| a.args().nth(1).filter(is_nan))
                     .is_some();
 
                 if (is_indexof_static_prop(&callee)
-                    && expr.arguments()?.args().next()?.text() == "NaN"
+                    && is_nan(&expr.arguments()?.args().next()?)
                     && !is_index_call)
                     || (is_index_call && second_arg_is_nan)
                 {
@@ -194,6 +193,15 @@ impl CstRule for UseIsnan {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[BIN_EXPR, SWITCH_STMT, CASE_CLAUSE, CALL_EXPR])
+    }
+}
+
+/// Whether an expression is always `NaN`, such as `NaN` or `0 / 0`.
+fn is_nan(expr: &Expr) -> bool {
+    util::eval_const_expr(expr.clone()).map_or(false, |value| value.is_nan())
 }
 
 const INDEX_OF_NAMES: [&str; 2] = ["lastIndexOf", "indexOf"];
@@ -232,7 +240,10 @@ rule_tests! {
         "NaN <= \"abc\";",
         "\"abc\" <= NaN;",
         "NaN >= \"abc\";",
-        "\"abc\" >= NaN;"
+        "\"abc\" >= NaN;",
+        "foo === (NaN);",
+        "foo == 0 / 0;",
+        "foo !== +\"abc\";"
     },
     ok: {
         "var x = NaN;",
@@ -251,6 +262,8 @@ rule_tests! {
         "var x; if (x = NaN) { }",
         "foo.indexOf(NaN)",
         "foo.lastIndexOf(NaN)",
+        "foo === 0 / 1;",
+        "foo === NaN.toString();",
     }
 }
 
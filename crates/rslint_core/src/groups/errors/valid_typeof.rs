@@ -1,5 +1,5 @@
 use crate::rule_prelude::*;
-use ast::{BinExpr, Expr, Literal, UnaryExpr};
+use ast::{BinExpr, Expr, UnaryExpr};
 
 declare_lint! {
     /**
@@ -28,8 +28,8 @@ declare_lint! {
 
     /**
      * If this option is `true`, `typeof` expression can only be compared
-     * to valid string literals, or other `typeof` expressions, but
-     * can not be compared to any other value.
+     * to valid string literals, templates without substitutions, or other
+     * `typeof` expressions, but can not be compared to any other value.
      */
     pub require_string_literals: bool,
 }
@@ -77,14 +77,18 @@ impl CstRule for ValidTypeof {
             return None;
         };
 
-        let str_literal = cmp_value
-            .syntax()
-            .try_to::<Literal>()
-            .and_then(|lit| Some((lit.inner_string_text()?, lit.range())));
+        // the value is a string literal if it is a literal or template without substitutions
+        let is_literal = matches!(cmp_value, Expr::Literal(_) | Expr::Template(_));
+        let str_value = match util::eval_const_expr(cmp_value.clone()) {
+            Some(util::JsValue::String(value)) if is_literal || !self.require_string_literals => {
+                Some((value, cmp_value.range()))
+            }
+            _ => None,
+        };
 
         let (literal, literal_range) = if self.require_string_literals {
-            if let Some(lit) = str_literal {
-                lit
+            if let Some(value) = str_value {
+                value
             } else if is_typeof_expr(&cmp_value) {
                 return None;
             } else {
@@ -97,11 +101,10 @@ impl CstRule for ValidTypeof {
                 return None;
             }
         } else {
-            str_literal?
+            str_value?
         };
 
-        if !VALID_TYPES.iter().any(|ty| *ty == literal) {
-            let literal = String::from(literal);
+        if !VALID_TYPES.contains(&literal.as_str()) {
             let suggestion =
                 util::find_best_match_for_name(VALID_TYPES.iter().copied(), &literal, None);
 
@@ -122,6 +125,10 @@ impl CstRule for ValidTypeof {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[SyntaxKind::BIN_EXPR])
+    }
 }
 
 fn is_typeof_expr(expr: &Expr) -> bool {
@@ -137,13 +144,17 @@ rule_tests! {
         r#"typeof foo === "strnig""#,
         r#"typeof foo == "undefimed""#,
         r#"typeof bar != "nunber""#,
-        r#"typeof bar !== "fucntion""#
+        r#"typeof bar !== "fucntion""#,
+        "typeof foo === `strnig`",
+        r#"typeof foo === "str" + "nig""#
     },
     ok: {
         r#"typeof foo === "string""#,
         r#"typeof bar == "undefined""#,
         "typeof foo === baz",
         "typeof foo === 4",
-        "typeof bar === typeof qux"
+        "typeof bar === typeof qux",
+        "typeof foo === `string`",
+        r#"typeof foo === "num" + "ber""#
     }
 }
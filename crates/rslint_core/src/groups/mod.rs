@@ -1,27 +1,40 @@
 //! All of the groups of built in rules in the linter.
 
 pub mod errors;
+pub mod project;
 pub mod regex;
 pub mod style;
 
 pub use errors::errors;
+pub use project::project;
 pub use regex::regex;
 pub use style::style;
 
 /// Macro for easily making a rule group hashmap.
 /// This will call `::new()` on each rule.  
+///
+/// Groups are made of [`CstRule`](crate::CstRule)s unless the rule trait is given
+/// after the group name, such as `project, ProjectRule; rule::Rule`.
 #[macro_export]
 macro_rules! group {
-    ($(#[$description:meta])* $groupname:ident, $($path:ident::$rule:ident),* $(,)?) => {
-        use $crate::CstRule;
+    ($(#[$description:meta])* $groupname:ident, $rule_trait:ident; $($path:ident::$rule:ident),* $(,)?) => {
+        use $crate::$rule_trait;
         $(
             mod $path;
             pub use $path::$rule;
         )*
 
         $(#[$description])*
-        pub fn $groupname() -> Vec<Box<dyn CstRule>> {
-            vec![$(Box::new($rule::new()) as Box<dyn CstRule>),*]
+        pub fn $groupname() -> Vec<Box<dyn $rule_trait>> {
+            vec![$(Box::new($rule::new()) as Box<dyn $rule_trait>),*]
+        }
+    };
+    ($(#[$description:meta])* $groupname:ident, $($path:ident::$rule:ident),* $(,)?) => {
+        $crate::group! {
+            $(#[$description])*
+            $groupname,
+            CstRule;
+            $($path::$rule),*
         }
     };
 }
@@ -0,0 +1,14 @@
+//! Rules which check a whole project instead of single files.
+
+use crate::group;
+
+group! {
+    /// Rules which check a whole project instead of single files.
+    /// These rules run after every file has been parsed and may report problems in any file.
+    project,
+    ProjectRule;
+    no_duplicate_module_names::NoDuplicateModuleNames,
+    no_import_cycles::NoImportCycles,
+    no_unresolved_imports::NoUnresolvedImports,
+    no_unused_exports::NoUnusedExports,
+}
@@ -0,0 +1,118 @@
+use crate::rule_prelude::*;
+use crate::{Project, ProjectCtx, ProjectRule};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+declare_lint! {
+    /**
+    Disallow files in the same directory which are imported with the same module specifier.
+
+    Module specifiers usually leave out the extension of the file, bundlers and TypeScript
+    then try every extension in a fixed order to resolve the module. If there are two files
+    with the same name and a different extension in a directory, only one of them can ever be imported
+    that way, and which one depends on the tool resolving it.
+
+    Declaration files (`.d.ts`) are not considered duplicates of the file they declare.
+
+    ## Incorrect Code Examples
+
+    A directory containing both `util.js` and `util.ts`, where `import "./util"` may import either of them.
+    */
+    #[derive(Default)]
+    NoDuplicateModuleNames,
+    project,
+    "no-duplicate-module-names"
+}
+
+const MODULE_EXTENSIONS: [&str; 6] = ["js", "jsx", "mjs", "cjs", "ts", "tsx"];
+
+#[typetag::serde]
+impl ProjectRule for NoDuplicateModuleNames {
+    fn check_project(&self, project: &Project, ctx: &mut ProjectCtx) {
+        let mut modules: BTreeMap<PathBuf, Vec<(&Path, usize)>> = BTreeMap::new();
+        for file in project.files() {
+            let path = match file.file.path.as_deref() {
+                Some(path) => path,
+                None => continue,
+            };
+            if !path
+                .extension()
+                .map_or(false, |ext| MODULE_EXTENSIONS.iter().any(|x| ext == *x))
+            {
+                continue;
+            }
+            modules
+                .entry(path.with_extension(""))
+                .or_default()
+                .push((path, file.file.id));
+        }
+
+        for (_, mut files) in modules {
+            files.sort_by_key(|(path, _)| *path);
+            let (first, rest) = match files.split_first() {
+                Some(split) => split,
+                None => continue,
+            };
+
+            // the files may not have their source, so the diagnostic is about the whole file and names both paths
+            for (path, file_id) in rest {
+                let err = ctx
+                    .err(
+                        *file_id,
+                        self.name(),
+                        format!(
+                            "`{}` has the same module specifier as `{}`",
+                            path.display(),
+                            first.0.display()
+                        ),
+                    )
+                    .footer_note("importing either file without its extension is ambiguous")
+                    .footer_help("rename one of the files or merge them into a single module");
+                ctx.add_err(err);
+            }
+        }
+    }
+
+    fn needs_trees(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_project_rule, File, ProjectFile};
+    use rslint_parser::FileKind;
+
+    fn lint(paths: &[&str]) -> Vec<Diagnostic> {
+        let files = paths
+            .iter()
+            .enumerate()
+            .map(|(id, path)| {
+                let mut file = File::from_string("", FileKind::Script, path);
+                file.path = Some(PathBuf::from(path));
+                file.id = id;
+                file
+            })
+            .collect::<Vec<_>>();
+        let project = Project::new(files.iter().map(|file| ProjectFile { file, root: None }));
+        run_project_rule(&NoDuplicateModuleNames::new(), &project, false)
+    }
+
+    #[test]
+    fn duplicates() {
+        let diagnostics = lint(&["src/a.js", "src/a.ts", "src/a.tsx", "src/b.js"]);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| d.file_id == 1 || d.file_id == 2));
+        assert!(diagnostics.iter().all(|d| d.primary.is_none()));
+        assert_eq!(
+            diagnostics[0].title,
+            "`src/a.ts` has the same module specifier as `src/a.js`"
+        );
+    }
+
+    #[test]
+    fn no_duplicates() {
+        assert!(lint(&["src/a.js", "src/a.d.ts", "lib/a.js", "src/a.json"]).is_empty());
+    }
+}
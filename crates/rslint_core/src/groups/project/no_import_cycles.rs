@@ -0,0 +1,100 @@
+use crate::rule_prelude::*;
+use crate::{Project, ProjectCtx, ProjectRule};
+
+declare_lint! {
+    /**
+    Disallow modules which import each other in a cycle.
+
+    When modules import each other in a cycle, one of them is always evaluated before the modules it imports
+    have finished evaluating. Any binding it uses from them while it is evaluated is then `undefined` or in
+    its temporal dead zone, which causes errors depending on which module of the cycle is imported first.
+    Cycles also make it harder to understand and split up code.
+
+    Import declarations, `export ... from` declarations and `require()` calls are part of cycles. `import type`
+    declarations and `import()` expressions are not, because they do not evaluate the imported module right away.
+
+    Each cycle is reported once, on the import of the first file of the cycle.
+
+    ## Incorrect Code Examples
+
+    A file `a.js` with `import { b } from "./b"` and a file `b.js` with `import { a } from "./a"`.
+    */
+    #[derive(Default)]
+    NoImportCycles,
+    project,
+    "no-import-cycles"
+}
+
+#[typetag::serde]
+impl ProjectRule for NoImportCycles {
+    fn check_project(&self, project: &Project, ctx: &mut ProjectCtx) {
+        let graph = project.module_graph();
+        for cycle in graph.cycles() {
+            let modules = cycle
+                .iter()
+                .filter_map(|(file_id, idx)| Some((graph.module(*file_id)?, *idx)))
+                .collect::<Vec<_>>();
+            let (first, idx) = match modules.first() {
+                Some(first) => *first,
+                None => continue,
+            };
+
+            let path = modules
+                .iter()
+                .chain(std::iter::once(&(first, idx)))
+                .map(|(module, _)| module.path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            let message = if modules.len() == 1 {
+                "this module imports itself".to_string()
+            } else {
+                format!(
+                    "this import is part of a cycle of {} modules",
+                    modules.len()
+                )
+            };
+
+            let err = ctx
+                .err(first.file_id, self.name(), message)
+                .primary(
+                    first.imports[idx].range,
+                    "this import leads back to this module",
+                )
+                .footer_note(format!("the import cycle is: {}", path));
+            ctx.add_err(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempProject;
+
+    #[test]
+    fn cycles() {
+        let project = TempProject::new(&[
+            ("a.js", "import './b';"),
+            ("b.js", "export * from './c';"),
+            ("c.js", "const a = require('./a');"),
+            ("d.js", "import './d';"),
+            ("e.js", "import './a';"),
+        ]);
+        let diagnostics = project.lint(&NoImportCycles::new());
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].file_id, 0);
+        assert_eq!(diagnostics[1].file_id, 3);
+    }
+
+    #[test]
+    fn lazy_imports() {
+        let project = TempProject::new(&[
+            ("a.ts", "import type { B } from './b'; export type A = 1;"),
+            (
+                "b.ts",
+                "import { A } from './a'; export type B = 1; import('./a');",
+            ),
+        ]);
+        assert!(project.lint(&NoImportCycles::new()).is_empty());
+    }
+}
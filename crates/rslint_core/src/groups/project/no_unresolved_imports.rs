@@ -0,0 +1,109 @@
+use crate::modules::{ImportKind, Resolution};
+use crate::rule_prelude::*;
+use crate::{Project, ProjectCtx, ProjectRule};
+
+declare_lint! {
+    /**
+    Disallow imports of modules which can not be found.
+
+    Import declarations, `export ... from` declarations, `require()` calls and `import()` expressions with
+    a string literal are resolved from the importing file the same way as Node and TypeScript resolve them.
+    Relative imports must point to a file (with or without an extension) or to a directory with an index file.
+    Packages are looked up in the `node_modules` directories of the file and its ancestors, using the package's
+    `exports` field, or its `main` field if it has none. Node builtins such as `fs` and URLs are never reported.
+
+    Nothing is fetched from the network, packages which are not installed are reported.
+
+    ## Incorrect Code Examples
+
+    An import of `./utils` in a directory without a `utils.js`, `utils.ts` or `utils/index.js` file.
+    */
+    #[serde(default)]
+    #[derive(Default)]
+    NoUnresolvedImports,
+    project,
+    "no-unresolved-imports",
+    /// Module specifiers which are not checked, a specifier ending with `*` ignores
+    /// every specifier starting with the text before it (for example `@generated/*`).
+    pub ignore: Vec<String>
+}
+
+impl NoUnresolvedImports {
+    fn is_ignored(&self, specifier: &str) -> bool {
+        self.ignore
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => specifier.starts_with(prefix),
+                None => specifier == pattern,
+            })
+    }
+}
+
+#[typetag::serde]
+impl ProjectRule for NoUnresolvedImports {
+    fn check_project(&self, project: &Project, ctx: &mut ProjectCtx) {
+        let graph = project.module_graph();
+        for module in graph.modules() {
+            for import in &module.imports {
+                if import.resolution != Resolution::Unresolved || self.is_ignored(&import.specifier)
+                {
+                    continue;
+                }
+
+                let is_package = !import.specifier.starts_with('.')
+                    && !std::path::Path::new(&import.specifier).is_absolute();
+                let mut err = if is_package {
+                    ctx.err(
+                        module.file_id,
+                        self.name(),
+                        format!("cannot find the package `{}`", import.specifier),
+                    )
+                    .primary(import.range, "this package is not installed")
+                    .footer_help(
+                        "install the package, or add it to the `ignore` option if it is provided by a build tool",
+                    )
+                } else {
+                    ctx.err(
+                        module.file_id,
+                        self.name(),
+                        format!("cannot resolve `{}`", import.specifier),
+                    )
+                    .primary(import.range, "no file exists at this path")
+                };
+                if import.kind == ImportKind::DynamicImport {
+                    err = err.footer_note("this module is only loaded when the `import()` expression runs, which then fails");
+                }
+                ctx.add_err(err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempProject;
+
+    #[test]
+    fn unresolved() {
+        let project = TempProject::new(&[
+            (
+                "src/a.js",
+                "import './b'; import 'fs'; import './nope'; const x = require('missing-pkg'); import('./gone');",
+            ),
+            ("src/b.ts", ""),
+        ]);
+        let diagnostics = project.lint(&NoUnresolvedImports::new());
+        assert_eq!(diagnostics.len(), 3);
+        assert!(diagnostics.iter().all(|d| d.file_id == 0));
+    }
+
+    #[test]
+    fn ignored() {
+        let project = TempProject::new(&[("a.js", "import '@generated/foo'; import 'virtual:x';")]);
+        let rule = NoUnresolvedImports {
+            ignore: vec!["@generated/*".to_string(), "virtual:x".to_string()],
+        };
+        assert!(project.lint(&rule).is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::modules::ImportedNames;
+use crate::rule_prelude::*;
+use crate::{Project, ProjectCtx, ProjectRule};
+use std::collections::HashSet;
+
+declare_lint! {
+    /**
+    Disallow exports which are never imported by another module.
+
+    Exports which nothing imports are dead code which can usually be removed, or made local to the module.
+    The rule only checks modules which are imported by at least one other file of the project, so entry points
+    and modules imported by files which are not linted are not reported.
+
+    Namespace imports (`import * as ns`), `export * from`, `require()` calls and `import()` expressions
+    may use any export of the module they import, so every export of a module imported that way is considered used.
+
+    ## Incorrect Code Examples
+
+    A file `a.js` with `export const a = 1; export const b = 2;`, which is only imported by
+    `import { a } from "./a"` in another file.
+    */
+    #[derive(Default)]
+    NoUnusedExports,
+    project,
+    "no-unused-exports"
+}
+
+#[typetag::serde]
+impl ProjectRule for NoUnusedExports {
+    fn check_project(&self, project: &Project, ctx: &mut ProjectCtx) {
+        let graph = project.module_graph();
+        'modules: for module in graph.modules() {
+            let mut imported = false;
+            let mut used = HashSet::new();
+            for (_, import) in graph.importers(module.file_id) {
+                imported = true;
+                match &import.names {
+                    ImportedNames::All => continue 'modules,
+                    ImportedNames::Names(names) => used.extend(names),
+                    ImportedNames::Nothing => {}
+                }
+            }
+            if !imported {
+                continue;
+            }
+
+            let mut reported = HashSet::new();
+            for export in &module.exports {
+                if used.contains(&export.name) || !reported.insert(&export.name) {
+                    continue;
+                }
+                let message = if export.name == "default" {
+                    "the default export is never imported".to_string()
+                } else {
+                    format!("`{}` is exported but never imported", export.name)
+                };
+                let err = ctx
+                    .err(module.file_id, self.name(), message)
+                    .primary(export.range, "this export is not used by any other module")
+                    .footer_help(
+                        "remove the export, or the code if it is not used in this module either",
+                    );
+                ctx.add_err(err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempProject;
+
+    #[test]
+    fn unused() {
+        let project = TempProject::new(&[
+            (
+                "a.js",
+                "import d, { b } from './b'; export { c } from './c';",
+            ),
+            (
+                "b.js",
+                "export default 1; export const b = 1, unused = 2; export function f() {}",
+            ),
+            ("c.js", "const c = 1, x = 2; export { c, x as y };"),
+        ]);
+        let diagnostics = project.lint(&NoUnusedExports::new());
+        let mut names = diagnostics
+            .iter()
+            .map(|d| (d.file_id, d.title.clone()))
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                (1, "`f` is exported but never imported".to_string()),
+                (1, "`unused` is exported but never imported".to_string()),
+                (2, "`y` is exported but never imported".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn namespace_imports() {
+        let project = TempProject::new(&[
+            (
+                "a.js",
+                "import * as b from './b'; const c = require('./c');",
+            ),
+            ("b.js", "export const b = 1;"),
+            ("c.js", "export const c = 1;"),
+        ]);
+        assert!(project.lint(&NoUnusedExports::new()).is_empty());
+    }
+}
@@ -1,21 +1,25 @@
 //! Rules which relate to regular expressions.
 
-use crate::group;
-use once_cell::sync::Lazy;
+use crate::util::{eval_const_expr, JsValue};
+use crate::{analysis::Analysis, group, RuleCtx};
 use rslint_errors::Span;
 use rslint_lexer::SyntaxKind;
 use rslint_parser::{
-    ast::{ArgList, Literal, LiteralKind},
+    ast::{ArgList, Expr, Literal, LiteralKind},
     AstNode, SyntaxNode, SyntaxNodeExt,
 };
 use rslint_regex::{validate_flags, EcmaVersion, Flags, Parser, Regex};
-use std::sync::Mutex;
-use std::{collections::HashMap, ops::Range};
+use std::ops::Range;
 
 type RegexResult = Result<(Regex, Range<usize>), (Range<usize>, String)>;
 
-pub(crate) static REGEX_MAP: Lazy<Mutex<HashMap<Range<usize>, RegexResult>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
+/// The regex held by a node, parsed once for every rule of the group.
+pub(crate) struct ParsedRegex;
+
+impl Analysis for ParsedRegex {
+    type Key = SyntaxNode;
+    type Value = Option<RegexResult>;
+}
 
 group! {
     /// Rules which relate to regular expressions.
@@ -24,17 +28,26 @@ group! {
     simplify_regex::SimplifyRegex
 }
 
-pub(crate) fn maybe_parse_and_store_regex(
-    node: &SyntaxNode,
-    file_id: usize,
-) -> Option<RegexResult> {
-    let mut map_handle = REGEX_MAP.lock().unwrap();
-    if let Some(r) = map_handle.get(&node.as_range()) {
-        return Some(r.to_owned());
+/// The kinds of nodes which may hold a regex, regex literals and `RegExp` constructor calls.
+pub(crate) const REGEX_KINDS: &[SyntaxKind] = &[
+    SyntaxKind::NEW_EXPR,
+    SyntaxKind::CALL_EXPR,
+    SyntaxKind::LITERAL,
+];
+
+pub(crate) fn maybe_parse_and_store_regex(node: &SyntaxNode, ctx: &RuleCtx) -> Option<RegexResult> {
+    ctx.analysis()
+        .get_or_insert_with::<ParsedRegex>(node.clone(), || {
+            collect_regex_from_node(node, ctx.file_id)
+        })
+}
+
+/// The value of an expression if it is a constant string.
+fn const_string(expr: Expr) -> Option<String> {
+    match eval_const_expr(expr)? {
+        JsValue::String(string) => Some(string),
+        _ => None,
     }
-    let r = collect_regex_from_node(node, file_id)?;
-    map_handle.insert(node.as_range(), r.clone());
-    Some(r)
 }
 
 fn collect_regex_from_node(node: &SyntaxNode, file_id: usize) -> Option<RegexResult> {
@@ -47,50 +60,45 @@ fn collect_regex_from_node(node: &SyntaxNode, file_id: usize) -> Option<RegexRes
                     .map(|x| x.args())
                     .into_iter()
                     .flatten();
-                let pat = args.next().and_then(|x| {
-                    Some((
-                        x.syntax().try_to::<Literal>()?.inner_string_text()?,
-                        x.range(),
-                    ))
-                });
-
-                let flags = args.next().and_then(|x| {
-                    Some((
-                        x.syntax().try_to::<Literal>()?.inner_string_text()?,
-                        x.range(),
-                    ))
-                });
-
-                if let Some((pat, range)) = pat {
-                    let range = range.as_range();
-                    let new_range = range.start + 1..range.end - 1;
-                    let flags = if let Some((flags, flag_range)) = flags {
-                        match validate_flags(&flags.to_string(), EcmaVersion::ES2021) {
-                            Ok(f) => f,
-                            Err(err) => {
-                                return Some(Err((flag_range.as_range(), err)));
-                            }
+                let pat = args.next()?;
+                let pattern = const_string(pat.clone())?;
+                let flags = args
+                    .next()
+                    .and_then(|arg| Some((const_string(arg.clone())?, arg.range())));
+                let flags = if let Some((flags, flag_range)) = flags {
+                    match validate_flags(&flags, EcmaVersion::ES2021) {
+                        Ok(f) => f,
+                        Err(err) => {
+                            return Some(Err((flag_range.as_range(), err)));
                         }
-                    } else {
-                        Flags::empty()
-                    };
-
-                    let pattern = &pat.to_string();
-                    let parser = Parser::new_from_pattern_and_flags(
-                        pattern,
-                        file_id,
-                        range.as_range().start + 1,
-                        EcmaVersion::ES2021,
-                        false,
-                        flags,
-                    );
-                    Some(match parser.parse() {
-                        Ok(r) => Ok((r, new_range)),
-                        Err(err) => Err((err.span.as_range(), err.message)),
-                    })
+                    }
                 } else {
-                    None
-                }
+                    Flags::empty()
+                };
+
+                let range = pat.range().as_range();
+                // offsets in the pattern are only offsets in the source if the pattern is written as is in a string
+                let exact = pat
+                    .syntax()
+                    .try_to::<Literal>()
+                    .and_then(|lit| lit.inner_string_text())
+                    .map_or(false, |text| text == pattern.as_str());
+                let offset = if exact { range.start + 1 } else { range.start };
+                let parser = Parser::new_from_pattern_and_flags(
+                    &pattern,
+                    file_id,
+                    offset,
+                    EcmaVersion::ES2021,
+                    false,
+                    flags,
+                );
+                Some(match parser.parse() {
+                    Ok(r) if exact => Ok((r, range.start + 1..range.end - 1)),
+                    // the spans of the regex do not match the source so it cannot be fixed
+                    Ok(_) => return None,
+                    Err(err) if exact => Err((err.span.as_range(), err.message)),
+                    Err(err) => Err((range, err.message)),
+                })
             } else {
                 None
             }
@@ -1,4 +1,4 @@
-use super::maybe_parse_and_store_regex;
+use super::{maybe_parse_and_store_regex, REGEX_KINDS};
 use crate::rule_prelude::*;
 
 declare_lint! {
@@ -6,7 +6,8 @@ declare_lint! {
     Disallow invalid regular expressions in literals and `RegExp` constructors.
 
     Invalid regex patterns in `RegExp` constructors are not caught until runtime. This
-    rule checks for calls to `RegExp` and validates the pattern given. This also checks regex literals
+    rule checks for calls to `RegExp` and validates the pattern given if it is a constant string, such as
+    `"a" + "("`. This also checks regex literals
     for errors as RSLint's parser currently does not validate regex patterns.
 
     ## Incorrect Code Examples
@@ -29,7 +30,7 @@ declare_lint! {
 #[typetag::serde]
 impl CstRule for NoInvalidRegexp {
     fn check_node(&self, node: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
-        let res = maybe_parse_and_store_regex(node, ctx.file_id)?;
+        let res = maybe_parse_and_store_regex(node, ctx)?;
         if let Err((range, string)) = res {
             let err = ctx
                 .err(self.name(), "invalid regex pattern")
@@ -39,6 +40,10 @@ impl CstRule for NoInvalidRegexp {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(REGEX_KINDS)
+    }
 }
 
 // no point in adding a lot of explicit tests because
@@ -50,6 +55,13 @@ rule_tests! {
         "RegExp('[')",
         "new RegExp('[')",
         "RegExp('a', 'h')",
+        "new RegExp('a' + '(')",
+        "RegExp('\\\\')",
+        "RegExp(`[`, 'g')",
     },
-    ok: {}
+    ok: {
+        "RegExp('\\\\d')",
+        "new RegExp('a' + '(?:b)')",
+        "RegExp('a', flags)",
+    }
 }
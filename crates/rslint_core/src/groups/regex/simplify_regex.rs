@@ -1,4 +1,4 @@
-use super::maybe_parse_and_store_regex;
+use super::{maybe_parse_and_store_regex, REGEX_KINDS};
 use crate::rule_prelude::*;
 use rslint_regex::Span;
 use rslint_regex::*;
@@ -55,13 +55,14 @@ declare_lint! {
     #[derive(Default)]
     SimplifyRegex,
     regex,
+    fixes(Always),
     "simplify-regex"
 }
 
 #[typetag::serde]
 impl CstRule for SimplifyRegex {
     fn check_node(&self, node: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
-        let (mut regex, range) = maybe_parse_and_store_regex(node, ctx.file_id)?.ok()?;
+        let (mut regex, range) = maybe_parse_and_store_regex(node, ctx)?.ok()?;
         let mut err = ctx
             .err(self.name(), "this regular expression can be simplified")
             .primary(range.clone(), "");
@@ -82,12 +83,16 @@ impl CstRule for SimplifyRegex {
 
         if !err.footers.is_empty() {
             let string = regex.node.to_string();
-            ctx.fix().replace(range, &string);
             err.title.push_str(&format!(" to `{}`", string));
             ctx.add_err(err);
+            ctx.fix().replace(range, &string);
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(REGEX_KINDS)
+    }
 }
 
 impl SimplifyRegex {
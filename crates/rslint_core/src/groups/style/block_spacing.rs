@@ -51,6 +51,7 @@ declare_lint! {
     #[derive(rslint_macros::Mergeable)]
     BlockSpacing,
     style,
+    fixes(Always),
     "block-spacing",
     /// The style of spacing, either "always" (default) to require one or more spaces, or
     /// "never" to disallow spaces
@@ -115,6 +116,10 @@ impl CstRule for BlockSpacing {
         }
         None
     }
+
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[SyntaxKind::SWITCH_STMT, SyntaxKind::BLOCK_STMT])
+    }
 }
 
 fn is_empty(node: &SyntaxNode) -> bool {
@@ -0,0 +1,296 @@
+//! Relinting of a file after an edit without linting the whole file again.
+//!
+//! The smallest block which contains the edit is reparsed with [`reparse_block`] and spliced into the
+//! previous tree. Rules are then only run on the new block and on the nodes above it, diagnostics which
+//! were emitted while checking nodes or tokens outside of the block are reused and shifted by the edit.
+//!
+//! Rules which look at nodes outside of the node they are checking may miss changes in the block,
+//! [`lint_file`] should be used when exact results are needed, such as before fixing.
+
+use crate::{
+    analysis::AnalysisCache,
+    directives::DECLARATOR,
+    dispatch::{check_subtree, RuleRun},
+    lint_file, run_rule_with_ctx,
+    semantic::LazySemanticModel,
+    skip_node, CstRule, DirectiveParser, File, LintResult, RuleCtx, RuleResult,
+};
+use crate::{apply_top_level_directives, directives::DirectiveResult, Diagnostic};
+use rslint_errors::SuggestionChange;
+use rslint_parser::{reparse_block, SyntaxKind, SyntaxNode, TextRange, TextSize};
+use rslint_text_edit::Indel;
+use std::cmp::Reverse;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Apply an edit to a file and relint it, reusing as much of a previous result of linting the file as possible.
+///
+/// Only the smallest block which contains the edit is reparsed, and node level rules are only run again on
+/// the nodes which changed. If no block can be reparsed on its own, or the edit adds or removes directives,
+/// the file is linted again in full.
+pub fn incrementally_relint<'s>(
+    previous: LintResult<'s>,
+    file: &mut File,
+    indel: &Indel,
+) -> LintResult<'s> {
+    let mut source = file.source.clone();
+    indel.apply(&mut source);
+    file.update_src(source);
+
+    let reparse = match reparse_block(
+        &previous.parsed,
+        indel.delete,
+        &indel.insert,
+        file.id,
+        file.syntax,
+    ) {
+        Some(reparse) => reparse,
+        None => return lint_file(file, previous.store, previous.verbose),
+    };
+
+    let old_block = previous.parsed.covering_element(reparse.old_range);
+    let new_block = match reparse.root.covering_element(reparse.new_range) {
+        rslint_parser::NodeOrToken::Node(node) if node.kind() == SyntaxKind::BLOCK_STMT => node,
+        _ => return lint_file(file, previous.store, previous.verbose),
+    };
+    if has_directives(old_block.as_node()) || has_directives(Some(&new_block)) {
+        return lint_file(file, previous.store, previous.verbose);
+    }
+
+    let edit = Edit {
+        old: reparse.old_range,
+        delta: i64::from(u32::from(reparse.new_range.len()))
+            - i64::from(u32::from(reparse.old_range.len())),
+    };
+    let root = reparse.root;
+
+    let parser_diagnostics = previous
+        .parser_diagnostics
+        .into_iter()
+        .filter(|d| {
+            d.primary
+                .as_ref()
+                .map_or(true, |p| edit.outside(&p.span.range))
+        })
+        .map(|d| edit.shift_diagnostic(d))
+        .collect();
+
+    let mut store = previous.store.clone();
+    let DirectiveResult {
+        directives,
+        diagnostics: mut directive_diagnostics,
+    } = DirectiveParser::new_with_store(root.clone(), file, previous.store).get_file_directives();
+    apply_top_level_directives(&directives, &mut store, &mut directive_diagnostics, file.id);
+
+    let src: Arc<str> = Arc::from(file.source.as_str());
+    let semantic = LazySemanticModel::new(root.clone());
+    let analysis = AnalysisCache::new();
+    let verbose = previous.verbose;
+    let mut old_results = previous.rule_results;
+
+    let rule_results = store
+        .rules
+        .into_iter()
+        .map(|rule| {
+            let ctx = RuleCtx {
+                file_id: file.id,
+                verbose,
+                diagnostics: vec![],
+                fixers: vec![],
+                src: src.clone(),
+                semantic: semantic.clone(),
+                analysis: analysis.clone(),
+            };
+            let result = match old_results.remove(rule.name()) {
+                Some(old) if old.has_origins() => {
+                    relint_rule(&*rule, old, ctx, &root, &new_block, &directives, &edit)
+                }
+                _ => run_rule_with_ctx(&*rule, &root, &directives, ctx),
+            };
+            (rule.name(), result)
+        })
+        .collect();
+
+    LintResult {
+        parser_diagnostics,
+        rule_results,
+        directive_diagnostics,
+        store: previous.store,
+        parsed: root,
+        file_id: file.id,
+        verbose,
+        fixed_code: None,
+    }
+}
+
+/// Whether a node contains a comment which could be a directive.
+fn has_directives(node: Option<&SyntaxNode>) -> bool {
+    node.map_or(false, |node| {
+        node.descendants_with_tokens()
+            .filter_map(|elem| elem.into_token())
+            .any(|token| token.kind() == SyntaxKind::COMMENT && token.text().contains(DECLARATOR))
+    })
+}
+
+/// Run a rule on the nodes which changed and reuse its other diagnostics.
+fn relint_rule(
+    rule: &dyn CstRule,
+    old: RuleResult,
+    ctx: RuleCtx,
+    root: &SyntaxNode,
+    block: &SyntaxNode,
+    directives: &[crate::Directive],
+    edit: &Edit,
+) -> RuleResult {
+    let mut kept = vec![];
+    for (diagnostic, origin) in old.diagnostics.into_iter().zip(old.origins) {
+        if origin.end() <= edit.old.start() || origin.start() >= edit.old.end() {
+            kept.push((edit.shift_range(origin), edit.shift_diagnostic(diagnostic)));
+        }
+    }
+
+    let mut run = RuleRun::new(rule, ctx);
+    run.check_root(root);
+
+    // the root is only checked by `check_root`, every other node from the root down to the block is checked again
+    let mut nodes = block
+        .ancestors()
+        .take_while(|node| node != root)
+        .collect::<Vec<_>>();
+    nodes.reverse();
+    let mut skipped = false;
+    for node in nodes {
+        if skip_node(directives, &node, rule) || node.kind() == SyntaxKind::ERROR {
+            skipped = true;
+            break;
+        }
+        if run.handles(node.kind()) {
+            run.check_node(&node);
+        }
+    }
+    if !skipped {
+        check_subtree(std::slice::from_mut(&mut run), block, directives);
+    }
+
+    let result = run.finish();
+    kept.extend(result.origins.into_iter().zip(result.diagnostics));
+    // match the order the diagnostics are emitted in when the whole file is linted
+    kept.sort_by_key(|(origin, _)| (origin.start(), Reverse(origin.len())));
+    let (origins, diagnostics) = kept.into_iter().unzip();
+
+    let mut result = RuleResult::new(diagnostics);
+    result.origins = origins;
+    result
+}
+
+/// A block of the old tree which was replaced by a block `delta` bytes longer.
+struct Edit {
+    old: TextRange,
+    delta: i64,
+}
+
+impl Edit {
+    fn outside(&self, range: &Range<usize>) -> bool {
+        range.end <= usize::from(self.old.start()) || range.start >= usize::from(self.old.end())
+    }
+
+    fn shift(&self, offset: usize) -> usize {
+        if offset >= usize::from(self.old.end()) {
+            (offset as i64 + self.delta) as usize
+        } else {
+            offset
+        }
+    }
+
+    fn shift_range(&self, range: TextRange) -> TextRange {
+        let start = self.shift(usize::from(range.start()));
+        let end = self.shift(usize::from(range.end()));
+        TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32))
+    }
+
+    fn shift_span(&self, range: &mut Range<usize>) {
+        *range = self.shift(range.start)..self.shift(range.end);
+    }
+
+    fn shift_diagnostic(&self, mut diagnostic: Diagnostic) -> Diagnostic {
+        if let Some(primary) = &mut diagnostic.primary {
+            self.shift_span(&mut primary.span.range);
+        }
+        for child in &mut diagnostic.children {
+            self.shift_span(&mut child.span.range);
+        }
+        for suggestion in &mut diagnostic.suggestions {
+            self.shift_span(&mut suggestion.span.range);
+            for label in &mut suggestion.labels {
+                self.shift_span(label);
+            }
+            if let SuggestionChange::Indels(indels) = &mut suggestion.substitution {
+                for indel in indels {
+                    indel.delete = self.shift_range(indel.delete);
+                }
+            }
+        }
+        if let Some(fix) = &mut diagnostic.fix {
+            for indel in &mut fix.indels {
+                indel.delete = self.shift_range(indel.delete);
+            }
+        }
+        diagnostic
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CstRuleStore;
+
+    /// Relint a file after an edit and check that the result is the same as linting the edited file in full.
+    fn check(src: &str, delete: (u32, u32), insert: &str, reparsed: bool) {
+        let store = CstRuleStore::new().builtins();
+        let mut file = File::from_string(src, rslint_parser::FileKind::Module, "test.js");
+        let previous = lint_file(&file, &store, false);
+
+        let indel = Indel::replace(
+            TextRange::new(delete.0.into(), delete.1.into()),
+            insert.to_string(),
+        );
+        let reparse = reparse_block(&previous.parsed, indel.delete, insert, file.id, file.syntax);
+        assert_eq!(reparse.is_some(), reparsed);
+        let incremental = incrementally_relint(previous, &mut file, &indel);
+        let full = lint_file(&file, &store, false);
+
+        assert_eq!(incremental.parsed.to_string(), file.source);
+        assert_eq!(
+            format!("{:#?}", incremental.parsed),
+            format!("{:#?}", full.parsed)
+        );
+        assert_eq!(incremental.parser_diagnostics, full.parser_diagnostics);
+        for (name, result) in &full.rule_results {
+            let relinted = &incremental.rule_results[name];
+            assert_eq!(relinted.diagnostics, result.diagnostics, "{}", name);
+            assert_eq!(relinted.origins, result.origins, "{}", name);
+        }
+    }
+
+    #[test]
+    fn edits_in_blocks() {
+        let src = "if (a) {\n  debugger;\n}\nfunction foo() {\n  if (b) {\n    x = 1;\n  }\n}\nif (c) { debugger; }\n";
+        // add an error inside of the block
+        check(src, (55, 55), "debugger; ", true);
+        // remove an error inside of the block, shifting the errors after it
+        check(src, (11, 20), "", true);
+        // edit a block without any errors in it
+        check(src, (55, 61), "y = 2 + 3;", true);
+    }
+
+    #[test]
+    fn falls_back_to_full_relint() {
+        let src = "if (a) { debugger; }\nif (b) { x; }\n";
+        // the edit is outside of any block
+        check(src, (0, 2), "while", false);
+        // the edit adds a directive
+        check(src, (30, 30), "// rslint-ignore\n", true);
+        // the block no longer parses on its own
+        check(src, (30, 31), "} {", false);
+    }
+}
@@ -0,0 +1,151 @@
+//! Isolation of panics so a bug in the parser or in a rule only affects a single file or rule.
+//!
+//! Panics are caught with [`catch_panic`] and reported as [`Severity::Bug`] diagnostics made by
+//! [`panic_diagnostic`], every other file and rule keeps being linted. A panic hook which reports panics
+//! and exits should let caught panics through, it can check [`is_catching_panics`] and give the location
+//! of the panic to the diagnostic with [`record_panic`].
+
+use crate::{Diagnostic, Severity};
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, AssertUnwindSafe, PanicInfo};
+
+/// Where bugs in the linter should be reported.
+pub const BUG_REPORT_URL: &str = "https://github.com/RDambrosio016/RSLint/issues/new?labels=ILE%2C+bug&template=internal-linter-error.md";
+
+thread_local! {
+    // how many calls of `catch_panic` the thread is in
+    static CATCHING: Cell<usize> = Cell::new(0);
+    static LOCATION: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// A panic caught by [`catch_panic`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panic {
+    pub message: String,
+    /// Where the panic happened, if the panic hook recorded it with [`record_panic`].
+    pub location: Option<String>,
+}
+
+/// Run a function and catch any panic in it.
+///
+/// The function must not leave anything it shares in a broken state if it panics, since it is assumed
+/// to be unwind safe.
+pub fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, Panic> {
+    CATCHING.with(|catching| catching.set(catching.get() + 1));
+    let res = catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|catching| catching.set(catching.get() - 1));
+
+    res.map_err(|payload| Panic {
+        message: payload_message(&*payload),
+        location: LOCATION.with(|location| location.borrow_mut().take()),
+    })
+}
+
+/// Whether a panic on the current thread would be caught by [`catch_panic`].
+pub fn is_catching_panics() -> bool {
+    CATCHING.with(|catching| catching.get() > 0)
+}
+
+/// Record where a panic happened so it can be included in the [`Panic`] caught by [`catch_panic`].
+///
+/// This is meant to be called by a panic hook.
+pub fn record_panic(info: &PanicInfo) {
+    let loc = info.location().map(|loc| loc.to_string());
+    LOCATION.with(|location| *location.borrow_mut() = loc);
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|msg| msg.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Make a bug diagnostic for a panic of a rule while linting a file, or of the rest of the linter, such as the
+/// parser, if `rule` is `None`.
+pub fn panic_diagnostic(file_id: usize, rule: Option<&str>, panic: &Panic) -> Diagnostic {
+    let culprit = match rule {
+        Some(rule) => format!("the rule `{}`", rule),
+        None => "the linter".to_string(),
+    };
+    let mut d = Diagnostic::new_with_code(
+        file_id,
+        Severity::Bug,
+        format!("{} panicked: {}", culprit, panic.message),
+        rule.map(|rule| rule.to_string()),
+    )
+    .primary(0usize..0, "while linting this file");
+    if let Some(location) = &panic.location {
+        d = d.footer_note(format!("the panic happened at {}", location));
+    }
+    d.footer_help(format!(
+        "this is a bug, we would appreciate a bug report: {}",
+        BUG_REPORT_URL
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule_prelude::*;
+    use crate::{lint_file, CstRuleStore, File};
+    use rslint_parser::FileKind;
+
+    // the rule is only made with `Box::default`, so the `new` declared with it is unused
+    #[allow(dead_code)]
+    mod rule {
+        use crate::rule_prelude::*;
+
+        declare_lint! {
+            #[derive(Default)]
+            PanicOnDebugger,
+            errors,
+            "panic-on-debugger"
+        }
+    }
+    use rule::PanicOnDebugger;
+
+    #[typetag::serde]
+    impl CstRule for PanicOnDebugger {
+        fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+            Some(&[SyntaxKind::DEBUGGER_STMT])
+        }
+
+        fn check_node(&self, _node: &SyntaxNode, _ctx: &mut RuleCtx) -> Option<()> {
+            panic!("found a debugger statement")
+        }
+    }
+
+    #[test]
+    fn catches_panics() {
+        let panic = catch_panic(|| panic!("oh no")).unwrap_err();
+        assert_eq!(panic.message, "oh no");
+        assert!(!is_catching_panics());
+        assert_eq!(catch_panic(|| 5), Ok(5));
+    }
+
+    #[test]
+    fn rule_panics_are_reported() {
+        let mut store = CstRuleStore::new().builtins();
+        store.load_rules(vec![Box::<PanicOnDebugger>::default() as Box<dyn CstRule>]);
+        let file = File::from_string(
+            "debugger;\ndebugger;\nif (a) {}",
+            FileKind::Script,
+            "test.js",
+        );
+        let result = lint_file(&file, &store, false);
+
+        let panicked = &result.rule_results["panic-on-debugger"].diagnostics;
+        assert_eq!(panicked.len(), 1);
+        assert_eq!(panicked[0].severity, Severity::Bug);
+        assert_eq!(
+            panicked[0].title,
+            "the rule `panic-on-debugger` panicked: found a debugger statement"
+        );
+        // other rules keep running on the file
+        assert_eq!(result.rule_results["no-debugger"].diagnostics.len(), 2);
+        assert_eq!(result.rule_results["no-empty"].diagnostics.len(), 1);
+    }
+}
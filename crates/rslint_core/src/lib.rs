@@ -22,6 +22,9 @@
 //! To run a single rule you can find the rule you want in the `groups` module and submodules within. Then
 //! to run a rule in full on a syntax tree you can use [`run_rule`].
 //!
+//! Rules which need to see every file of a project implement [`ProjectRule`] instead, they are run
+//! with [`lint_project`] once every file has been parsed.
+//!
 //! Rules can also be run on individual nodes using the functions on [`CstRule`].
 //! ⚠️ note however that many rules rely on checking tokens or the root and running on single nodes
 //! may yield incorrect results, you should only do this if you know about the rule's implementation.
@@ -29,32 +32,57 @@
 // FIXME: Workaround for https://github.com/GREsau/schemars/pull/65
 #![allow(clippy::field_reassign_with_default)]
 
+mod custom;
+mod dispatch;
 mod file;
+mod incremental;
+mod plugin;
+mod project;
 mod rule;
 mod store;
 mod testing;
 
+pub mod analysis;
 pub mod autofix;
+pub mod cfg;
 pub mod directives;
 pub mod groups;
+pub mod isolation;
+pub mod modules;
+pub mod pattern;
 pub mod rule_prelude;
+pub mod semantic;
 pub mod util;
 
 pub use self::{
+    custom::{syntax_kind_by_name, CustomRule, CustomRuleConfig, CustomRuleError, CUSTOM_GROUP},
     file::File,
-    rule::{CstRule, Inferable, Outcome, Rule, RuleCtx, RuleLevel, RuleResult, Tag},
-    store::CstRuleStore,
+    incremental::incrementally_relint,
+    plugin::{
+        get_plugin_rule, plugin_rules, register_plugin, registered_plugins, Plugin, PluginError,
+        PluginRule, PLUGIN_FUEL, PLUGIN_FUEL_PER_BYTE,
+    },
+    project::{
+        lint_project, run_project_rule, Project, ProjectCtx, ProjectFile, ProjectLintResult,
+    },
+    rule::{CstRule, Inferable, Outcome, ProjectRule, Rule, RuleCtx, RuleLevel, RuleResult, Tag},
+    store::{CstRuleStore, ProjectRuleStore},
 };
-pub use rslint_errors::{Diagnostic, Severity, Span};
+pub use rslint_errors::{Applicability, Diagnostic, Severity, Span};
 
 pub use crate::directives::{
     apply_top_level_directives, skip_node, Directive, DirectiveError, DirectiveErrorKind,
     DirectiveParser,
 };
 
+use analysis::AnalysisCache;
+use dispatch::{check_subtree, RuleRun};
 use dyn_clone::clone_box;
-use rslint_parser::{util::SyntaxNodeExt, SyntaxKind, SyntaxNode};
-use std::collections::HashMap;
+use isolation::{catch_panic, panic_diagnostic};
+use rslint_parser::{parse_with_syntax, SyntaxKind, SyntaxNode};
+use semantic::LazySemanticModel;
+use std::collections::BTreeMap;
+use std::ops::Range;
 use std::sync::Arc;
 
 /// The result of linting a file.
@@ -64,7 +92,7 @@ pub struct LintResult<'s> {
     /// Any diagnostics (errors, warnings, etc) emitted from the parser
     pub parser_diagnostics: Vec<Diagnostic>,
     /// The diagnostics emitted by each rule run
-    pub rule_results: HashMap<&'static str, RuleResult>,
+    pub rule_results: BTreeMap<&'static str, RuleResult>,
     /// Any warnings or errors emitted by the directive parser
     pub directive_diagnostics: Vec<DirectiveError>,
     pub store: &'s CstRuleStore,
@@ -89,6 +117,13 @@ impl LintResult<'_> {
             .chain(self.directive_diagnostics.iter().map(|x| &x.diagnostic))
     }
 
+    /// Whether the parser panicked on the file, in which case no rules were run and the tree is empty.
+    pub fn parser_panicked(&self) -> bool {
+        self.parser_diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Bug)
+    }
+
     /// The overall outcome of linting this file (failure, warning, success, etc)
     pub fn outcome(&self) -> Outcome {
         self.diagnostics().into()
@@ -96,25 +131,30 @@ impl LintResult<'_> {
 
     /// Attempt to automatically fix any fixable issues and return the fixed code.
     ///
-    /// This will not run if there are syntax errors unless `dirty` is set to true.
-    pub fn fix(&mut self, dirty: bool, file: &File) -> Option<String> {
-        if self
-            .parser_diagnostics
-            .iter()
-            .any(|x| x.severity == Severity::Error)
-            && !dirty
+    /// This will not run if there are syntax errors unless `dirty` is set to true, or if the parser panicked.
+    /// Fixes which may be incorrect are only applied if `unsafe_fixes` is true.
+    pub fn fix(&mut self, dirty: bool, file: &File, unsafe_fixes: bool) -> Option<String> {
+        if self.parser_panicked()
+            || self
+                .parser_diagnostics
+                .iter()
+                .any(|x| x.severity == Severity::Error)
+                && !dirty
         {
             None
         } else {
-            Some(autofix::recursively_apply_fixes(self, file))
+            Some(autofix::recursively_apply_fixes(self, file, unsafe_fixes))
         }
     }
 }
 
 /// Lint a file with a specific rule store.
+///
+/// Panics of the parser and of rules are caught and reported as [`Severity::Bug`] diagnostics, see [`isolation`].
 pub fn lint_file<'s>(file: &File, store: &'s CstRuleStore, verbose: bool) -> LintResult<'s> {
-    let (diagnostics, node) = file.parse_with_errors();
-    lint_file_inner(node, diagnostics, file, store, verbose)
+    let job = LintJob::new(file, store, verbose);
+    let results = job.run(0..job.rule_count());
+    job.finish(results)
 }
 
 /// used by lint_file and incrementally_relint to not duplicate code
@@ -125,49 +165,160 @@ pub(crate) fn lint_file_inner<'s>(
     store: &'s CstRuleStore,
     verbose: bool,
 ) -> LintResult<'s> {
-    let mut new_store = store.clone();
-    let directives::DirectiveResult {
-        directives,
-        diagnostics: mut directive_diagnostics,
-    } = { DirectiveParser::new_with_store(node.clone(), file, store).get_file_directives() };
-
-    apply_top_level_directives(
-        directives.as_slice(),
-        &mut new_store,
-        &mut directive_diagnostics,
-        file.id,
-    );
-
-    let src: Arc<str> = Arc::from(node.to_string());
-
-    // FIXME: Replace with thread pool
-    let results = new_store
-        .rules
-        .into_iter()
-        .map(|rule| {
-            (
-                rule.name(),
-                run_rule(
-                    &*rule,
-                    file.id,
-                    node.clone(),
-                    verbose,
-                    &directives,
-                    src.clone(),
-                ),
-            )
+    let job = LintJob::from_parsed(node, parser_diagnostics, file, store, verbose);
+    let results = job.run(0..job.rule_count());
+    job.finish(results)
+}
+
+/// Files of at least this many bytes have their rules split into more than one batch by [`LintJob::batches`].
+pub const PARALLEL_LINT_THRESHOLD: usize = 128 * 1024;
+
+/// A file which was parsed and had its directives applied, ready to have its rules run.
+///
+/// [`lint_file`] runs every rule at once, a large file can instead be linted on many threads by running
+/// [`batches`](Self::batches) of its rules with [`run`](Self::run) on different threads, then combining their
+/// results with [`finish`](Self::finish). Each batch walks its own root rebuilt from the green tree of the file,
+/// so threads do not share the cursor nodes of a tree.
+pub struct LintJob<'s> {
+    store: &'s CstRuleStore,
+    // the rules of the store after applying the top level directives of the file
+    rules: Vec<Box<dyn CstRule>>,
+    parsed: SyntaxNode,
+    parser_diagnostics: Vec<Diagnostic>,
+    directives: Vec<Directive>,
+    directive_diagnostics: Vec<DirectiveError>,
+    file_id: usize,
+    verbose: bool,
+    src: Arc<str>,
+    semantic: LazySemanticModel,
+    analysis: AnalysisCache,
+}
+
+impl<'s> LintJob<'s> {
+    /// Parse a file and apply its directives to the rules of a store.
+    ///
+    /// If the parser or the directive parser panics, the job gets an empty tree and no rules to run, and
+    /// the panic is reported as a parser diagnostic.
+    pub fn new(file: &File, store: &'s CstRuleStore, verbose: bool) -> Self {
+        let parsed = catch_panic(|| {
+            let (diagnostics, node) = file.parse_with_errors();
+            Self::from_parsed(node, diagnostics, file, store, verbose)
+        });
+        parsed.unwrap_or_else(|panic| {
+            let node = parse_with_syntax("", file.id, file.syntax).syntax();
+            let d = panic_diagnostic(file.id, None, &panic);
+            let mut job = Self::from_parsed(node, vec![d], file, store, verbose);
+            job.rules.clear();
+            job
         })
-        .collect();
-
-    LintResult {
-        parser_diagnostics,
-        rule_results: results,
-        directive_diagnostics,
-        store,
-        parsed: node,
-        file_id: file.id,
-        verbose,
-        fixed_code: None,
+    }
+
+    pub(crate) fn from_parsed(
+        node: SyntaxNode,
+        parser_diagnostics: Vec<Diagnostic>,
+        file: &File,
+        store: &'s CstRuleStore,
+        verbose: bool,
+    ) -> Self {
+        let mut new_store = store.clone();
+        let directives::DirectiveResult {
+            directives,
+            diagnostics: mut directive_diagnostics,
+        } = { DirectiveParser::new_with_store(node.clone(), file, store).get_file_directives() };
+
+        apply_top_level_directives(
+            directives.as_slice(),
+            &mut new_store,
+            &mut directive_diagnostics,
+            file.id,
+        );
+
+        Self {
+            store,
+            rules: new_store.rules,
+            src: Arc::from(node.to_string()),
+            semantic: LazySemanticModel::new(node.clone()),
+            analysis: AnalysisCache::new(),
+            parsed: node,
+            parser_diagnostics,
+            directives,
+            directive_diagnostics,
+            file_id: file.id,
+            verbose,
+        }
+    }
+
+    /// The number of rules which are run on the file.
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Split the rules into at most `max` batches of about the same size.
+    ///
+    /// Each batch costs a traversal of the file, therefore files smaller than [`PARALLEL_LINT_THRESHOLD`]
+    /// only get a single batch.
+    pub fn batches(&self, max: usize) -> Vec<Range<usize>> {
+        let len = self.rules.len();
+        if len == 0 {
+            return vec![];
+        }
+        let count = if self.src.len() < PARALLEL_LINT_THRESHOLD {
+            1
+        } else {
+            max.clamp(1, len)
+        };
+        let size = (len + count - 1) / count;
+        (0..len)
+            .step_by(size)
+            .map(|start| start..(start + size).min(len))
+            .collect()
+    }
+
+    /// Run a batch of the rules in a single traversal of the file.
+    ///
+    /// # Panics
+    /// Panics if the batch is out of the bounds of [`rule_count`](Self::rule_count).
+    pub fn run(&self, batch: Range<usize>) -> Vec<(&'static str, RuleResult)> {
+        let root = SyntaxNode::new_root(self.parsed.green().clone());
+        let mut runs = self.rules[batch]
+            .iter()
+            .map(|rule| {
+                let ctx = RuleCtx {
+                    file_id: self.file_id,
+                    verbose: self.verbose,
+                    diagnostics: vec![],
+                    fixers: vec![],
+                    src: self.src.clone(),
+                    semantic: self.semantic.clone(),
+                    analysis: self.analysis.clone(),
+                };
+                let mut run = RuleRun::new(&**rule, ctx);
+                run.check_root(&root);
+                run
+            })
+            .collect::<Vec<_>>();
+        // the tree is walked once for all rules of the batch rather than once per rule
+        check_subtree(&mut runs, &root, &self.directives);
+        runs.into_iter()
+            .map(|run| (run.rule.name(), run.finish()))
+            .collect()
+    }
+
+    /// Combine the results of every batch into the result of linting the file.
+    pub fn finish(
+        self,
+        results: impl IntoIterator<Item = (&'static str, RuleResult)>,
+    ) -> LintResult<'s> {
+        LintResult {
+            parser_diagnostics: self.parser_diagnostics,
+            rule_results: results.into_iter().collect(),
+            directive_diagnostics: self.directive_diagnostics,
+            store: self.store,
+            parsed: self.parsed,
+            file_id: self.file_id,
+            verbose: self.verbose,
+            fixed_code: None,
+        }
     }
 }
 
@@ -182,33 +333,49 @@ pub fn run_rule(
     verbose: bool,
     directives: &[Directive],
     src: Arc<str>,
+) -> RuleResult {
+    let semantic = LazySemanticModel::new(root.clone());
+    run_rule_with_semantic(rule, file_id, root, verbose, directives, src, semantic)
+}
+
+/// Run a single rule on an entire parsed file like [`run_rule`], sharing the semantic model of
+/// the file with other rules run on it.
+///
+/// # Panics
+/// Panics if `root`'s kind is not `SCRIPT` or `MODULE`
+pub fn run_rule_with_semantic(
+    rule: &dyn CstRule,
+    file_id: usize,
+    root: SyntaxNode,
+    verbose: bool,
+    directives: &[Directive],
+    src: Arc<str>,
+    semantic: LazySemanticModel,
 ) -> RuleResult {
     assert!(root.kind() == SyntaxKind::SCRIPT || root.kind() == SyntaxKind::MODULE);
-    let mut ctx = RuleCtx {
+    let ctx = RuleCtx {
         file_id,
         verbose,
         diagnostics: vec![],
-        fixer: None,
+        fixers: vec![],
         src,
+        semantic,
+        analysis: AnalysisCache::new(),
     };
+    run_rule_with_ctx(rule, &root, directives, ctx)
+}
 
-    rule.check_root(&root, &mut ctx);
-
-    root.descendants_with_tokens_with(&mut |elem| {
-        match elem {
-            rslint_parser::NodeOrToken::Node(node) => {
-                if skip_node(directives, &node, rule) || node.kind() == SyntaxKind::ERROR {
-                    return false;
-                }
-                rule.check_node(&node, &mut ctx);
-            }
-            rslint_parser::NodeOrToken::Token(tok) => {
-                let _ = rule.check_token(&tok, &mut ctx);
-            }
-        };
-        true
-    });
-    RuleResult::new(ctx.diagnostics, ctx.fixer)
+/// Run a single rule on an entire parsed file with a context made by the caller.
+pub(crate) fn run_rule_with_ctx(
+    rule: &dyn CstRule,
+    root: &SyntaxNode,
+    directives: &[Directive],
+    ctx: RuleCtx,
+) -> RuleResult {
+    let mut run = RuleRun::new(rule, ctx);
+    run.check_root(root);
+    check_subtree(std::slice::from_mut(&mut run), root, directives);
+    run.finish()
 }
 
 /// Get a rule by its kebab-case name.
@@ -221,7 +388,12 @@ pub fn get_rule_by_name(name: &str) -> Option<Box<dyn CstRule>> {
         .map(|rule| clone_box(&**rule))
 }
 
-/// Get a group's rules by the group name.
+/// Get a project rule by its kebab-case name.
+pub fn get_project_rule_by_name(name: &str) -> Option<Box<dyn ProjectRule>> {
+    ProjectRuleStore::new().builtins().get(name)
+}
+
+/// Get a group's rules by the group name, the name of a registered plugin is the group of its rules.
 // TODO: there should be a good way to not have to hardcode all of this
 pub fn get_group_rules_by_name(group_name: &str) -> Option<Vec<Box<dyn CstRule>>> {
     use groups::*;
@@ -230,7 +402,16 @@ pub fn get_group_rules_by_name(group_name: &str) -> Option<Vec<Box<dyn CstRule>>
         "errors" => errors(),
         "style" => style(),
         "regex" => regex(),
-        _ => return None,
+        _ => {
+            let rules = plugin_rules()
+                .into_iter()
+                .filter(|rule| rule.group() == group_name)
+                .collect::<Vec<_>>();
+            if rules.is_empty() {
+                return None;
+            }
+            rules
+        }
     })
 }
 
@@ -244,12 +425,24 @@ pub fn get_rule_suggestion(incorrect_rule_name: &str) -> Option<&str> {
     util::find_best_match_for_name(rules, incorrect_rule_name, None)
 }
 
+/// Get a suggestion for an incorrect project rule name, like [`get_rule_suggestion`].
+pub fn get_project_rule_suggestion(incorrect_rule_name: &str) -> Option<&str> {
+    let rules = ProjectRuleStore::new()
+        .builtins()
+        .rules
+        .into_iter()
+        .map(|rule| rule.name());
+    util::find_best_match_for_name(rules, incorrect_rule_name, None)
+}
+
 /// Get a rule and its documentation.
 ///
-/// This will always be `Some` for valid rule names and it will be an empty string
+/// This will always be `Some` for valid rule and project rule names and it will be an empty string
 /// if the rule has no docs
 pub fn get_rule_docs(rule: &str) -> Option<&'static str> {
-    get_rule_by_name(rule).map(|rule| rule.docs())
+    get_rule_by_name(rule)
+        .map(|rule| rule.docs())
+        .or_else(|| get_project_rule_by_name(rule).map(|rule| rule.docs()))
 }
 
 macro_rules! trait_obj_helper {
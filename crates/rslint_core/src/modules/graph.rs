@@ -0,0 +1,475 @@
+//! The graph of imports between the files of a project.
+
+use super::{Resolution, Resolver};
+use crate::Project;
+use rslint_parser::{
+    NodeOrToken, SmolStr, SyntaxElement, SyntaxKind::*, SyntaxNode, SyntaxNodeExt, TextRange,
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::PathBuf,
+};
+
+/// How a module is imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImportKind {
+    /// An `import` declaration.
+    Import,
+    /// An `import type` declaration, which is removed by TypeScript.
+    TypeImport,
+    /// An `export ... from` declaration.
+    ReExport,
+    /// A `require()` call or a TypeScript `import x = require()` declaration.
+    Require,
+    /// An `import()` expression, which loads the module lazily.
+    DynamicImport,
+}
+
+/// The names an import uses from the module it imports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportedNames {
+    /// Only the listed exports are used, the default export is named `default`.
+    Names(Vec<SmolStr>),
+    /// Any export may be used, such as with a namespace import or `require()`.
+    All,
+    /// The module is only imported for its side effects.
+    Nothing,
+}
+
+/// A single import of a module by a file.
+#[derive(Debug, Clone)]
+pub struct Import {
+    /// The module specifier without quotes.
+    pub specifier: String,
+    /// The range of the specifier's string literal.
+    pub range: TextRange,
+    pub kind: ImportKind,
+    pub names: ImportedNames,
+    pub resolution: Resolution,
+    /// The id of the project file the import resolved to, if it resolved to one.
+    pub target: Option<usize>,
+}
+
+/// A name exported by a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Export {
+    /// The exported name, the default export is named `default`.
+    pub name: SmolStr,
+    /// The range of the exported name, or of the `default` keyword for default exports.
+    pub range: TextRange,
+}
+
+/// The imports and exports of a single file of a project.
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub file_id: usize,
+    pub path: PathBuf,
+    pub imports: Vec<Import>,
+    pub exports: Vec<Export>,
+}
+
+/// The graph of imports between the files of a project.
+///
+/// Only files with a path and a tree are part of the graph, imports are resolved from the file's path.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleGraph {
+    modules: Vec<Module>,
+}
+
+impl ModuleGraph {
+    /// Collect the imports and exports of every file of a project and resolve the imports.
+    pub fn new(project: &Project, resolver: &Resolver) -> Self {
+        let mut modules = project
+            .files()
+            .iter()
+            .filter_map(|file| {
+                let path = file.file.path.clone()?;
+                let (imports, exports) = collect(file.root.as_ref()?);
+                let imports = imports
+                    .into_iter()
+                    .map(|(specifier, range, kind, names)| Import {
+                        resolution: resolver.resolve(&path, &specifier),
+                        specifier,
+                        range,
+                        kind,
+                        names,
+                        target: None,
+                    })
+                    .collect();
+                Some(Module {
+                    file_id: file.file.id,
+                    path,
+                    imports,
+                    exports,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let ids = modules
+            .iter()
+            .map(|module| (canonical(&module.path), module.file_id))
+            .collect::<HashMap<_, _>>();
+        for import in modules.iter_mut().flat_map(|module| &mut module.imports) {
+            import.target = import
+                .resolution
+                .path()
+                .and_then(|path| ids.get(&canonical(path)).copied());
+        }
+
+        Self { modules }
+    }
+
+    pub fn modules(&self) -> &[Module] {
+        &self.modules
+    }
+
+    /// Get the module of a file by the file's id.
+    pub fn module(&self, file_id: usize) -> Option<&Module> {
+        self.modules.iter().find(|module| module.file_id == file_id)
+    }
+
+    /// All imports of a file by other files of the project, along with the module importing it.
+    pub fn importers(&self, file_id: usize) -> impl Iterator<Item = (&Module, &Import)> {
+        self.modules.iter().flat_map(move |module| {
+            module
+                .imports
+                .iter()
+                .filter(move |import| import.target == Some(file_id))
+                .map(move |import| (module, import))
+        })
+    }
+
+    /// Find import cycles between modules, one for each group of modules which import each other.
+    ///
+    /// Each cycle is a list of `(file id, import index)` pairs where the import of each module imports
+    /// the next module, and the last import imports the first module. Type imports and dynamic imports
+    /// are not considered because they do not cause modules to be evaluated in a cycle.
+    pub fn cycles(&self) -> Vec<Vec<(usize, usize)>> {
+        let edges = |module: &Module| {
+            module
+                .imports
+                .iter()
+                .enumerate()
+                .filter(|(_, import)| {
+                    !matches!(
+                        import.kind,
+                        ImportKind::TypeImport | ImportKind::DynamicImport
+                    )
+                })
+                .filter_map(|(idx, import)| Some((idx, import.target?)))
+                .collect::<Vec<_>>()
+        };
+        let graph = self
+            .modules
+            .iter()
+            .map(|module| (module.file_id, edges(module)))
+            .collect::<HashMap<_, _>>();
+
+        strongly_connected(&self.modules, &graph)
+            .into_iter()
+            .filter_map(|component| {
+                let start = *component.iter().min()?;
+                shortest_cycle(start, &component, &graph)
+            })
+            .collect()
+    }
+}
+
+fn canonical(path: &std::path::Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_owned())
+}
+
+type Graph = HashMap<usize, Vec<(usize, usize)>>;
+
+/// Tarjan's algorithm, only components which contain a cycle are returned.
+fn strongly_connected(modules: &[Module], graph: &Graph) -> Vec<HashSet<usize>> {
+    struct State<'a> {
+        graph: &'a Graph,
+        index: usize,
+        indices: HashMap<usize, usize>,
+        lowlinks: HashMap<usize, usize>,
+        stack: Vec<usize>,
+        on_stack: HashSet<usize>,
+        components: Vec<HashSet<usize>>,
+    }
+
+    fn visit(state: &mut State, node: usize) {
+        state.indices.insert(node, state.index);
+        state.lowlinks.insert(node, state.index);
+        state.index += 1;
+        state.stack.push(node);
+        state.on_stack.insert(node);
+
+        let graph = state.graph;
+        for (_, next) in graph.get(&node).into_iter().flatten() {
+            if !state.indices.contains_key(next) {
+                visit(state, *next);
+                let low = state.lowlinks[&node].min(state.lowlinks[next]);
+                state.lowlinks.insert(node, low);
+            } else if state.on_stack.contains(next) {
+                let low = state.lowlinks[&node].min(state.indices[next]);
+                state.lowlinks.insert(node, low);
+            }
+        }
+
+        if state.lowlinks[&node] == state.indices[&node] {
+            let mut component = HashSet::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(&member);
+                component.insert(member);
+                if member == node {
+                    break;
+                }
+            }
+            let self_import = graph
+                .get(&node)
+                .map_or(false, |edges| edges.iter().any(|(_, next)| *next == node));
+            if component.len() > 1 || self_import {
+                state.components.push(component);
+            }
+        }
+    }
+
+    let mut state = State {
+        graph,
+        index: 0,
+        indices: HashMap::new(),
+        lowlinks: HashMap::new(),
+        stack: vec![],
+        on_stack: HashSet::new(),
+        components: vec![],
+    };
+    for module in modules {
+        if !state.indices.contains_key(&module.file_id) {
+            visit(&mut state, module.file_id);
+        }
+    }
+    state
+        .components
+        .sort_by_key(|component| component.iter().min().copied());
+    state.components
+}
+
+/// Find the shortest path from `start` back to itself inside of a component.
+fn shortest_cycle(
+    start: usize,
+    component: &HashSet<usize>,
+    graph: &Graph,
+) -> Option<Vec<(usize, usize)>> {
+    // the import each module was first reached through
+    let mut reached: HashMap<usize, (usize, usize)> = HashMap::new();
+    let mut queue = VecDeque::from(vec![start]);
+
+    while let Some(node) = queue.pop_front() {
+        for (idx, next) in graph.get(&node).into_iter().flatten() {
+            if !component.contains(next) || reached.contains_key(next) {
+                continue;
+            }
+            reached.insert(*next, (node, *idx));
+            if *next == start {
+                let mut cycle = vec![];
+                let mut cur = start;
+                loop {
+                    let (prev, idx) = reached[&cur];
+                    cycle.push((prev, idx));
+                    cur = prev;
+                    if cur == start {
+                        break;
+                    }
+                }
+                cycle.reverse();
+                return Some(cycle);
+            }
+            queue.push_back(*next);
+        }
+    }
+    None
+}
+
+type RawImport = (String, TextRange, ImportKind, ImportedNames);
+
+/// Collect the imports and exports of a file.
+fn collect(root: &SyntaxNode) -> (Vec<RawImport>, Vec<Export>) {
+    let mut imports = vec![];
+    let mut exports = vec![];
+
+    for node in root.descendants() {
+        match node.kind() {
+            IMPORT_DECL => {
+                let source = node.child_with_kind(IMPORT_STRING_SPECIFIER).map_or_else(
+                    || string(node.children_with_tokens()),
+                    |spec| string(spec.children_with_tokens()),
+                );
+                let (specifier, range) = match source {
+                    Some(source) => source,
+                    None => continue,
+                };
+
+                let kind = if node.token_with_kind(TYPE_KW).is_some() {
+                    ImportKind::TypeImport
+                } else {
+                    ImportKind::Import
+                };
+                let mut names = vec![];
+                let mut all = false;
+                if node.child_with_kind(NAME).is_some() {
+                    names.push("default".into());
+                }
+                if let Some(named) = node.child_with_kind(NAMED_IMPORTS) {
+                    names.extend(
+                        named
+                            .children()
+                            .filter(|spec| spec.kind() == SPECIFIER)
+                            .filter_map(|spec| first_name(&spec)),
+                    );
+                }
+                if node.child_with_kind(WILDCARD_IMPORT).is_some() {
+                    all = true;
+                }
+
+                let names = if all {
+                    ImportedNames::All
+                } else if names.is_empty() {
+                    ImportedNames::Nothing
+                } else {
+                    ImportedNames::Names(names)
+                };
+                imports.push((specifier, range, kind, names));
+            }
+            EXPORT_NAMED => {
+                let specifiers = node
+                    .children()
+                    .filter(|spec| spec.kind() == SPECIFIER)
+                    .collect::<Vec<_>>();
+                for spec in &specifiers {
+                    if let Some(name) = spec.children().filter(|n| n.kind() == NAME).last() {
+                        exports.push(Export {
+                            name: name.trimmed_text().to_string().into(),
+                            range: name.trimmed_range(),
+                        });
+                    }
+                }
+
+                if node.token_with_kind(FROM_KW).is_some() {
+                    if let Some((specifier, range)) = string(node.children_with_tokens()) {
+                        let names = specifiers.iter().filter_map(first_name).collect();
+                        imports.push((
+                            specifier,
+                            range,
+                            ImportKind::ReExport,
+                            ImportedNames::Names(names),
+                        ));
+                    }
+                }
+            }
+            EXPORT_WILDCARD => {
+                if let Some(name) = node.child_with_kind(NAME) {
+                    exports.push(Export {
+                        name: name.trimmed_text().to_string().into(),
+                        range: name.trimmed_range(),
+                    });
+                }
+                if let Some((specifier, range)) = string(node.children_with_tokens()) {
+                    imports.push((specifier, range, ImportKind::ReExport, ImportedNames::All));
+                }
+            }
+            EXPORT_DECL => {
+                let decl = match node.children().next() {
+                    Some(decl) => decl,
+                    None => continue,
+                };
+                let names = if decl.kind() == VAR_DECL {
+                    decl.descendants()
+                        .filter(|name| {
+                            name.kind() == NAME
+                                && name.parent().map_or(false, |p| p.kind() == SINGLE_PATTERN)
+                        })
+                        .collect()
+                } else {
+                    decl.child_with_kind(NAME).into_iter().collect::<Vec<_>>()
+                };
+                exports.extend(names.into_iter().map(|name| Export {
+                    name: name.trimmed_text().to_string().into(),
+                    range: name.trimmed_range(),
+                }));
+            }
+            EXPORT_DEFAULT_EXPR | EXPORT_DEFAULT_DECL => {
+                if let Some(default) = node.token_with_kind(DEFAULT_KW) {
+                    exports.push(Export {
+                        name: "default".into(),
+                        range: default.text_range(),
+                    });
+                }
+            }
+            TS_EXPORT_ASSIGNMENT => {
+                if let Some(export) = node.token_with_kind(EXPORT_KW) {
+                    exports.push(Export {
+                        name: "default".into(),
+                        range: export.text_range(),
+                    });
+                }
+            }
+            TS_EXTERNAL_MODULE_REF => {
+                if let Some((specifier, range)) = string(node.children_with_tokens()) {
+                    imports.push((specifier, range, ImportKind::Require, ImportedNames::All));
+                }
+            }
+            IMPORT_CALL => {
+                if let Some(literal) = node.child_with_kind(LITERAL) {
+                    if let Some((specifier, range)) = string(literal.children_with_tokens()) {
+                        imports.push((
+                            specifier,
+                            range,
+                            ImportKind::DynamicImport,
+                            ImportedNames::All,
+                        ));
+                    }
+                }
+            }
+            CALL_EXPR => {
+                let is_require = node.children().next().map_or(false, |callee| {
+                    callee.kind() == NAME_REF && callee.trimmed_text() == "require"
+                });
+                let args = node
+                    .child_with_kind(ARG_LIST)
+                    .map(|args| args.children().collect::<Vec<_>>())
+                    .unwrap_or_default();
+                if let (true, [arg]) = (is_require, args.as_slice()) {
+                    if arg.kind() == LITERAL {
+                        if let Some((specifier, range)) = string(arg.children_with_tokens()) {
+                            imports.push((
+                                specifier,
+                                range,
+                                ImportKind::Require,
+                                ImportedNames::All,
+                            ));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (imports, exports)
+}
+
+/// The first name of an import or export specifier, which is the name in the module it comes from.
+fn first_name(spec: &SyntaxNode) -> Option<SmolStr> {
+    spec.child_with_kind(NAME)
+        .map(|name| name.trimmed_text().to_string().into())
+}
+
+/// The unquoted value and range of the first string token.
+fn string(mut elements: impl Iterator<Item = SyntaxElement>) -> Option<(String, TextRange)> {
+    elements.find_map(|elem| match elem {
+        NodeOrToken::Token(tok) if tok.kind() == STRING => {
+            let text = tok.text().as_str();
+            let value = text
+                .get(1..text.len().saturating_sub(1))
+                .unwrap_or_default();
+            Some((value.to_string(), tok.text_range()))
+        }
+        _ => None,
+    })
+}
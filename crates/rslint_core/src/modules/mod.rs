@@ -0,0 +1,112 @@
+//! Module resolution and the import graph of a project.
+//!
+//! The [`Resolver`] resolves module specifiers to files on disk like Node and TypeScript do, without
+//! any network access. The [`ModuleGraph`] is built from the imports, exports and `require()` calls of every
+//! file in a [`Project`](crate::Project) and is used by project rules to check imports across files.
+
+mod graph;
+mod resolve;
+
+pub use self::{
+    graph::{Export, Import, ImportKind, ImportedNames, Module, ModuleGraph},
+    resolve::{Resolution, Resolver, NODE_BUILTINS},
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempProject;
+    use std::path::Path;
+
+    #[test]
+    fn resolve() {
+        let project = TempProject::new(&[
+            ("src/a.ts", ""),
+            ("src/b/index.js", ""),
+            ("src/c.js", ""),
+            (
+                "node_modules/pkg/package.json",
+                r#"{ "main": "lib/main.js" }"#,
+            ),
+            ("node_modules/pkg/lib/main.js", ""),
+            ("node_modules/pkg/lib/other.js", ""),
+            (
+                "node_modules/@scope/exp/package.json",
+                r#"{ "exports": { ".": { "import": "./esm.mjs", "default": "./cjs.js" }, "./feature/*": "./features/*.js" } }"#,
+            ),
+            ("node_modules/@scope/exp/esm.mjs", ""),
+            ("node_modules/@scope/exp/features/x.js", ""),
+            ("node_modules/@scope/exp/private.js", ""),
+        ]);
+        let resolver = Resolver::default();
+        let importer = project.dir.join("src/main.ts");
+        let resolve = |spec: &str| resolver.resolve(&importer, spec);
+        let file = |path: &str| Resolution::File(project.dir.join(path));
+        let same = |res: Resolution, path: &str| {
+            res.path().map(Path::canonicalize).map(Result::unwrap)
+                == Some(project.dir.join(path).canonicalize().unwrap())
+        };
+
+        assert!(same(resolve("./a"), "src/a.ts"));
+        assert!(same(resolve("./a.js"), "src/a.ts"));
+        assert!(same(resolve("./b"), "src/b/index.js"));
+        assert!(same(resolve("./c.js"), "src/c.js"));
+        assert!(same(resolve("pkg"), "node_modules/pkg/lib/main.js"));
+        assert!(same(
+            resolve("pkg/lib/other"),
+            "node_modules/pkg/lib/other.js"
+        ));
+        assert!(same(
+            resolve("@scope/exp"),
+            "node_modules/@scope/exp/esm.mjs"
+        ));
+        assert!(same(
+            resolve("@scope/exp/feature/x"),
+            "node_modules/@scope/exp/features/x.js"
+        ));
+        assert_ne!(resolve("./a"), file("src/nope.ts"));
+        assert!(resolve("./nope").is_unresolved());
+        assert!(resolve("@scope/exp/private.js").is_unresolved());
+        assert!(resolve("missing").is_unresolved());
+        assert_eq!(resolve("fs"), Resolution::External);
+        assert_eq!(resolve("node:path"), Resolution::External);
+    }
+
+    #[test]
+    fn graph() {
+        let project = TempProject::new(&[
+            (
+                "a.js",
+                "import b, { x } from './b'; export * from './c'; export const a = 1;",
+            ),
+            (
+                "b.js",
+                "import { a } from './a'; export default 1; export const x = 1;",
+            ),
+            (
+                "c.ts",
+                "const d = require('./d'); import type { T } from './a';",
+            ),
+            ("d.ts", "export function d() {} export = d;"),
+        ]);
+        let graph = project.module_graph();
+
+        let a = graph.module(0).unwrap();
+        assert_eq!(a.imports.len(), 2);
+        assert_eq!(
+            a.imports[0].names,
+            ImportedNames::Names(vec!["default".into(), "x".into()])
+        );
+        assert_eq!(a.imports[0].target, Some(1));
+        assert_eq!(a.imports[1].kind, ImportKind::ReExport);
+        assert_eq!(a.exports[0].name, "a");
+
+        let c = graph.module(2).unwrap();
+        assert_eq!(c.imports[0].kind, ImportKind::Require);
+        assert_eq!(c.imports[0].target, Some(3));
+        assert_eq!(c.imports[1].kind, ImportKind::TypeImport);
+
+        assert_eq!(graph.importers(0).count(), 2);
+        assert_eq!(graph.cycles(), vec![vec![(0, 0), (1, 0)]]);
+    }
+}
@@ -0,0 +1,388 @@
+//! Resolution of module specifiers to files on disk, in the same way as Node and TypeScript.
+
+use serde::{
+    de::{Deserializer, MapAccess, SeqAccess, Visitor},
+    Deserialize,
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Modules which are built into Node, they may also be imported with a `node:` prefix.
+pub const NODE_BUILTINS: [&str; 41] = [
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "domain",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "repl",
+    "stream",
+    "string_decoder",
+    "sys",
+    "timers",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "worker_threads",
+    "zlib",
+];
+
+/// The result of resolving a module specifier.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Resolution {
+    /// The specifier resolved to a file.
+    File(PathBuf),
+    /// The specifier is a module which does not come from a file, such as a Node builtin or a URL.
+    External,
+    /// The specifier does not resolve to any file.
+    Unresolved,
+}
+
+impl Resolution {
+    pub fn is_unresolved(&self) -> bool {
+        *self == Resolution::Unresolved
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Resolution::File(path) => Some(path),
+            _ => None,
+        }
+    }
+}
+
+/// A resolver for module specifiers which only looks at files on disk.
+///
+/// Relative and absolute specifiers are resolved to a file, a file with one of the [`extensions`](Self::extensions),
+/// or a directory with a `package.json` `main` field or an index file. Like TypeScript, a `.js` specifier may also
+/// resolve to a `.ts` file. Bare specifiers are resolved in the `node_modules` directories of the importing file
+/// and its ancestors using the package's `exports` field, or its `main` and `types` fields if it has no `exports`.
+#[derive(Debug)]
+pub struct Resolver {
+    /// The extensions which are tried in order if a specifier does not point to a file.
+    pub extensions: Vec<String>,
+    /// The conditions of a package's `exports` which are matched, `default` is always matched.
+    pub conditions: Vec<String>,
+    packages: Mutex<HashMap<PathBuf, Option<PackageJson>>>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new(
+            [
+                ".ts", ".tsx", ".d.ts", ".js", ".jsx", ".mjs", ".cjs", ".json",
+            ]
+            .iter()
+            .map(|ext| ext.to_string())
+            .collect(),
+            ["types", "import", "require", "node"]
+                .iter()
+                .map(|cond| cond.to_string())
+                .collect(),
+        )
+    }
+}
+
+impl Resolver {
+    pub fn new(extensions: Vec<String>, conditions: Vec<String>) -> Self {
+        Self {
+            extensions,
+            conditions,
+            packages: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Resolve a specifier imported by the file at `importer`.
+    pub fn resolve(&self, importer: &Path, specifier: &str) -> Resolution {
+        if is_external(specifier) {
+            return Resolution::External;
+        }
+
+        let dir = importer.parent().unwrap_or_else(|| Path::new(""));
+        let found = if is_path(specifier) {
+            self.resolve_path(&dir.join(specifier))
+        } else {
+            self.resolve_bare(dir, specifier)
+        };
+        found.map_or(Resolution::Unresolved, Resolution::File)
+    }
+
+    /// Resolve a path to a file, a file with an extension, or a directory.
+    fn resolve_path(&self, path: &Path) -> Option<PathBuf> {
+        self.resolve_file(path).or_else(|| self.resolve_dir(path))
+    }
+
+    fn resolve_file(&self, path: &Path) -> Option<PathBuf> {
+        if path.is_file() {
+            return Some(path.to_owned());
+        }
+
+        // TypeScript allows importing a `.ts` file using the `.js` extension it is compiled to
+        let ts_ext = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("js") => Some(["ts", "tsx"].as_ref()),
+            Some("jsx") => Some(["tsx"].as_ref()),
+            Some("mjs") => Some(["mts"].as_ref()),
+            Some("cjs") => Some(["cts"].as_ref()),
+            _ => None,
+        };
+        if let Some(exts) = ts_ext {
+            if let Some(found) = exts
+                .iter()
+                .map(|ext| path.with_extension(ext))
+                .find(|path| path.is_file())
+            {
+                return Some(found);
+            }
+        }
+
+        self.extensions
+            .iter()
+            .map(|ext| {
+                let mut path = path.as_os_str().to_owned();
+                path.push(ext);
+                PathBuf::from(path)
+            })
+            .find(|path| path.is_file())
+    }
+
+    fn resolve_dir(&self, dir: &Path) -> Option<PathBuf> {
+        if !dir.is_dir() {
+            return None;
+        }
+        if let Some(package) = self.package(dir) {
+            if let Some(found) = package
+                .entry_points()
+                .find_map(|main| self.resolve_file(&dir.join(main)))
+            {
+                return Some(found);
+            }
+        }
+        self.resolve_file(&dir.join("index"))
+    }
+
+    /// Resolve a bare specifier such as `foo` or `@scope/foo/bar` in `node_modules`.
+    fn resolve_bare(&self, dir: &Path, specifier: &str) -> Option<PathBuf> {
+        let mut segments = specifier.splitn(if specifier.starts_with('@') { 3 } else { 2 }, '/');
+        let name = if specifier.starts_with('@') {
+            format!("{}/{}", segments.next()?, segments.next()?)
+        } else {
+            segments.next()?.to_string()
+        };
+        let subpath = segments.next();
+
+        let package_dir = dir
+            .ancestors()
+            .map(|ancestor| ancestor.join("node_modules").join(&name))
+            .find(|path| path.is_dir())?;
+
+        let exports = self
+            .package(&package_dir)
+            .and_then(|package| package.exports);
+        match exports {
+            Some(exports) => {
+                let key = subpath.map_or(".".to_string(), |subpath| format!("./{}", subpath));
+                let target = self.match_exports(&exports, &key)?;
+                self.resolve_file(&package_dir.join(target))
+            }
+            None => match subpath {
+                Some(subpath) => self.resolve_path(&package_dir.join(subpath)),
+                None => self.resolve_dir(&package_dir),
+            },
+        }
+    }
+
+    /// Find the target of a subpath such as `.` or `./foo` in a package's `exports`.
+    fn match_exports(&self, exports: &Exports, key: &str) -> Option<String> {
+        let subpaths = match exports {
+            Exports::Object(entries) if entries.iter().any(|(k, _)| k.starts_with('.')) => entries,
+            // `"exports": "./index.js"` and conditions without subpaths are the exports of `.`
+            _ if key == "." => return self.match_conditions(exports, None),
+            _ => return None,
+        };
+
+        if let Some((_, target)) = subpaths.iter().find(|(k, _)| k == key) {
+            return self.match_conditions(target, None);
+        }
+
+        // patterns such as `./features/*`, the longest prefix wins
+        subpaths
+            .iter()
+            .filter_map(|(pattern, target)| {
+                let (prefix, suffix) = pattern.split_at(pattern.find('*')?);
+                let suffix = &suffix[1..];
+                let matched = key
+                    .strip_prefix(prefix)?
+                    .strip_suffix(suffix)
+                    .filter(|matched| !matched.is_empty())?;
+                Some((prefix.len(), matched, target))
+            })
+            .max_by_key(|(len, ..)| *len)
+            .and_then(|(_, matched, target)| self.match_conditions(target, Some(matched)))
+    }
+
+    fn match_conditions(&self, target: &Exports, pattern: Option<&str>) -> Option<String> {
+        match target {
+            Exports::String(target) => Some(match pattern {
+                Some(pattern) => target.replace('*', pattern),
+                None => target.to_owned(),
+            }),
+            Exports::Array(targets) => targets
+                .iter()
+                .find_map(|target| self.match_conditions(target, pattern)),
+            Exports::Object(conditions) => conditions
+                .iter()
+                .filter(|(cond, _)| cond == "default" || self.conditions.contains(cond))
+                .find_map(|(_, target)| self.match_conditions(target, pattern)),
+            Exports::Null => None,
+        }
+    }
+
+    /// Get the parsed `package.json` of a directory, which is read once.
+    fn package(&self, dir: &Path) -> Option<PackageJson> {
+        let mut packages = self.packages.lock().unwrap();
+        packages
+            .entry(dir.to_owned())
+            .or_insert_with(|| {
+                let source = read_to_string(dir.join("package.json")).ok()?;
+                serde_json::from_str(&source).ok()
+            })
+            .clone()
+    }
+}
+
+/// Whether a specifier is a relative or absolute path.
+fn is_path(specifier: &str) -> bool {
+    specifier == "."
+        || specifier == ".."
+        || specifier.starts_with("./")
+        || specifier.starts_with("../")
+        || Path::new(specifier).is_absolute()
+}
+
+/// Whether a specifier is a Node builtin or a URL.
+fn is_external(specifier: &str) -> bool {
+    if specifier.starts_with("node:") || specifier.contains("://") || specifier.starts_with("data:")
+    {
+        return true;
+    }
+    let name = specifier.split('/').next().unwrap_or_default();
+    NODE_BUILTINS.contains(&name)
+}
+
+/// The fields of a `package.json` used for resolution.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct PackageJson {
+    main: Option<String>,
+    types: Option<String>,
+    typings: Option<String>,
+    exports: Option<Exports>,
+}
+
+impl PackageJson {
+    fn entry_points(&self) -> impl Iterator<Item = &String> {
+        self.main
+            .iter()
+            .chain(self.types.iter())
+            .chain(self.typings.iter())
+    }
+}
+
+/// The `exports` field of a `package.json`, the order of keys matters for conditions
+/// so objects are kept as a list of entries.
+#[derive(Debug, Clone, PartialEq)]
+enum Exports {
+    String(String),
+    Array(Vec<Exports>),
+    Object(Vec<(String, Exports)>),
+    Null,
+}
+
+impl<'de> Deserialize<'de> for Exports {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ExportsVisitor;
+
+        impl<'de> Visitor<'de> for ExportsVisitor {
+            type Value = Exports;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a package exports field")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Exports, E> {
+                Ok(Exports::String(value.to_owned()))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Exports, A::Error> {
+                let mut items = vec![];
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(Exports::Array(items))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Exports, A::Error> {
+                let mut entries = vec![];
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Exports::Object(entries))
+            }
+
+            // any other value can not be a target
+            fn visit_unit<E>(self) -> Result<Exports, E> {
+                Ok(Exports::Null)
+            }
+
+            fn visit_bool<E>(self, _: bool) -> Result<Exports, E> {
+                Ok(Exports::Null)
+            }
+
+            fn visit_i64<E>(self, _: i64) -> Result<Exports, E> {
+                Ok(Exports::Null)
+            }
+
+            fn visit_u64<E>(self, _: u64) -> Result<Exports, E> {
+                Ok(Exports::Null)
+            }
+
+            fn visit_f64<E>(self, _: f64) -> Result<Exports, E> {
+                Ok(Exports::Null)
+            }
+        }
+
+        deserializer.deserialize_any(ExportsVisitor)
+    }
+}
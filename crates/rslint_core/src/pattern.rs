@@ -0,0 +1,468 @@
+//! Structural code patterns with metavariables.
+//!
+//! A [`Pattern`] is written as JavaScript or TypeScript code, such as `$OBJ.innerHTML = $VAL`, and matches
+//! any node with the same structure. Nodes are compared like [`lexical_eq`](SyntaxNodeExt::lexical_eq) does,
+//! therefore whitespace and comments are ignored. Identifiers starting with `$` are metavariables:
+//!
+//! - `$NAME` matches any single node or token and binds it to `NAME`. If the same metavariable is
+//!   used more than once, every occurrence must match lexically equal code.
+//! - `$_` matches any single node or token without binding it.
+//! - `$$$NAME` matches zero or more sibling nodes and tokens, such as the arguments of a call in `foo($$$ARGS)`.
+//!   `$$$_` does the same without binding them.
+//!
+//! A pattern which is an expression without a semicolon matches the expression anywhere, so `foo($_)` matches
+//! calls anywhere while `foo($_);` only matches calls which are statements. The semicolon at the end of a statement
+//! is optional in the code being matched, like it is in JavaScript.
+
+use rslint_parser::{
+    parse_module, parse_with_syntax, NodeOrToken, Syntax, SyntaxElement, SyntaxKind, SyntaxNode,
+    SyntaxNodeExt, TextRange,
+};
+use std::{collections::HashMap, fmt};
+
+/// An error encountered while compiling a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    /// The pattern is not valid JavaScript or TypeScript, the message of the first parser error.
+    Syntax(String),
+    /// The pattern is empty or contains more than one statement.
+    NotSingle,
+    /// The pattern is only a metavariable, so it would match every node.
+    OnlyMetavariable,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternError::Syntax(err) => write!(f, "the pattern is not valid code: {}", err),
+            PatternError::NotSingle => {
+                write!(f, "a pattern must be a single statement or expression")
+            }
+            PatternError::OnlyMetavariable => {
+                write!(f, "a pattern can not be only a metavariable")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Element {
+    Node {
+        kind: SyntaxKind,
+        children: Vec<Element>,
+    },
+    Token {
+        kind: SyntaxKind,
+        text: String,
+    },
+    /// `$NAME` or `$_`, the name is `None` for wildcards.
+    Var(Option<String>),
+    /// `$$$NAME` or `$$$_`.
+    Repeat(Option<String>),
+}
+
+/// A compiled structural pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    source: String,
+    root: Element,
+}
+
+impl Pattern {
+    /// Compile a pattern from its source code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rslint_core::pattern::Pattern;
+    /// use rslint_parser::parse_module;
+    ///
+    /// let pattern = Pattern::new("$OBJ.innerHTML = $VAL").unwrap();
+    /// let root = parse_module("el.innerHTML = html;", 0).syntax();
+    /// let found = pattern.find_all(&root);
+    ///
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(found[0].text("OBJ", "el.innerHTML = html;"), Some("el"));
+    /// ```
+    pub fn new(source: &str) -> Result<Self, PatternError> {
+        let module = parse_module(source, 0);
+        let root = if module.errors().is_empty() {
+            module.syntax()
+        } else {
+            let ts = parse_with_syntax(source, 0, Syntax::default().typescript());
+            if !ts.errors().is_empty() {
+                return Err(PatternError::Syntax(module.errors()[0].title.clone()));
+            }
+            ts.syntax()
+        };
+
+        let mut statements = root.children();
+        let stmt = match (statements.next(), statements.next()) {
+            (Some(stmt), None) => stmt,
+            _ => return Err(PatternError::NotSingle),
+        };
+        let has_semicolon = stmt
+            .lossy_tokens()
+            .last()
+            .map_or(false, |token| token.kind() == SyntaxKind::SEMICOLON);
+        let node = if stmt.kind() == SyntaxKind::EXPR_STMT && !has_semicolon {
+            stmt.first_child().ok_or(PatternError::NotSingle)?
+        } else {
+            stmt
+        };
+
+        match compile(node.into()) {
+            Element::Var(_) | Element::Repeat(_) => Err(PatternError::OnlyMetavariable),
+            root => Ok(Self {
+                source: source.to_string(),
+                root,
+            }),
+        }
+    }
+
+    /// The source code the pattern was compiled from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The kind of nodes this pattern can match.
+    pub fn kind(&self) -> SyntaxKind {
+        match self.root {
+            Element::Node { kind, .. } => kind,
+            _ => unreachable!("patterns are always rooted at a node"),
+        }
+    }
+
+    /// The names of every metavariable bound by this pattern, without their `$` prefix.
+    pub fn variables(&self) -> Vec<&str> {
+        fn collect<'a>(elem: &'a Element, names: &mut Vec<&'a str>) {
+            match elem {
+                Element::Node { children, .. } => {
+                    children.iter().for_each(|child| collect(child, names))
+                }
+                Element::Var(Some(name)) | Element::Repeat(Some(name)) => {
+                    if !names.contains(&name.as_str()) {
+                        names.push(name);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut names = vec![];
+        collect(&self.root, &mut names);
+        names
+    }
+
+    /// Match a single node against this pattern, returning the bound metavariables if it matches.
+    pub fn matches(&self, node: &SyntaxNode) -> Option<Match> {
+        let mut bindings = HashMap::new();
+        if match_elem(&self.root, &node.clone().into(), &mut bindings) {
+            Some(Match {
+                node: node.clone(),
+                bindings,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Find every node in a tree which matches this pattern, in preorder.
+    pub fn find_all(&self, root: &SyntaxNode) -> Vec<Match> {
+        root.descendants()
+            .filter_map(|node| self.matches(&node))
+            .collect()
+    }
+}
+
+/// A node which matched a [`Pattern`] and its bound metavariables.
+#[derive(Debug, Clone)]
+pub struct Match {
+    pub node: SyntaxNode,
+    bindings: HashMap<String, Vec<SyntaxElement>>,
+}
+
+impl Match {
+    /// The nodes and tokens bound to a metavariable, which are more than one only for `$$$NAME`.
+    pub fn get(&self, name: &str) -> Option<&[SyntaxElement]> {
+        self.bindings.get(name).map(Vec::as_slice)
+    }
+
+    /// The range of the code bound to a metavariable without leading and trailing whitespace,
+    /// this is an empty range at the start of the match for a `$$$NAME` which matched nothing.
+    pub fn range(&self, name: &str) -> Option<TextRange> {
+        let elems = self.get(name)?;
+        match (elems.first(), elems.last()) {
+            (Some(first), Some(last)) => Some(elem_range(first).cover(elem_range(last))),
+            _ => Some(TextRange::empty(self.node.trimmed_range().start())),
+        }
+    }
+
+    /// The source code bound to a metavariable.
+    pub fn text<'a>(&self, name: &str, src: &'a str) -> Option<&'a str> {
+        let range = self.range(name)?;
+        Some(&src[range])
+    }
+
+    /// Replace every bound `$NAME` or `$$$NAME` in a template with the code bound to it,
+    /// metavariables which are not bound are left as is.
+    pub fn substitute(&self, template: &str, src: &str) -> String {
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(idx) = rest.find('$') {
+            out.push_str(&rest[..idx]);
+            let (len, name) = metavariable(&rest[idx..]);
+            match name.and_then(|name| self.text(name, src)) {
+                Some(text) => out.push_str(text),
+                None => out.push_str(&rest[idx..idx + len]),
+            }
+            rest = &rest[idx + len..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// The names of the metavariables used in a template, such as a rewrite or a message.
+pub fn template_variables(template: &str) -> Vec<&str> {
+    let mut names = vec![];
+    let mut rest = template;
+    while let Some(idx) = rest.find('$') {
+        let (len, name) = metavariable(&rest[idx..]);
+        names.extend(name);
+        rest = &rest[idx + len..];
+    }
+    names
+}
+
+/// Split the metavariable at the start of a string which starts with `$`, returning its length and its name.
+/// The name is `None` if the `$`s are not followed by a name, or if it is the wildcard `_`.
+fn metavariable(s: &str) -> (usize, Option<&str>) {
+    let dollars = s.len() - s.trim_start_matches('$').len();
+    let name_len = s[dollars..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(s.len() - dollars);
+    let name = &s[dollars..dollars + name_len];
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && name != "_";
+    (dollars + name_len, Some(name).filter(|_| valid))
+}
+
+/// Turn an identifier such as `$X` or `$$$_` into a metavariable element.
+fn parse_metavariable(text: &str) -> Option<Element> {
+    let (len, name) = metavariable(text);
+    let dollars = text.len() - text.trim_start_matches('$').len();
+    if len != text.len() || !(dollars == 1 || dollars == 3) {
+        return None;
+    }
+    if name.is_none() && &text[dollars..] != "_" {
+        return None;
+    }
+    let name = name.map(str::to_string);
+    Some(if dollars == 1 {
+        Element::Var(name)
+    } else {
+        Element::Repeat(name)
+    })
+}
+
+fn compile(elem: SyntaxElement) -> Element {
+    match elem {
+        NodeOrToken::Token(token) => {
+            if token.kind() == SyntaxKind::IDENT {
+                if let Some(var) = parse_metavariable(token.text()) {
+                    return var;
+                }
+            }
+            Element::Token {
+                kind: token.kind(),
+                text: token.text().to_string(),
+            }
+        }
+        NodeOrToken::Node(node) => {
+            // a node which only wraps a metavariable, such as the `NAME_REF` of `$X`, is the metavariable itself
+            if let [token] = node.lossy_tokens().as_slice() {
+                if token.kind() == SyntaxKind::IDENT {
+                    if let Some(var) = parse_metavariable(token.text()) {
+                        return var;
+                    }
+                }
+            }
+            Element::Node {
+                kind: node.kind(),
+                children: significant_children(&node).map(compile).collect(),
+            }
+        }
+    }
+}
+
+fn significant_children(node: &SyntaxNode) -> impl Iterator<Item = SyntaxElement> {
+    node.children_with_tokens()
+        .filter(|elem| !elem.kind().is_trivia())
+}
+
+fn elem_range(elem: &SyntaxElement) -> TextRange {
+    match elem {
+        NodeOrToken::Node(node) => node.trimmed_range(),
+        NodeOrToken::Token(token) => token.text_range(),
+    }
+}
+
+fn lossy_text(elems: &[SyntaxElement]) -> Vec<String> {
+    elems
+        .iter()
+        .flat_map(|elem| match elem {
+            NodeOrToken::Node(node) => node.lossy_tokens(),
+            NodeOrToken::Token(token) => vec![token.clone()],
+        })
+        .map(|token| token.text().to_string())
+        .collect()
+}
+
+/// Bind a metavariable, or check that the code is lexically equal to what it is already bound to.
+fn bind(
+    name: &Option<String>,
+    elems: &[SyntaxElement],
+    bindings: &mut HashMap<String, Vec<SyntaxElement>>,
+) -> bool {
+    let name = match name {
+        Some(name) => name,
+        None => return true,
+    };
+    match bindings.get(name) {
+        Some(prev) => lossy_text(prev) == lossy_text(elems),
+        None => {
+            bindings.insert(name.clone(), elems.to_vec());
+            true
+        }
+    }
+}
+
+fn is_semicolon(elem: &Element) -> bool {
+    matches!(elem, Element::Token { kind, .. } if *kind == SyntaxKind::SEMICOLON)
+}
+
+fn match_elem(
+    pattern: &Element,
+    elem: &SyntaxElement,
+    bindings: &mut HashMap<String, Vec<SyntaxElement>>,
+) -> bool {
+    match (pattern, elem) {
+        (Element::Var(name), _) => bind(name, std::slice::from_ref(elem), bindings),
+        (Element::Token { kind, text }, NodeOrToken::Token(token)) => {
+            *kind == token.kind() && text == token.text()
+        }
+        (Element::Node { kind, children }, NodeOrToken::Node(node)) => {
+            if *kind != node.kind() {
+                return false;
+            }
+            let elems = significant_children(node).collect::<Vec<_>>();
+            match_seq(children, &elems, bindings)
+        }
+        _ => false,
+    }
+}
+
+fn match_seq(
+    patterns: &[Element],
+    elems: &[SyntaxElement],
+    bindings: &mut HashMap<String, Vec<SyntaxElement>>,
+) -> bool {
+    let (first, rest) = match patterns.split_first() {
+        Some(split) => split,
+        None => return elems.is_empty(),
+    };
+    if elems.is_empty() && rest.is_empty() && is_semicolon(first) {
+        return true;
+    }
+
+    if let Element::Repeat(name) = first {
+        // try the shortest run first and backtrack into longer runs
+        for len in 0..=elems.len() {
+            let mut attempt = bindings.clone();
+            if bind(name, &elems[..len], &mut attempt)
+                && match_seq(rest, &elems[len..], &mut attempt)
+            {
+                *bindings = attempt;
+                return true;
+            }
+        }
+        return false;
+    }
+
+    match elems.split_first() {
+        Some((elem, elems)) => {
+            match_elem(first, elem, bindings) && match_seq(rest, elems, bindings)
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(pattern: &str, src: &str) -> Vec<Match> {
+        let root = parse_module(src, 0).syntax();
+        Pattern::new(pattern).unwrap().find_all(&root)
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        assert!(matches!(Pattern::new("foo("), Err(PatternError::Syntax(_))));
+        assert_eq!(Pattern::new(""), Err(PatternError::NotSingle));
+        assert_eq!(Pattern::new("a; b"), Err(PatternError::NotSingle));
+        assert_eq!(Pattern::new("$X"), Err(PatternError::OnlyMetavariable));
+    }
+
+    #[test]
+    fn metavariables() {
+        let src = "a.b.innerHTML = x; el.innerHTML /* set */ = `<p>`; el.outerHTML = y;";
+        let found = find("$OBJ.innerHTML = $VAL", src);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].text("OBJ", src), Some("a.b"));
+        assert_eq!(found[0].text("VAL", src), Some("x"));
+        assert_eq!(found[1].text("VAL", src), Some("`<p>`"));
+        assert_eq!(
+            Pattern::new("$OBJ.innerHTML = $VAL").unwrap().variables(),
+            vec!["OBJ", "VAL"]
+        );
+    }
+
+    #[test]
+    fn repeated_metavariables() {
+        let src = "a.b === a . b; a === b; x = x;";
+        assert_eq!(find("$X === $X", src).len(), 1);
+        assert_eq!(find("$_ === $_", src).len(), 2);
+    }
+
+    #[test]
+    fn repeats() {
+        let src = "foo(); foo(a); foo(a, b, c); bar(a);";
+        assert_eq!(find("foo($$$ARGS)", src).len(), 3);
+
+        let found = find("foo($$$REST, $LAST)", src);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].text("REST", src), Some("a, b"));
+        assert_eq!(found[0].text("LAST", src), Some("c"));
+    }
+
+    #[test]
+    fn statements() {
+        let src = "if (a) { b() } if (a) {} if (a) b(); else c();";
+        assert_eq!(find("if ($C) { $$$_ }", src).len(), 2);
+        assert_eq!(find("debugger;", "debugger; debugger").len(), 2);
+    }
+
+    #[test]
+    fn substitute() {
+        let src = "foo(a, b);";
+        let found = find("foo($$$ARGS)", src);
+        assert_eq!(
+            found[0].substitute("bar($ARGS, $$$ARGS, $Y, $)", src),
+            "bar(a, b, a, b, $Y, $)"
+        );
+        assert_eq!(template_variables("$A + $$$B - $_ $"), vec!["A", "B"]);
+    }
+}
@@ -0,0 +1,616 @@
+//! Rules loaded from WebAssembly plugins.
+//!
+//! A plugin is a `.wasm` module which provides one or more rules. Plugins are run with an embedded
+//! interpreter, they can not import anything from the host so they have no access to the file system or the network.
+//! Data is exchanged as UTF-8 JSON written to the plugin's memory, the plugin must export:
+//!
+//! - `memory`, its linear memory.
+//! - `alloc(len: i32) -> i32`, which allocates `len` bytes and returns a pointer to them.
+//!   The host writes the input of a call to this buffer.
+//! - `rules(ptr: i32, len: i32) -> i64`, which is given an empty input and returns the rules of the plugin:
+//!   `[{ "name": "no-foo", "docs": "Disallow foo.", "recommended": false, "fixes": ["Always"] }]`, where `fixes` is
+//!   the applicability of each kind of fix the rule makes and defaults to `["MaybeIncorrect"]`.
+//! - `check(ptr: i32, len: i32) -> i64`, which checks a file with a rule. The input is
+//!   `{ "rule": "no-foo", "options": {}, "source": "...", "tree": {...} }` where the tree is the serialized
+//!   syntax tree of the file, each node is `{ "kind": "CALL_EXPR", "text_range": [0, 5], "children": [...] }` and each
+//!   token is `{ "kind": "IDENT", "text_range": [0, 3], "text": "foo" }`. It returns a list of diagnostics:
+//!   `[{ "message": "...", "range": [0, 5], "label": "...", "notes": ["..."], "fix": [{ "range": [0, 3], "insert": "bar" }], "applicability": "Always" }]`,
+//!   where everything except the message and the range is optional. Fixes are `MaybeIncorrect` unless an applicability
+//!   is given, so they are only applied with unsafe fixes.
+//!
+//! `rules` and `check` return the pointer to their output in the upper 32 bits and its length in the lower 32 bits.
+//! Every call runs in a new instance of the module, and is stopped once it runs out of fuel, which is
+//! [`PLUGIN_FUEL`] plus [`PLUGIN_FUEL_PER_BYTE`] for every byte of its input.
+//!
+//! Rules of a plugin are named `<plugin>/<rule>` and their group is the plugin's name. Plugins are made available
+//! to the rest of the linter with [`register_plugin`], after which their rules are found by [`get_rule_by_name`](crate::get_rule_by_name)
+//! and [`CstRuleStore::builtins`](crate::CstRuleStore::builtins) like built in rules.
+
+use crate::{Applicability, CstRule, Diagnostic, Rule, RuleCtx, Severity, Tag};
+use once_cell::sync::Lazy;
+use rslint_parser::{SyntaxKind, SyntaxNode};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fmt,
+    hash::Hash,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+use wasmi::{Engine, Linker, Module, Store};
+
+/// The amount of fuel a single call into a plugin may consume before it is stopped,
+/// most instructions consume one unit of fuel.
+pub const PLUGIN_FUEL: u64 = 50_000_000;
+
+/// The amount of fuel added to [`PLUGIN_FUEL`] for every byte of the input of a call,
+/// so plugins can read the tree of large files.
+pub const PLUGIN_FUEL_PER_BYTE: u64 = 100;
+
+static PLUGINS: Lazy<RwLock<Vec<Arc<Plugin>>>> = Lazy::new(Default::default);
+
+/// The names and docs of plugins and their rules, which are returned as `&'static str` by rules.
+/// Each string is only leaked the first time it is seen, so loading a plugin again does not leak more memory.
+static STRINGS: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+/// The fixes of plugin rules, which are returned as `&'static [Applicability]` by rules.
+static FIXES: Lazy<Mutex<HashSet<&'static [Applicability]>>> = Lazy::new(Default::default);
+
+/// Get the leaked copy of a value from a set of interned values, leaking the value if it is not in the set.
+fn intern<T: ?Sized + Hash + Eq>(
+    interned: &Mutex<HashSet<&'static T>>,
+    value: &T,
+    leak: impl FnOnce(&T) -> &'static T,
+) -> &'static T {
+    let mut interned = interned.lock().unwrap();
+    if let Some(value) = interned.get(value) {
+        return value;
+    }
+    let value = leak(value);
+    interned.insert(value);
+    value
+}
+
+fn intern_str(value: &str) -> &'static str {
+    intern(&STRINGS, value, |value| Box::leak(value.into()))
+}
+
+fn intern_fixes(value: &[Applicability]) -> &'static [Applicability] {
+    intern(&FIXES, value, |value| Box::leak(value.into()))
+}
+
+/// Make the rules of a plugin available to the linter, replacing any plugin with the same name.
+pub fn register_plugin(plugin: Plugin) -> Arc<Plugin> {
+    let plugin = Arc::new(plugin);
+    let mut plugins = PLUGINS.write().unwrap();
+    plugins.retain(|prev| prev.name != plugin.name);
+    plugins.push(plugin.clone());
+    plugin
+}
+
+/// All registered plugins, in the order they were registered.
+pub fn registered_plugins() -> Vec<Arc<Plugin>> {
+    PLUGINS.read().unwrap().clone()
+}
+
+/// The rules of every registered plugin, with their default options.
+pub fn plugin_rules() -> Vec<Box<dyn CstRule>> {
+    registered_plugins()
+        .iter()
+        .flat_map(|plugin| plugin.rules())
+        .map(|rule| Box::new(rule) as Box<dyn CstRule>)
+        .collect()
+}
+
+/// Get a rule of a registered plugin by its full name, such as `acme/no-foo`.
+pub fn get_plugin_rule(name: &str) -> Option<PluginRule> {
+    registered_plugins()
+        .iter()
+        .flat_map(|plugin| plugin.rules())
+        .find(|rule| rule.name == name)
+}
+
+/// An error which occurred while loading or running a plugin.
+#[derive(Debug)]
+pub enum PluginError {
+    Io(std::io::Error),
+    /// The module is invalid or it trapped while running.
+    Wasm(String),
+    /// The module does not follow the plugin interface.
+    Interface(String),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PluginError::Io(err) => write!(f, "{}", err),
+            PluginError::Wasm(err) => write!(f, "{}", err),
+            PluginError::Interface(err) => write!(f, "invalid plugin: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+fn wasm_err(err: impl fmt::Display) -> PluginError {
+    PluginError::Wasm(err.to_string())
+}
+
+/// A rule as described by the `rules` export of a plugin.
+#[derive(Debug, Clone, Deserialize)]
+struct RuleInfo {
+    name: String,
+    #[serde(default)]
+    docs: String,
+    #[serde(default)]
+    recommended: bool,
+    #[serde(default = "default_fixes")]
+    fixes: Vec<PluginApplicability>,
+}
+
+fn default_fixes() -> Vec<PluginApplicability> {
+    vec![PluginApplicability::MaybeIncorrect]
+}
+
+/// The applicability of a fix of a plugin, named like the variants of [`Applicability`].
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+enum PluginApplicability {
+    Always,
+    #[default]
+    MaybeIncorrect,
+    HasPlaceholders,
+    Unspecified,
+}
+
+impl From<PluginApplicability> for Applicability {
+    fn from(applicability: PluginApplicability) -> Self {
+        match applicability {
+            PluginApplicability::Always => Applicability::Always,
+            PluginApplicability::MaybeIncorrect => Applicability::MaybeIncorrect,
+            PluginApplicability::HasPlaceholders => Applicability::HasPlaceholders,
+            PluginApplicability::Unspecified => Applicability::Unspecified,
+        }
+    }
+}
+
+/// A diagnostic returned by the `check` export of a plugin.
+#[derive(Debug, Clone, Deserialize)]
+struct PluginDiagnostic {
+    message: String,
+    range: (usize, usize),
+    #[serde(default)]
+    label: String,
+    #[serde(default)]
+    notes: Vec<String>,
+    #[serde(default)]
+    fix: Vec<PluginIndel>,
+    #[serde(default)]
+    applicability: PluginApplicability,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PluginIndel {
+    range: (usize, usize),
+    #[serde(default)]
+    insert: String,
+}
+
+/// A compiled WebAssembly plugin.
+pub struct Plugin {
+    name: &'static str,
+    path: Option<PathBuf>,
+    engine: Engine,
+    module: Module,
+    // (full name, docs, recommended, fixes), the strings and fixes are interned so rules can return them as static references
+    rules: Vec<(&'static str, &'static str, bool, &'static [Applicability])>,
+}
+
+impl fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Plugin")
+            .field("name", &self.name)
+            .field("path", &self.path)
+            .field("rules", &self.rules)
+            .finish()
+    }
+}
+
+impl Plugin {
+    /// Read and compile a plugin, then ask it for its rules.
+    pub fn load(name: &str, path: &Path) -> Result<Self, PluginError> {
+        let bytes = std::fs::read(path).map_err(PluginError::Io)?;
+        let mut plugin = Self::from_bytes(name, &bytes)?;
+        plugin.path = Some(path.to_owned());
+        Ok(plugin)
+    }
+
+    /// Compile a plugin from the bytes of a `.wasm` module, then ask it for its rules.
+    pub fn from_bytes(name: &str, bytes: &[u8]) -> Result<Self, PluginError> {
+        if name.is_empty() || name.contains('/') {
+            return Err(PluginError::Interface(format!(
+                "`{}` is not a valid plugin name",
+                name
+            )));
+        }
+
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, bytes).map_err(wasm_err)?;
+        if let Some(import) = module.imports().next() {
+            return Err(PluginError::Interface(format!(
+                "plugins can not import anything, but it imports `{}::{}`",
+                import.module(),
+                import.name()
+            )));
+        }
+
+        let mut plugin = Self {
+            name: intern_str(name),
+            path: None,
+            engine,
+            module,
+            rules: vec![],
+        };
+        let output = plugin.call("rules", b"")?;
+        let rules: Vec<RuleInfo> = serde_json::from_slice(&output).map_err(|err| {
+            PluginError::Interface(format!("`rules` returned invalid JSON: {}", err))
+        })?;
+        plugin.rules = rules
+            .into_iter()
+            .map(|rule| {
+                let full_name = format!("{}/{}", name, rule.name);
+                let fixes = rule.fixes.into_iter().map(Into::into).collect::<Vec<_>>();
+                (
+                    intern_str(&full_name),
+                    intern_str(&rule.docs),
+                    rule.recommended,
+                    intern_fixes(&fixes),
+                )
+            })
+            .collect();
+        Ok(plugin)
+    }
+
+    /// The name the plugin was loaded with, which is also the group of its rules.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// The rules of this plugin with their default options.
+    pub fn rules(self: &Arc<Self>) -> Vec<PluginRule> {
+        self.rules
+            .iter()
+            .map(|(name, docs, recommended, fixes)| PluginRule {
+                name,
+                docs,
+                recommended: *recommended,
+                fixes,
+                plugin: Some(self.clone()),
+                options: Default::default(),
+            })
+            .collect()
+    }
+
+    /// Call an export of the plugin in a new instance with an input, returning its output.
+    fn call(&self, export: &str, input: &[u8]) -> Result<Vec<u8>, PluginError> {
+        let mut store = Store::new(&self.engine, ());
+        let fuel = PLUGIN_FUEL + PLUGIN_FUEL_PER_BYTE * input.len() as u64;
+        store.add_fuel(fuel).map_err(wasm_err)?;
+        let instance = Linker::<()>::new(&self.engine)
+            .instantiate(&mut store, &self.module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(wasm_err)?;
+
+        let missing = |item: &str| PluginError::Interface(format!("missing the `{}` export", item));
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| missing("memory"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|_| missing("alloc"))?;
+        let func = instance
+            .get_typed_func::<(i32, i32), i64>(&store, export)
+            .map_err(|_| missing(export))?;
+
+        let len = input.len() as i32;
+        let ptr = alloc.call(&mut store, len).map_err(wasm_err)?;
+        memory
+            .write(&mut store, ptr as u32 as usize, input)
+            .map_err(wasm_err)?;
+        let packed = func.call(&mut store, (ptr, len)).map_err(wasm_err)? as u64;
+
+        let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        let mut output = vec![0; out_len];
+        memory
+            .read(&store, out_ptr, &mut output)
+            .map_err(wasm_err)?;
+        Ok(output)
+    }
+
+    fn check(
+        &self,
+        rule: &str,
+        options: &serde_json::Value,
+        source: &str,
+        root: &SyntaxNode,
+    ) -> Result<Vec<PluginDiagnostic>, PluginError> {
+        let input = serde_json::json!({
+            "rule": rule,
+            "options": options,
+            "source": source,
+            "tree": root,
+        });
+        let input = serde_json::to_vec(&input).map_err(wasm_err)?;
+        let output = self.call("check", &input)?;
+        let diagnostics: Vec<PluginDiagnostic> =
+            serde_json::from_slice(&output).map_err(|err| {
+                PluginError::Interface(format!("`check` returned invalid JSON: {}", err))
+            })?;
+
+        let valid = |(start, end): (usize, usize)| {
+            start <= end && source.is_char_boundary(start) && source.is_char_boundary(end)
+        };
+        for diagnostic in &diagnostics {
+            if !valid(diagnostic.range) || diagnostic.fix.iter().any(|indel| !valid(indel.range)) {
+                return Err(PluginError::Interface(format!(
+                    "`check` returned a range outside of the file: {:?}",
+                    diagnostic
+                )));
+            }
+        }
+        Ok(diagnostics)
+    }
+}
+
+/// A rule provided by a [`Plugin`].
+///
+/// The options of the rule are given to the plugin as they are written in the config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginRule {
+    #[serde(skip)]
+    name: &'static str,
+    #[serde(skip)]
+    docs: &'static str,
+    #[serde(skip)]
+    recommended: bool,
+    #[serde(skip)]
+    fixes: &'static [Applicability],
+    #[serde(skip)]
+    plugin: Option<Arc<Plugin>>,
+    pub options: serde_json::Map<String, serde_json::Value>,
+}
+
+impl PluginRule {
+    pub fn with_options(mut self, options: serde_json::Map<String, serde_json::Value>) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl Rule for PluginRule {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn group(&self) -> &'static str {
+        self.plugin.as_ref().map_or("", |plugin| plugin.name)
+    }
+
+    fn docs(&self) -> &'static str {
+        self.docs
+    }
+
+    fn tags(&self) -> &'static [Tag] {
+        if self.recommended {
+            &[Tag::Recommended]
+        } else {
+            &[]
+        }
+    }
+
+    fn fixes(&self) -> &'static [Applicability] {
+        self.fixes
+    }
+}
+
+#[typetag::serde]
+impl CstRule for PluginRule {
+    fn check_root(&self, root: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
+        let plugin = self.plugin.as_ref()?;
+        let rule = &self.name[plugin.name.len() + 1..];
+        let options = serde_json::Value::Object(self.options.clone());
+
+        let diagnostics = match plugin.check(rule, &options, &ctx.src, root) {
+            Ok(diagnostics) => diagnostics,
+            Err(err) => {
+                let d = Diagnostic::new_with_code(
+                    ctx.file_id,
+                    Severity::Bug,
+                    format!("the `{}` plugin failed to run `{}`", plugin.name, rule),
+                    Some(self.name.to_string()),
+                )
+                .primary(0usize..0, "while checking this file")
+                .footer_note(err.to_string());
+                ctx.add_err(d);
+                return None;
+            }
+        };
+
+        for diagnostic in diagnostics {
+            let (start, end) = diagnostic.range;
+            let mut d = ctx
+                .err(self.name, diagnostic.message)
+                .primary(start..end, diagnostic.label);
+            for note in diagnostic.notes {
+                d = d.footer_note(note);
+            }
+            ctx.add_err(d);
+            if !diagnostic.fix.is_empty() {
+                let fixer = ctx.fix();
+                fixer.applicability(diagnostic.applicability.into());
+                for indel in diagnostic.fix {
+                    let (start, end) = indel.range;
+                    fixer.replace(start..end, indel.insert);
+                }
+            }
+        }
+        None
+    }
+
+    // plugins are given the whole file at once
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        Some(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_parser::parse_module;
+
+    /// A plugin with one rule which always reports the first 8 bytes of the file and replaces them with `void 0`.
+    /// The output of `rules` is at offset 0 and the output of `check` is at offset 512.
+    const PLUGIN: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (data (i32.const 0) "[{\"name\": \"no-debugger\", \"docs\": \"Disallow debugger.\"}]")
+            (data (i32.const 512) "[{\"message\": \"unexpected debugger\", \"range\": [0, 8], \"label\": \"remove this\", \"fix\": [{\"range\": [0, 8], \"insert\": \"void 0\"}]}]")
+            (global $heap (mut i32) (i32.const 4096))
+            (func (export "alloc") (param $len i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $heap))
+                (global.set $heap (i32.add (global.get $heap) (local.get $len)))
+                (if (i32.gt_u (global.get $heap) (i32.mul (memory.size) (i32.const 65536)))
+                    (then (drop (memory.grow (i32.add (i32.shr_u (local.get $len) (i32.const 16)) (i32.const 1))))))
+                (local.get $ptr))
+            (func (export "rules") (param i32 i32) (result i64)
+                (i64.const 55))
+            (func (export "check") (param i32 i32) (result i64)
+                (i64.const 2199023255677))
+        )
+    "#;
+
+    /// The test plugin with another output of `check`.
+    fn with_check_output(output: &str) -> String {
+        let data = PLUGIN
+            .lines()
+            .find(|line| line.contains("(i32.const 512)"))
+            .unwrap();
+        let packed = (512i64 << 32) | output.len() as i64;
+        PLUGIN
+            .replace(
+                data.trim(),
+                &format!("(data (i32.const 512) \"{}\")", output.replace('"', "\\\"")),
+            )
+            .replace(
+                "(i64.const 2199023255677)",
+                &format!("(i64.const {})", packed),
+            )
+    }
+
+    fn plugin(name: &str) -> Plugin {
+        Plugin::from_bytes(name, &wat::parse_str(PLUGIN).unwrap()).unwrap()
+    }
+
+    fn check(plugin: Plugin, src: &str) -> crate::RuleResult {
+        let rule = Arc::new(plugin).rules().remove(0);
+        let res = parse_module(src, 0);
+        crate::run_rule(&rule, 0, res.syntax(), false, &[], Arc::from(src))
+    }
+
+    #[test]
+    fn rules() {
+        let plugin = Arc::new(plugin("test"));
+        let rules = plugin.rules();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].name(), "test/no-debugger");
+        assert_eq!(rules[0].group(), "test");
+        assert_eq!(rules[0].docs(), "Disallow debugger.");
+        assert!(!rules[0].recommended());
+        assert_eq!(rules[0].fixes(), &[Applicability::MaybeIncorrect]);
+    }
+
+    #[test]
+    fn invalid_plugins() {
+        assert!(Plugin::from_bytes("a/b", &wat::parse_str(PLUGIN).unwrap()).is_err());
+        assert!(Plugin::from_bytes("test", b"not wasm").is_err());
+        let imports = r#"(module (import "env" "f" (func)))"#;
+        assert!(matches!(
+            Plugin::from_bytes("test", &wat::parse_str(imports).unwrap()),
+            Err(PluginError::Interface(_))
+        ));
+    }
+
+    #[test]
+    fn diagnostics_and_fixes() {
+        let result = check(plugin("test"), "debugger;");
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(
+            result.diagnostics[0].code.as_deref(),
+            Some("test/no-debugger")
+        );
+        assert_eq!(result.diagnostics[0].title, "unexpected debugger");
+        assert_eq!(result.fix("debugger;").as_deref(), Some("void 0;"));
+        let fix = result.diagnostics[0].fix.as_ref().unwrap();
+        assert_eq!(fix.applicability, Applicability::MaybeIncorrect);
+
+        let safe = with_check_output(
+            r#"[{"message": "unexpected debugger", "range": [0, 8], "fix": [{"range": [0, 8]}], "applicability": "Always"}]"#,
+        );
+        let safe = Plugin::from_bytes("test", &wat::parse_str(safe).unwrap()).unwrap();
+        let result = check(safe, "debugger;");
+        let fix = result.diagnostics[0].fix.as_ref().unwrap();
+        assert_eq!(fix.applicability, Applicability::Always);
+        assert_eq!(result.fix("debugger;").as_deref(), Some(";"));
+
+        // the range is outside of the file
+        let result = check(plugin("test"), "a");
+        assert_eq!(result.diagnostics[0].severity, Severity::Bug);
+    }
+
+    #[test]
+    fn traps_are_reported() {
+        let trapping = PLUGIN.replace("(i64.const 2199023255677)", "(unreachable)");
+        let plugin = Plugin::from_bytes("test", &wat::parse_str(trapping).unwrap()).unwrap();
+        let result = check(plugin, "debugger;");
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].severity, Severity::Bug);
+        assert!(result.fix("debugger;").is_none());
+    }
+
+    #[test]
+    fn runaway_plugins_are_stopped() {
+        let looping = PLUGIN.replace(
+            "(i64.const 2199023255677)",
+            "(loop $l (br $l)) (i64.const 0)",
+        );
+        let plugin = Plugin::from_bytes("test", &wat::parse_str(looping).unwrap()).unwrap();
+        let result = check(plugin, "debugger;");
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].severity, Severity::Bug);
+    }
+
+    #[test]
+    fn strings_are_interned() {
+        let first = Arc::new(plugin("interned")).rules().remove(0);
+        let second = Arc::new(plugin("interned")).rules().remove(0);
+        assert!(std::ptr::eq(first.name(), second.name()));
+        assert!(std::ptr::eq(first.docs(), second.docs()));
+    }
+
+    #[test]
+    fn registry() {
+        register_plugin(plugin("registry-test"));
+        assert!(get_plugin_rule("registry-test/no-debugger").is_some());
+        assert!(crate::get_rule_by_name("registry-test/no-debugger").is_some());
+        assert_eq!(
+            crate::get_group_rules_by_name("registry-test").map(|rules| rules.len()),
+            Some(1)
+        );
+    }
+}
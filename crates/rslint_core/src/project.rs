@@ -0,0 +1,157 @@
+//! Linting of a whole project after every file in it has been parsed.
+
+use crate::modules::{ModuleGraph, Resolver};
+use crate::{Diagnostic, File, LintResult, ProjectRule, ProjectRuleStore, RuleResult};
+use once_cell::sync::OnceCell;
+use rslint_parser::SyntaxNode;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// A file of a project along with its parsed syntax tree.
+#[derive(Debug, Clone)]
+pub struct ProjectFile<'a> {
+    /// The file, its source is empty if the project was made without trees.
+    pub file: &'a File,
+    /// The root of the file's tree, its kind is either `SCRIPT` or `MODULE`.
+    /// This is `None` if the project was made without trees because none of its rules
+    /// [need them](crate::ProjectRule::needs_trees).
+    pub root: Option<SyntaxNode>,
+}
+
+/// Every file linted in a single run, which is given to [`ProjectRule`]s.
+#[derive(Debug, Clone, Default)]
+pub struct Project<'a> {
+    files: Vec<ProjectFile<'a>>,
+    module_graph: OnceCell<Arc<ModuleGraph>>,
+}
+
+impl<'a> Project<'a> {
+    /// Make a new project out of parsed files, the files are ordered by their id.
+    pub fn new(files: impl IntoIterator<Item = ProjectFile<'a>>) -> Self {
+        let mut files = files.into_iter().collect::<Vec<_>>();
+        files.sort_by_key(|file| file.file.id);
+        Self {
+            files,
+            module_graph: OnceCell::new(),
+        }
+    }
+
+    /// Make a project out of the results of linting each file, this reuses the tree of every result.
+    ///
+    /// # Panics
+    /// Panics if `get_file` does not return a file for the id of a result.
+    pub fn from_results<'r, 's: 'r>(
+        results: impl IntoIterator<Item = &'r LintResult<'s>>,
+        get_file: impl Fn(usize) -> Option<&'a File>,
+    ) -> Self {
+        Self::new(results.into_iter().map(|result| ProjectFile {
+            file: get_file(result.file_id).expect("no file for the id of a lint result"),
+            root: Some(result.parsed.clone()),
+        }))
+    }
+
+    pub fn files(&self) -> &[ProjectFile<'a>] {
+        &self.files
+    }
+
+    /// Get a file of the project by its id.
+    pub fn file(&self, file_id: usize) -> Option<&ProjectFile<'a>> {
+        self.files.iter().find(|file| file.file.id == file_id)
+    }
+
+    /// Get the graph of imports between the files of the project, which is built the first time it is used.
+    pub fn module_graph(&self) -> Arc<ModuleGraph> {
+        self.module_graph
+            .get_or_init(|| Arc::new(ModuleGraph::new(self, &Resolver::default())))
+            .clone()
+    }
+}
+
+/// Context given to a [`ProjectRule`] when running it.
+#[derive(Debug, Clone)]
+pub struct ProjectCtx {
+    /// Whether the linter is run with the `--verbose` option.
+    pub verbose: bool,
+    /// An empty vector of diagnostics which the rule adds to, diagnostics may be for any file of the project.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ProjectCtx {
+    /// Make a new diagnostic builder for a file of the project.
+    pub fn err(
+        &mut self,
+        file_id: usize,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Diagnostic {
+        Diagnostic::error(file_id, code.into(), message.into())
+    }
+
+    pub fn add_err(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic)
+    }
+}
+
+/// The result of running project rules on a project.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectLintResult {
+    /// The diagnostics emitted by each rule run
+    pub rule_results: BTreeMap<&'static str, Vec<Diagnostic>>,
+}
+
+impl ProjectLintResult {
+    /// Get all of the diagnostics emitted by every rule.
+    pub fn diagnostics(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.rule_results.values().flatten()
+    }
+
+    /// Add the diagnostics to the results of the files they are for, so they are remapped and
+    /// emitted like the diagnostics of any other rule.
+    ///
+    /// Diagnostics for files without a result are dropped.
+    pub fn merge_into(self, results: &mut [LintResult]) {
+        for (rule, diagnostics) in self.rule_results {
+            for diagnostic in diagnostics {
+                if let Some(result) = results
+                    .iter_mut()
+                    .find(|result| result.file_id == diagnostic.file_id)
+                {
+                    result
+                        .rule_results
+                        .entry(rule)
+                        .or_insert_with(|| RuleResult::new(vec![]))
+                        .diagnostics
+                        .push(diagnostic);
+                }
+            }
+        }
+    }
+}
+
+/// Run every rule of a store on a project.
+pub fn lint_project(
+    project: &Project,
+    store: &ProjectRuleStore,
+    verbose: bool,
+) -> ProjectLintResult {
+    let rule_results = store
+        .rules
+        .iter()
+        .map(|rule| (rule.name(), run_project_rule(&**rule, project, verbose)))
+        .collect();
+
+    ProjectLintResult { rule_results }
+}
+
+/// Run a single project rule on a project.
+pub fn run_project_rule(
+    rule: &dyn ProjectRule,
+    project: &Project,
+    verbose: bool,
+) -> Vec<Diagnostic> {
+    let mut ctx = ProjectCtx {
+        verbose,
+        diagnostics: vec![],
+    };
+    rule.check_project(project, &mut ctx);
+    ctx.diagnostics
+}
@@ -3,11 +3,14 @@
 
 #![allow(unused_variables, unused_imports)]
 
-use crate::autofix::Fixer;
+use crate::analysis::AnalysisCache;
+use crate::autofix::{merge_fixes, Fixer};
+use crate::project::{Project, ProjectCtx};
+use crate::semantic::{LazySemanticModel, SemanticModel};
 use crate::Diagnostic;
 use dyn_clone::DynClone;
-use rslint_errors::Severity;
-use rslint_parser::{SyntaxNode, SyntaxNodeExt, SyntaxToken};
+use rslint_errors::{Applicability, Fix, Severity};
+use rslint_parser::{SyntaxKind, SyntaxNode, SyntaxNodeExt, SyntaxToken, TextRange};
 use rslint_text_edit::apply_indels;
 use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
@@ -48,7 +51,7 @@ pub enum Tag {
 /// - Do not be afraid to clone syntax nodes, ast nodes, and syntax tokens. They are all backed by an [`Rc`](std::rc::Rc) around Node data.
 /// therefore they can be cheaply cloned (but if you can, have your functions take a reference since Rc cloning is not zero cost).
 /// - Do not try to rely on the result of other rules, it is impossible because rules are run at the same time.
-/// - Do not rely on file data of different files. There is a separate rule type for this.
+/// - Do not rely on file data of different files, use a [`ProjectRule`] for this.
 /// - Do not unwrap pieces of an AST node (sometimes it is ok because they are guaranteed to be there), since that will cause panics
 /// with error recovery.
 /// - Do not use node or string coloring outside of diagnostic notes, it messes with termcolor and ends up looking horrible.
@@ -81,6 +84,21 @@ pub trait CstRule: Rule {
     fn check_root(&self, root: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
         None
     }
+
+    /// The kinds of nodes [`check_node`](Self::check_node) is called with, `None` if it is called with every node.
+    ///
+    /// The runner walks a file once for all rules, and only gives each node to the rules which handle its kind.
+    /// Rules should declare the kinds they check, `check_node` may then assume the node is one of them.
+    /// An empty slice means the rule does not check nodes at all. Defaults to `None`.
+    fn node_kinds(&self) -> Option<&[SyntaxKind]> {
+        None
+    }
+
+    /// Whether [`check_token`](Self::check_token) is called with every token of the file.
+    /// Defaults to `true` for rules which do not declare their [`node_kinds`](Self::node_kinds), and `false` otherwise.
+    fn needs_tokens(&self) -> bool {
+        self.node_kinds().is_none()
+    }
 }
 
 /// A generic trait which describes things common to a rule regardless on what they run on.
@@ -101,6 +119,10 @@ pub trait Rule: Debug + DynClone + Send + Sync {
     fn tags(&self) -> &'static [Tag] {
         &[]
     }
+    /// The applicability of each kind of fix this rule can make, empty if the rule does not make fixes.
+    fn fixes(&self) -> &'static [Applicability] {
+        &[]
+    }
     /// Whether this rule is recommended, this is a simple helper around [`Self::tags`].
     fn recommended(&self) -> bool {
         self.tags().iter().any(|x| x == &Tag::Recommended)
@@ -112,8 +134,33 @@ pub trait Rule: Debug + DynClone + Send + Sync {
     }
 }
 
+/// A rule which runs once after every file of a project has been parsed.
+///
+/// Project rules see every file and its syntax tree, therefore they can check things which
+/// span multiple files, such as modules which conflict with each other. They may emit diagnostics
+/// for any file of the project using the file's id.
+///
+/// Project rules are run after every [`CstRule`], they are not affected by directives
+/// in files and they must be [`Send`] + [`Sync`] like any other rule.
+#[typetag::serde]
+pub trait ProjectRule: Rule {
+    /// Check the project, adding any diagnostics to the context.
+    fn check_project(&self, project: &Project, ctx: &mut ProjectCtx);
+
+    /// Whether the rule reads the syntax trees of the files of the project.
+    ///
+    /// The trees of every file are only kept until the project rules are run if a rule needs them,
+    /// rules which only look at the paths of files should return `false` so files can be emitted as
+    /// soon as they are linted. The [`ProjectFile::root`](crate::ProjectFile::root) of every file is
+    /// `None` if no rule needs trees.
+    fn needs_trees(&self) -> bool {
+        true
+    }
+}
+
 dyn_clone::clone_trait_object!(Rule);
 dyn_clone::clone_trait_object!(CstRule);
+dyn_clone::clone_trait_object!(ProjectRule);
 
 /// A trait describing rules for which their configuration can be automatically deduced (inferred) using
 /// parsed syntax trees
@@ -124,12 +171,47 @@ pub trait Inferable: CstRule {
 }
 
 /// The level configured for a rule.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
 pub enum RuleLevel {
+    /// The rule is not run.
+    Off,
+    /// Diagnostics are shown as help messages.
+    Hint,
+    /// Diagnostics are shown as notes.
+    Info,
+    #[serde(rename = "warn", alias = "warning")]
     Warning,
     Error,
 }
 
+impl RuleLevel {
+    /// Remap the error and warning diagnostics of a rule to this level.
+    ///
+    /// Warnings are left untouched for the error and warning levels because rules should be able
+    /// to emit errors and warnings for context without the warnings being remapped to errors.
+    pub fn remap_diagnostics(self, diagnostics: &mut Vec<Diagnostic>) {
+        if self == RuleLevel::Off {
+            diagnostics.clear();
+            return;
+        }
+
+        for diagnostic in diagnostics.iter_mut() {
+            diagnostic.severity = match (diagnostic.severity, self) {
+                (Severity::Error, RuleLevel::Warning) => Severity::Warning,
+                (Severity::Error, RuleLevel::Info) | (Severity::Warning, RuleLevel::Info) => {
+                    Severity::Note
+                }
+                (Severity::Error, RuleLevel::Hint) | (Severity::Warning, RuleLevel::Hint) => {
+                    Severity::Help
+                }
+                (severity, _) => severity,
+            };
+        }
+    }
+}
+
 /// Context given to a rule when running it.
 // This is passed by reference and not by Arc, which is very important,
 // Arcs are very expensive to copy, and for 50 rules running on 50 files we will have a total of
@@ -143,8 +225,13 @@ pub struct RuleCtx {
     pub verbose: bool,
     /// An empty vector of diagnostics which the rule adds to.
     pub diagnostics: Vec<Diagnostic>,
-    pub fixer: Option<Fixer>,
+    /// The fixers made by the rule and the number of diagnostics there were when each was made.
+    pub(crate) fixers: Vec<(usize, Fixer)>,
     pub src: Arc<str>,
+    /// The semantic model of the file, which is shared by every rule run on the file.
+    pub(crate) semantic: LazySemanticModel,
+    /// The data derived from the file by rules, which is shared by every rule run on the file.
+    pub(crate) analysis: AnalysisCache,
 }
 
 impl RuleCtx {
@@ -157,11 +244,43 @@ impl RuleCtx {
         self.diagnostics.push(diagnostic)
     }
 
-    /// Make a new fixer for this context and return a mutable reference to it
+    /// Make a new fixer for the last diagnostic added and return a mutable reference to it.
+    ///
+    /// Every diagnostic has its own fix, so a diagnostic must be added before it is fixed.
+    /// Making another fixer for the same diagnostic replaces its fix.
     pub fn fix(&mut self) -> &mut Fixer {
         let fixer = Fixer::new(self.src.clone());
-        self.fixer = Some(fixer);
-        self.fixer.as_mut().unwrap()
+        self.fixers.push((self.diagnostics.len(), fixer));
+        &mut self.fixers.last_mut().unwrap().1
+    }
+
+    /// Take the diagnostics added to this context, with the fixes made for them.
+    pub(crate) fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        let mut diagnostics = std::mem::take(&mut self.diagnostics);
+        for (count, fixer) in self.fixers.drain(..) {
+            if let Some(diagnostic) = count.checked_sub(1).and_then(|i| diagnostics.get_mut(i)) {
+                diagnostic.fix = if fixer.indels.is_empty() {
+                    None
+                } else {
+                    Some(Fix::new(fixer.indels, fixer.applicability))
+                };
+            }
+        }
+        diagnostics
+    }
+
+    /// Get the scopes, declarations and references of the file being linted.
+    ///
+    /// The model is built the first time any rule run on the file requests it.
+    pub fn semantic(&self) -> Arc<SemanticModel> {
+        self.semantic.get()
+    }
+
+    /// Get the cache of data derived from the file being linted, such as parsed regexes.
+    ///
+    /// Values computed by a rule are reused by every other rule run on the file.
+    pub fn analysis(&self) -> &AnalysisCache {
+        &self.analysis
     }
 
     /// Create a context which is used to simply run a rule without needing to know about
@@ -171,8 +290,10 @@ impl RuleCtx {
             file_id: 0,
             verbose: false,
             diagnostics: vec![],
-            fixer: None,
+            fixers: vec![],
             src: Arc::from(String::new()),
+            semantic: LazySemanticModel::new(rslint_parser::parse_text("", 0).syntax()),
+            analysis: AnalysisCache::new(),
         }
     }
 }
@@ -181,15 +302,17 @@ impl RuleCtx {
 #[derive(Debug, Clone)]
 pub struct RuleResult {
     pub diagnostics: Vec<Diagnostic>,
-    pub fixer: Option<Fixer>,
+    /// The range of the node or token which was being checked when each diagnostic was emitted,
+    /// diagnostics are only reused when relinting incrementally if this matches them.
+    pub(crate) origins: Vec<TextRange>,
 }
 
 impl RuleResult {
-    /// Make a new rule result with diagnostics and an optional fixer.
-    pub fn new(diagnostics: Vec<Diagnostic>, fixer: impl Into<Option<Fixer>>) -> Self {
+    /// Make a new rule result with diagnostics.
+    pub fn new(diagnostics: Vec<Diagnostic>) -> Self {
         Self {
             diagnostics,
-            fixer: fixer.into(),
+            origins: vec![],
         }
     }
 
@@ -198,18 +321,41 @@ impl RuleResult {
         Outcome::from(&self.diagnostics)
     }
 
-    /// Merge two results, this will join `self` and `other`'s diagnostics and take
-    /// `self`'s fixer if available or otherwise take `other`'s fixer
+    /// Merge two results, this will join `self` and `other`'s diagnostics
     pub fn merge(self, other: RuleResult) -> RuleResult {
+        let origins = if self.has_origins() && other.has_origins() {
+            [self.origins, other.origins].concat()
+        } else {
+            vec![]
+        };
         RuleResult {
             diagnostics: [self.diagnostics, other.diagnostics].concat(),
-            fixer: self.fixer.or(other.fixer),
+            origins,
         }
     }
 
-    /// Attempt to fix the issue if the rule can be autofixed.
-    pub fn fix(&self) -> Option<String> {
-        self.fixer.as_ref().map(|x| x.apply())
+    /// Whether the origin of every diagnostic is known.
+    pub(crate) fn has_origins(&self) -> bool {
+        self.origins.len() == self.diagnostics.len()
+    }
+
+    /// Apply the fixes of the diagnostics to the source code the rule was run on, including fixes
+    /// which may be incorrect. Fixes which overlap an earlier fix are skipped.
+    ///
+    /// Returns `None` if none of the diagnostics can be fixed.
+    pub fn fix(&self, src: &str) -> Option<String> {
+        let fixes = self
+            .diagnostics
+            .iter()
+            .filter_map(|d| d.fix.as_ref())
+            .filter(|fix| fix.is_applied(true))
+            .collect::<Vec<_>>();
+        if fixes.is_empty() {
+            return None;
+        }
+        let mut fixed = src.to_string();
+        apply_indels(&merge_fixes(fixes), &mut fixed);
+        Some(fixed)
     }
 }
 
@@ -333,6 +479,10 @@ macro_rules! __declare_lint_inner {
         $(
             tags($($tag:ident),* $(,)?),
         )?
+        // The applicability of the fixes the rule makes
+        $(
+            fixes($($fix:ident),* $(,)?),
+        )?
         // A unique kebab-case name for the rule
         $code:literal
         $(,
@@ -385,6 +535,12 @@ macro_rules! __declare_lint_inner {
                 }
             )?
 
+            $(
+                fn fixes(&self) -> &'static [$crate::Applicability] {
+                    &[$($crate::Applicability::$fix),*]
+                }
+            )?
+
             #[cfg(feature = "schema")]
             fn schema(&self) -> Option<schemars::schema::RootSchema> {
                 Some(schemars::schema_for!($name))
@@ -402,6 +558,8 @@ macro_rules! __declare_lint_inner {
 ///     RuleName,
 ///     // The name of the group this rule belongs to.
 ///     groupname,
+///     // The applicability of the fixes the rule makes, if it makes any.
+///     fixes(Always),
 ///     // Make sure this is kebab-case and unique.
 ///     "rule-name",
 ///     /// A description of the attribute here, used for config docs.
@@ -0,0 +1,407 @@
+//! Building of a semantic model from a syntax tree.
+//!
+//! Scopes and declarations are collected in a single preorder walk of the tree, references are
+//! collected along with them but only resolved once the walk is done so they can see hoisted declarations.
+
+use super::*;
+use rslint_parser::{ast::VarDecl, AstNode, SyntaxKind, SyntaxNodeExt, WalkEvent, T};
+use SyntaxKind::*;
+
+pub(super) fn build(root: &SyntaxNode) -> SemanticModel {
+    let mut builder = Builder {
+        model: SemanticModel {
+            scopes: vec![],
+            declarations: vec![],
+            references: vec![],
+            node_scopes: HashMap::new(),
+            node_declarations: HashMap::new(),
+            node_references: HashMap::new(),
+        },
+        stack: vec![],
+    };
+
+    for event in root.preorder() {
+        match event {
+            WalkEvent::Enter(node) => builder.enter(node),
+            WalkEvent::Leave(node) => {
+                if scope_kind(&node).is_some() {
+                    builder.stack.pop();
+                }
+            }
+        }
+    }
+    builder.resolve();
+    builder.model
+}
+
+struct Builder {
+    model: SemanticModel,
+    stack: Vec<ScopeId>,
+}
+
+impl Builder {
+    fn enter(&mut self, node: SyntaxNode) {
+        if let Some(kind) = scope_kind(&node) {
+            self.push_scope(kind, &node);
+        }
+
+        match node.kind() {
+            NAME => self.name(node),
+            NAME_REF => self.name_ref(node),
+            TS_TYPE_NAME => self.type_name(node),
+            TS_TYPE_PARAM | TS_MAPPED_TYPE_PARAM => {
+                if let Some(ident) = node.token_with_kind(T![ident]) {
+                    let range = ident.text_range();
+                    self.declare(
+                        self.current(),
+                        DeclarationKind::TypeParam,
+                        node,
+                        ident.text().clone(),
+                        range,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn current(&self) -> ScopeId {
+        *self
+            .stack
+            .last()
+            .expect("a scope is always entered at the root")
+    }
+
+    /// The scope the current scope is nested in, used for names of declarations which create a scope.
+    fn enclosing(&self) -> ScopeId {
+        let idx = self.stack.len().saturating_sub(2);
+        self.stack[idx]
+    }
+
+    fn var_scope(&self) -> ScopeId {
+        self.stack
+            .iter()
+            .rev()
+            .copied()
+            .find(|id| self.model.scope(*id).kind.is_var_scope())
+            .unwrap_or_else(|| self.model.root_scope())
+    }
+
+    fn push_scope(&mut self, kind: ScopeKind, node: &SyntaxNode) {
+        let id = ScopeId(self.model.scopes.len());
+        let parent = self.stack.last().copied();
+        if let Some(parent) = parent {
+            self.model.scopes[parent.0].children.push(id);
+        }
+        self.model.scopes.push(Scope {
+            kind,
+            node: node.clone(),
+            parent,
+            children: vec![],
+            declarations: vec![],
+            references: vec![],
+            names: HashMap::new(),
+        });
+        self.model.node_scopes.insert(node.clone(), id);
+        self.stack.push(id);
+    }
+
+    fn declare(
+        &mut self,
+        scope: ScopeId,
+        kind: DeclarationKind,
+        node: SyntaxNode,
+        name: SmolStr,
+        range: TextRange,
+    ) {
+        let id = DeclarationId(self.model.declarations.len());
+        let scope_data = &mut self.model.scopes[scope.0];
+        scope_data.declarations.push(id);
+        scope_data.names.entry(name.clone()).or_default().push(id);
+        self.model.node_declarations.insert(node.clone(), id);
+        self.model.declarations.push(Declaration {
+            name,
+            kind,
+            node,
+            range,
+            scope,
+            references: vec![],
+        });
+    }
+
+    fn declare_name(&mut self, scope: ScopeId, kind: DeclarationKind, node: SyntaxNode) {
+        let name = SmolStr::new(node.trimmed_text().to_string());
+        // `this` parameters in TypeScript only annotate the type of `this`
+        if name == "this" {
+            return;
+        }
+        let range = node.trimmed_range();
+        self.declare(scope, kind, node, name, range);
+    }
+
+    fn reference(
+        &mut self,
+        kind: ReferenceKind,
+        node: SyntaxNode,
+        name: SmolStr,
+        range: TextRange,
+    ) {
+        let id = ReferenceId(self.model.references.len());
+        let scope = self.current();
+        self.model.scopes[scope.0].references.push(id);
+        self.model.node_references.insert(node.clone(), id);
+        self.model.references.push(Reference {
+            name,
+            kind,
+            node,
+            range,
+            scope,
+            declaration: None,
+        });
+    }
+
+    fn reference_name(&mut self, kind: ReferenceKind, node: SyntaxNode) {
+        let name = SmolStr::new(node.trimmed_text().to_string());
+        let range = node.trimmed_range();
+        self.reference(kind, node, name, range);
+    }
+
+    fn name(&mut self, node: SyntaxNode) {
+        let parent = match node.parent() {
+            Some(parent) => parent,
+            None => return,
+        };
+
+        match parent.kind() {
+            SINGLE_PATTERN => self.pattern_name(node, &parent),
+            // `function foo(a = 1) {}` has the name directly in the assign pattern
+            ASSIGN_PATTERN if parent.first_child().as_ref() == Some(&node) => {
+                self.pattern_name(node, &parent)
+            }
+            ARROW_EXPR | TS_CONSTRUCTOR_PARAM => {
+                self.declare_name(self.current(), DeclarationKind::Param, node)
+            }
+            FN_DECL => self.declare_name(self.enclosing(), DeclarationKind::Function, node),
+            CLASS_DECL => self.declare_name(self.enclosing(), DeclarationKind::Class, node),
+            // `export default function foo() {}` declares `foo` in the module, while the name of
+            // any other function or class expression is only visible inside of it
+            FN_EXPR | CLASS_EXPR => {
+                let kind = if parent.kind() == FN_EXPR {
+                    DeclarationKind::Function
+                } else {
+                    DeclarationKind::Class
+                };
+                let scope = if parent.parent().map(|p| p.kind()) == Some(EXPORT_DEFAULT_EXPR) {
+                    self.enclosing()
+                } else {
+                    self.current()
+                };
+                self.declare_name(scope, kind, node)
+            }
+            IMPORT_DECL | WILDCARD_IMPORT | TS_IMPORT_EQUALS_DECL => {
+                self.declare_name(self.current(), DeclarationKind::Import, node)
+            }
+            SPECIFIER => self.specifier_name(node, &parent),
+            TS_TYPE_ALIAS_DECL | TS_INTERFACE_DECL => {
+                self.declare_name(self.enclosing(), DeclarationKind::Type, node)
+            }
+            TS_NAMESPACE_DECL | TS_MODULE_DECL => {
+                self.declare_name(self.enclosing(), DeclarationKind::Namespace, node)
+            }
+            TS_ENUM => self.declare_name(self.current(), DeclarationKind::Enum, node),
+            TS_INFER => self.declare_name(self.current(), DeclarationKind::TypeParam, node),
+            // `{ foo }` is shorthand for `{ foo: foo }`
+            IDENT_PROP => self.reference_name(ReferenceKind::Read, node),
+            _ => {}
+        }
+    }
+
+    /// A name bound by a pattern, which is either a declaration or the target of an assignment.
+    fn pattern_name(&mut self, node: SyntaxNode, pattern: &SyntaxNode) {
+        let owner = match pattern_owner(pattern) {
+            Some(owner) => owner,
+            None => return,
+        };
+
+        match owner.kind() {
+            DECLARATOR => {
+                let decl = match owner.parent().and_then(VarDecl::cast) {
+                    Some(decl) => decl,
+                    None => return,
+                };
+                if decl.is_var() {
+                    self.declare_name(self.var_scope(), DeclarationKind::Var, node)
+                } else if decl.is_const() {
+                    self.declare_name(self.current(), DeclarationKind::Const, node)
+                } else {
+                    self.declare_name(self.current(), DeclarationKind::Let, node)
+                }
+            }
+            PARAMETER_LIST | ARROW_EXPR => {
+                self.declare_name(self.current(), DeclarationKind::Param, node)
+            }
+            CATCH_CLAUSE => self.declare_name(self.current(), DeclarationKind::CatchParam, node),
+            ASSIGN_EXPR | FOR_STMT_INIT => self.reference_name(ReferenceKind::Write, node),
+            _ => {}
+        }
+    }
+
+    fn specifier_name(&mut self, node: SyntaxNode, specifier: &SyntaxNode) {
+        let list = match specifier.parent() {
+            Some(list) => list,
+            None => return,
+        };
+        let names = specifier
+            .children()
+            .filter(|child| child.kind() == NAME)
+            .collect::<Vec<_>>();
+
+        match list.kind() {
+            // `import { a as b }` declares `b`
+            NAMED_IMPORTS if names.last() == Some(&node) => {
+                self.declare_name(self.current(), DeclarationKind::Import, node)
+            }
+            // `export { a as b }` refers to a local `a`, unless it is reexported from another module
+            EXPORT_NAMED
+                if names.first() == Some(&node) && list.token_with_kind(T![from]).is_none() =>
+            {
+                self.reference_name(ReferenceKind::Read, node)
+            }
+            _ => {}
+        }
+    }
+
+    fn name_ref(&mut self, node: SyntaxNode) {
+        let parent = match node.parent() {
+            Some(parent) => parent,
+            None => return,
+        };
+        let is_first_child = parent.first_child().as_ref() == Some(&node);
+
+        let kind = match parent.kind() {
+            // labels are not variables
+            BREAK_STMT | CONTINUE_STMT => return,
+            ASSIGN_EXPR if is_first_child => {
+                if parent.token_with_kind(T![=]).is_some() {
+                    ReferenceKind::Write
+                } else {
+                    ReferenceKind::ReadWrite
+                }
+            }
+            UNARY_EXPR
+                if parent.token_with_kind(T![++]).is_some()
+                    || parent.token_with_kind(T![--]).is_some() =>
+            {
+                ReferenceKind::ReadWrite
+            }
+            FOR_STMT_INIT
+                if matches!(
+                    parent.parent().map(|p| p.kind()),
+                    Some(FOR_IN_STMT) | Some(FOR_OF_STMT)
+                ) =>
+            {
+                ReferenceKind::Write
+            }
+            _ => ReferenceKind::Read,
+        };
+        self.reference_name(kind, node);
+    }
+
+    fn type_name(&mut self, node: SyntaxNode) {
+        let mut parent = match node.parent() {
+            Some(parent) => parent,
+            None => return,
+        };
+        if parent.kind() == TS_QUALIFIED_PATH {
+            // only the first name of `a.b.c` refers to a declaration
+            if parent.first_child().as_ref() != Some(&node) {
+                return;
+            }
+            parent = match parent.parent() {
+                Some(parent) => parent,
+                None => return,
+            };
+        }
+
+        let kind = if matches!(parent.kind(), TS_TYPE_QUERY | TS_TYPE_QUERY_EXPR) {
+            ReferenceKind::Read
+        } else {
+            ReferenceKind::Type
+        };
+        if let Some(ident) = node.token_with_kind(T![ident]) {
+            let range = ident.text_range();
+            self.reference(kind, node, ident.text().clone(), range);
+        }
+    }
+
+    fn resolve(&mut self) {
+        for idx in 0..self.model.references.len() {
+            let reference = &self.model.references[idx];
+            let decl = if reference.kind == ReferenceKind::Type {
+                self.model.lookup_type(reference.scope, &reference.name)
+            } else {
+                self.model.lookup(reference.scope, &reference.name)
+            };
+
+            if let Some(decl) = decl {
+                self.model.references[idx].declaration = Some(decl);
+                self.model.declarations[decl.0]
+                    .references
+                    .push(ReferenceId(idx));
+            }
+        }
+    }
+}
+
+/// The kind of scope a node creates, if it creates one.
+fn scope_kind(node: &SyntaxNode) -> Option<ScopeKind> {
+    Some(match node.kind() {
+        SCRIPT => ScopeKind::Script,
+        MODULE => ScopeKind::Module,
+        FN_DECL | FN_EXPR | ARROW_EXPR | METHOD | GETTER | SETTER | CONSTRUCTOR => {
+            ScopeKind::Function
+        }
+        // the body of a function or a catch clause is part of their scope
+        BLOCK_STMT
+            if !matches!(
+                node.parent().map(|p| p.kind()),
+                Some(FN_DECL)
+                    | Some(FN_EXPR)
+                    | Some(ARROW_EXPR)
+                    | Some(METHOD)
+                    | Some(GETTER)
+                    | Some(SETTER)
+                    | Some(CONSTRUCTOR)
+                    | Some(CATCH_CLAUSE)
+            ) =>
+        {
+            ScopeKind::Block
+        }
+        FOR_STMT | FOR_IN_STMT | FOR_OF_STMT | SWITCH_STMT => ScopeKind::Block,
+        CLASS_DECL | CLASS_EXPR => ScopeKind::Class,
+        CATCH_CLAUSE => ScopeKind::Catch,
+        TS_NAMESPACE_DECL | TS_MODULE_DECL => ScopeKind::TsNamespace,
+        TS_TYPE_ALIAS_DECL
+        | TS_INTERFACE_DECL
+        | TS_MAPPED_TYPE
+        | TS_FN_TYPE
+        | TS_CONSTRUCTOR_TYPE
+        | TS_CALL_SIGNATURE_DECL
+        | TS_CONSTRUCT_SIGNATURE_DECL
+        | TS_METHOD_SIGNATURE => ScopeKind::TsType,
+        _ => return None,
+    })
+}
+
+/// The node which owns an entire (possibly nested) pattern, such as a declarator or a parameter list.
+fn pattern_owner(pattern: &SyntaxNode) -> Option<SyntaxNode> {
+    let mut node = pattern.clone();
+    loop {
+        let parent = node.parent()?;
+        match parent.kind() {
+            SINGLE_PATTERN | OBJECT_PATTERN | ARRAY_PATTERN | ASSIGN_PATTERN | REST_PATTERN
+            | KEY_VALUE_PATTERN => node = parent,
+            _ => return Some(parent),
+        }
+    }
+}
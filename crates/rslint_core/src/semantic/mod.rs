@@ -0,0 +1,519 @@
+//! Scope and binding analysis of a syntax tree.
+//!
+//! The [`SemanticModel`] of a file records every scope in it, the declarations made in each scope,
+//! and every reference to a name along with the declaration it resolves to. Rules can get the model
+//! of the file they are linting through [`RuleCtx::semantic`](crate::RuleCtx::semantic), it is only
+//! built the first time a rule asks for it and it is shared by every rule run on the file.
+//!
+//! Resolution happens after every declaration in the file was collected, therefore references resolve
+//! to `var` declarations, functions and imports which appear after them (hoisting), as well as to `let`,
+//! `const` and `class` declarations which appear after them (which would be a runtime error if evaluated).
+//! Rules which care about the order should compare the ranges of the reference and the declaration.
+
+mod builder;
+
+use once_cell::sync::OnceCell;
+use rslint_parser::{SmolStr, SyntaxNode, TextRange};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+macro_rules! id {
+    ($($(#[$meta:meta])* $name:ident),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+            pub struct $name(usize);
+        )*
+    };
+}
+
+id! {
+    /// The index of a [`Scope`] in a [`SemanticModel`].
+    ScopeId,
+    /// The index of a [`Declaration`] in a [`SemanticModel`].
+    DeclarationId,
+    /// The index of a [`Reference`] in a [`SemanticModel`].
+    ReferenceId,
+}
+
+/// The kind of node which created a scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScopeKind {
+    /// The root of a script.
+    Script,
+    /// The root of an ES module.
+    Module,
+    /// A function, arrow function, method, accessor or constructor, including its parameters.
+    Function,
+    /// A block statement, a `switch` statement, or a `for` loop including its head.
+    Block,
+    /// A class, including its name, heritage clauses and type parameters.
+    Class,
+    /// A `catch` clause, including its parameter and its body.
+    Catch,
+    /// A TypeScript namespace or module declaration.
+    TsNamespace,
+    /// A TypeScript type alias, interface, mapped type or signature, which holds its type parameters.
+    TsType,
+}
+
+impl ScopeKind {
+    /// Whether `var` declarations inside of this scope (and any nested block scopes) are declared in this scope.
+    pub fn is_var_scope(self) -> bool {
+        matches!(
+            self,
+            ScopeKind::Script | ScopeKind::Module | ScopeKind::Function | ScopeKind::TsNamespace
+        )
+    }
+}
+
+/// A single scope in a file.
+#[derive(Debug, Clone)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    /// The node which created this scope.
+    pub node: SyntaxNode,
+    /// The scope this scope is nested in, only `None` for the root scope.
+    pub parent: Option<ScopeId>,
+    /// The scopes directly nested in this scope.
+    pub children: Vec<ScopeId>,
+    /// The declarations made in this scope, in the order they appear in.
+    pub declarations: Vec<DeclarationId>,
+    /// The references made directly in this scope, not including references in nested scopes.
+    pub references: Vec<ReferenceId>,
+    names: HashMap<SmolStr, Vec<DeclarationId>>,
+}
+
+/// The way a name was declared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeclarationKind {
+    Var,
+    Let,
+    Const,
+    /// A function or constructor parameter.
+    Param,
+    /// The parameter of a `catch` clause.
+    CatchParam,
+    /// A default, named, namespace or TypeScript `import x = ...` import.
+    Import,
+    Class,
+    Function,
+    /// A TypeScript type alias or interface.
+    Type,
+    /// A TypeScript type parameter, including mapped type parameters and `infer` types.
+    TypeParam,
+    /// A TypeScript enum.
+    Enum,
+    /// A TypeScript namespace or module.
+    Namespace,
+}
+
+impl DeclarationKind {
+    /// Whether this declaration can be referred to by an expression.
+    pub fn is_value(self) -> bool {
+        !matches!(self, DeclarationKind::Type | DeclarationKind::TypeParam)
+    }
+
+    /// Whether this declaration can be referred to by a type.
+    pub fn is_type(self) -> bool {
+        matches!(
+            self,
+            DeclarationKind::Import
+                | DeclarationKind::Class
+                | DeclarationKind::Type
+                | DeclarationKind::TypeParam
+                | DeclarationKind::Enum
+                | DeclarationKind::Namespace
+        )
+    }
+
+    /// Whether this declaration can be used before the place it appears at without throwing an error at runtime.
+    pub fn is_hoisted(self) -> bool {
+        matches!(
+            self,
+            DeclarationKind::Var
+                | DeclarationKind::Function
+                | DeclarationKind::Import
+                | DeclarationKind::Type
+                | DeclarationKind::TypeParam
+        )
+    }
+}
+
+/// A name declared in a scope.
+#[derive(Debug, Clone)]
+pub struct Declaration {
+    pub name: SmolStr,
+    pub kind: DeclarationKind,
+    /// The `NAME` node of the declaration, or the `TS_TYPE_PARAM` or `TS_MAPPED_TYPE_PARAM` node of a type parameter.
+    pub node: SyntaxNode,
+    /// The range of the declared name.
+    pub range: TextRange,
+    /// The scope the name is declared in, this is the enclosing function scope for `var` declarations.
+    pub scope: ScopeId,
+    /// Every reference which resolves to this declaration, in the order they appear in.
+    pub references: Vec<ReferenceId>,
+}
+
+/// The way a name is used by a reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceKind {
+    /// The value is read, such as `foo` in `foo + 1`.
+    Read,
+    /// The value is overwritten, such as `foo` in `foo = 1` or `[foo] = bar`.
+    Write,
+    /// The value is both read and written, such as `foo` in `foo += 1` or `foo++`.
+    ReadWrite,
+    /// The name is used in a TypeScript type, such as `Foo` in `let a: Foo`.
+    Type,
+}
+
+/// A use of a name, initializers of declarations are not references.
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub name: SmolStr,
+    pub kind: ReferenceKind,
+    /// The `NAME_REF`, `NAME` or `TS_TYPE_NAME` node of the reference.
+    pub node: SyntaxNode,
+    /// The range of the referenced name.
+    pub range: TextRange,
+    /// The scope the reference is made in.
+    pub scope: ScopeId,
+    /// The declaration the name resolves to, `None` for names which are not declared in the file such as globals.
+    pub declaration: Option<DeclarationId>,
+}
+
+impl Reference {
+    /// Whether the reference reads the value of the name.
+    pub fn is_read(&self) -> bool {
+        matches!(self.kind, ReferenceKind::Read | ReferenceKind::ReadWrite)
+    }
+
+    /// Whether the reference writes to the name.
+    pub fn is_write(&self) -> bool {
+        matches!(self.kind, ReferenceKind::Write | ReferenceKind::ReadWrite)
+    }
+}
+
+/// The scopes, declarations and references of a single file.
+#[derive(Debug, Clone)]
+pub struct SemanticModel {
+    scopes: Vec<Scope>,
+    declarations: Vec<Declaration>,
+    references: Vec<Reference>,
+    node_scopes: HashMap<SyntaxNode, ScopeId>,
+    node_declarations: HashMap<SyntaxNode, DeclarationId>,
+    node_references: HashMap<SyntaxNode, ReferenceId>,
+}
+
+impl SemanticModel {
+    /// Build the model of a file from its root, the root's kind should be `SCRIPT` or `MODULE`.
+    pub fn new(root: &SyntaxNode) -> Self {
+        builder::build(root)
+    }
+
+    /// The scope of the root of the file.
+    pub fn root_scope(&self) -> ScopeId {
+        ScopeId(0)
+    }
+
+    pub fn scope(&self, id: ScopeId) -> &Scope {
+        &self.scopes[id.0]
+    }
+
+    pub fn declaration(&self, id: DeclarationId) -> &Declaration {
+        &self.declarations[id.0]
+    }
+
+    pub fn reference(&self, id: ReferenceId) -> &Reference {
+        &self.references[id.0]
+    }
+
+    /// All scopes in the file, in the order they start in.
+    pub fn scopes(&self) -> impl Iterator<Item = (ScopeId, &Scope)> {
+        self.scopes.iter().enumerate().map(|(i, s)| (ScopeId(i), s))
+    }
+
+    /// All declarations in the file, in the order they appear in.
+    pub fn declarations(&self) -> impl Iterator<Item = (DeclarationId, &Declaration)> {
+        self.declarations
+            .iter()
+            .enumerate()
+            .map(|(i, d)| (DeclarationId(i), d))
+    }
+
+    /// All references in the file, in the order they appear in.
+    pub fn references(&self) -> impl Iterator<Item = (ReferenceId, &Reference)> {
+        self.references
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (ReferenceId(i), r))
+    }
+
+    /// References to names which are not declared in the file, such as globals.
+    pub fn unresolved_references(&self) -> impl Iterator<Item = &Reference> {
+        self.references.iter().filter(|r| r.declaration.is_none())
+    }
+
+    /// The scope created by a node, if it creates one.
+    pub fn node_scope(&self, node: &SyntaxNode) -> Option<ScopeId> {
+        self.node_scopes.get(node).copied()
+    }
+
+    /// The innermost scope created by the node or one of its ancestors.
+    ///
+    /// Note that the names of function and class declarations are declared in the parent
+    /// of the scope their `NAME` node is contained in.
+    pub fn scope_of(&self, node: &SyntaxNode) -> ScopeId {
+        node.ancestors()
+            .find_map(|node| self.node_scope(&node))
+            .unwrap_or_else(|| self.root_scope())
+    }
+
+    /// The declaration made by a `NAME` node, or by a type parameter node.
+    pub fn declaration_of(&self, node: &SyntaxNode) -> Option<DeclarationId> {
+        self.node_declarations.get(node).copied()
+    }
+
+    /// The reference made by a `NAME_REF`, `NAME` or `TS_TYPE_NAME` node.
+    pub fn reference_of(&self, node: &SyntaxNode) -> Option<ReferenceId> {
+        self.node_references.get(node).copied()
+    }
+
+    /// Resolve the reference made by a node to the declaration it refers to.
+    pub fn resolve(&self, node: &SyntaxNode) -> Option<&Declaration> {
+        let reference = self.reference(self.reference_of(node)?);
+        Some(self.declaration(reference.declaration?))
+    }
+
+    /// Find the declaration an expression using `name` in `scope` would resolve to.
+    pub fn lookup(&self, scope: ScopeId, name: &str) -> Option<DeclarationId> {
+        self.lookup_with(scope, name, DeclarationKind::is_value)
+    }
+
+    /// Find the declaration a type using `name` in `scope` would resolve to.
+    pub fn lookup_type(&self, scope: ScopeId, name: &str) -> Option<DeclarationId> {
+        self.lookup_with(scope, name, DeclarationKind::is_type)
+    }
+
+    fn lookup_with(
+        &self,
+        scope: ScopeId,
+        name: &str,
+        filter: fn(DeclarationKind) -> bool,
+    ) -> Option<DeclarationId> {
+        let mut scope = Some(scope);
+        while let Some(id) = scope {
+            let found = self.scope(id).names.get(name).and_then(|decls| {
+                decls
+                    .iter()
+                    .copied()
+                    .find(|decl| filter(self.declaration(*decl).kind))
+            });
+            if found.is_some() {
+                return found;
+            }
+            scope = self.scope(id).parent;
+        }
+        None
+    }
+
+    /// Whether the value of a declaration is read anywhere, types refering to it count as reads.
+    pub fn is_read(&self, id: DeclarationId) -> bool {
+        self.declaration(id).references.iter().any(|r| {
+            let reference = self.reference(*r);
+            reference.is_read() || reference.kind == ReferenceKind::Type
+        })
+    }
+
+    /// The declaration in an enclosing scope which has the same name as this declaration and is therefore shadowed by it.
+    pub fn shadowed(&self, id: DeclarationId) -> Option<DeclarationId> {
+        let decl = self.declaration(id);
+        let parent = self.scope(decl.scope).parent?;
+        let filter = if decl.kind.is_value() {
+            DeclarationKind::is_value
+        } else {
+            DeclarationKind::is_type
+        };
+        self.lookup_with(parent, &decl.name, filter)
+    }
+}
+
+/// A [`SemanticModel`] which is built the first time it is requested, clones share the same model.
+#[derive(Debug, Clone)]
+pub struct LazySemanticModel {
+    root: SyntaxNode,
+    model: Arc<OnceCell<Arc<SemanticModel>>>,
+}
+
+impl LazySemanticModel {
+    pub fn new(root: SyntaxNode) -> Self {
+        Self {
+            root,
+            model: Default::default(),
+        }
+    }
+
+    /// Get the model, building it if this is the first time it is requested.
+    pub fn get(&self) -> Arc<SemanticModel> {
+        self.model
+            .get_or_init(|| Arc::new(SemanticModel::new(&self.root)))
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_parser::{parse_module, parse_text, parse_with_syntax, Syntax, SyntaxKind};
+
+    fn decl_kind(model: &SemanticModel, name: &str) -> Option<DeclarationKind> {
+        model
+            .declarations()
+            .find(|(_, d)| d.name == name)
+            .map(|(_, d)| d.kind)
+    }
+
+    fn resolved(model: &SemanticModel, name: &str) -> Vec<Option<DeclarationKind>> {
+        model
+            .references()
+            .filter(|(_, r)| r.name == name)
+            .map(|(_, r)| r.declaration.map(|d| model.declaration(d).kind))
+            .collect()
+    }
+
+    #[test]
+    fn declarations() {
+        let src = "
+            var a = 1, { b, c: [d = 2, ...e] } = x;
+            let f; const g = 1;
+            function h(i, j = 1, ...k) {}
+            class L {}
+            try {} catch ({ m }) {}
+            let n = o => o;
+        ";
+        let model = SemanticModel::new(&parse_text(src, 0).syntax());
+        for name in &["a", "b", "d", "e"] {
+            assert_eq!(decl_kind(&model, name), Some(DeclarationKind::Var));
+        }
+        assert_eq!(decl_kind(&model, "c"), None);
+        assert_eq!(decl_kind(&model, "f"), Some(DeclarationKind::Let));
+        assert_eq!(decl_kind(&model, "g"), Some(DeclarationKind::Const));
+        assert_eq!(decl_kind(&model, "h"), Some(DeclarationKind::Function));
+        for name in &["i", "j", "k", "o"] {
+            assert_eq!(decl_kind(&model, name), Some(DeclarationKind::Param));
+        }
+        assert_eq!(decl_kind(&model, "L"), Some(DeclarationKind::Class));
+        assert_eq!(decl_kind(&model, "m"), Some(DeclarationKind::CatchParam));
+        assert_eq!(resolved(&model, "x"), vec![None]);
+    }
+
+    #[test]
+    fn hoisting_and_block_scoping() {
+        let src = "
+            a; b();
+            { var a = 1; let c = 2; }
+            function b() { c; }
+        ";
+        let model = SemanticModel::new(&parse_text(src, 0).syntax());
+        assert_eq!(resolved(&model, "a"), vec![Some(DeclarationKind::Var)]);
+        assert_eq!(resolved(&model, "b"), vec![Some(DeclarationKind::Function)]);
+        assert_eq!(resolved(&model, "c"), vec![None]);
+
+        let a = model.lookup(model.root_scope(), "a").unwrap();
+        assert_eq!(model.declaration(a).scope, model.root_scope());
+        assert!(model.lookup(model.root_scope(), "c").is_none());
+    }
+
+    #[test]
+    fn reference_kinds() {
+        let src = "let a, b, c, d; a = 1; b += 1; c++; [d] = [a]; for (d of b) {} label: { break label; }";
+        let model = SemanticModel::new(&parse_text(src, 0).syntax());
+        let kinds = |name| {
+            model
+                .references()
+                .filter(|(_, r)| r.name == name)
+                .map(|(_, r)| r.kind)
+                .collect::<Vec<_>>()
+        };
+        use ReferenceKind::*;
+        assert_eq!(kinds("a"), vec![Write, Read]);
+        assert_eq!(kinds("b"), vec![ReadWrite, Read]);
+        assert_eq!(kinds("c"), vec![ReadWrite]);
+        assert_eq!(kinds("d"), vec![Write, Write]);
+        assert!(kinds("label").is_empty());
+    }
+
+    #[test]
+    fn shadowing_and_reads() {
+        let src = "let a = 1; function f(a) { return a; } let unused = 2;";
+        let model = SemanticModel::new(&parse_text(src, 0).syntax());
+        let (param, _) = model
+            .declarations()
+            .find(|(_, d)| d.kind == DeclarationKind::Param)
+            .unwrap();
+        let outer = model.lookup(model.root_scope(), "a").unwrap();
+        assert_eq!(model.shadowed(param), Some(outer));
+        assert!(model.is_read(param));
+        assert!(!model.is_read(outer));
+
+        let unused = model.lookup(model.root_scope(), "unused").unwrap();
+        assert!(!model.is_read(unused));
+    }
+
+    #[test]
+    fn modules() {
+        let src =
+            "import a, { b, c as d } from 'x'; import * as e from 'y'; export { a as f }; d(e);";
+        let model = SemanticModel::new(&parse_module(src, 0).syntax());
+        assert_eq!(model.scope(model.root_scope()).kind, ScopeKind::Module);
+        for name in &["a", "b", "d", "e"] {
+            assert_eq!(decl_kind(&model, name), Some(DeclarationKind::Import));
+        }
+        assert_eq!(decl_kind(&model, "c"), None);
+        assert_eq!(resolved(&model, "a"), vec![Some(DeclarationKind::Import)]);
+        assert_eq!(resolved(&model, "f"), vec![]);
+    }
+
+    #[test]
+    fn typescript() {
+        let src = "
+            type T<U> = U[];
+            interface I { x: T<number> }
+            namespace N { export const j = 1; }
+            const T = 5;
+            let k: T<string> = T;
+            enum E { A }
+            let l: typeof k = E.A;
+        ";
+        let syntax = Syntax::default().typescript();
+        let root = parse_with_syntax(src, 0, syntax).syntax();
+        let model = SemanticModel::new(&root);
+
+        assert_eq!(decl_kind(&model, "U"), Some(DeclarationKind::TypeParam));
+        assert_eq!(decl_kind(&model, "I"), Some(DeclarationKind::Type));
+        assert_eq!(decl_kind(&model, "N"), Some(DeclarationKind::Namespace));
+        assert_eq!(decl_kind(&model, "E"), Some(DeclarationKind::Enum));
+        assert_eq!(
+            resolved(&model, "T"),
+            vec![
+                Some(DeclarationKind::Type),
+                Some(DeclarationKind::Type),
+                Some(DeclarationKind::Const)
+            ]
+        );
+        assert_eq!(
+            resolved(&model, "U"),
+            vec![Some(DeclarationKind::TypeParam)]
+        );
+        assert_eq!(resolved(&model, "k"), vec![Some(DeclarationKind::Let)]);
+
+        let namespace = model
+            .scopes()
+            .find(|(_, s)| s.node.kind() == SyntaxKind::TS_NAMESPACE_DECL)
+            .unwrap()
+            .0;
+        assert!(model.lookup(namespace, "j").is_some());
+        assert!(model.lookup(model.root_scope(), "j").is_none());
+    }
+}
@@ -1,7 +1,7 @@
 //! A rule store, which houses rule groups as well as individual rules.
 
 use crate::groups::*;
-use crate::CstRule;
+use crate::{plugin_rules, CstRule, ProjectRule};
 
 /// A utility structure for housing CST rules for a linting run.
 #[derive(Debug, Default, Clone)]
@@ -14,15 +14,16 @@ impl CstRuleStore {
         Self::default()
     }
 
-    /// All built in rules from every group.
+    /// All built in rules from every group, along with the rules of every registered plugin.
     pub fn builtins(mut self) -> Self {
         self.rules.extend(errors());
         self.rules.extend(style());
         self.rules.extend(regex());
+        self.rules.extend(plugin_rules());
         self
     }
 
-    /// All recommended rules from every group.
+    /// All recommended rules from every group and registered plugin.
     pub fn recommended(mut self) -> Self {
         self.rules
             .extend(errors().into_iter().filter(|x| x.recommended()));
@@ -30,6 +31,8 @@ impl CstRuleStore {
             .extend(style().into_iter().filter(|x| x.recommended()));
         self.rules
             .extend(regex().into_iter().filter(|x| x.recommended()));
+        self.rules
+            .extend(plugin_rules().into_iter().filter(|x| x.recommended()));
         self
     }
 
@@ -53,3 +56,53 @@ impl CstRuleStore {
             .cloned()
     }
 }
+
+/// A utility structure for housing project rules for a linting run.
+#[derive(Debug, Default, Clone)]
+pub struct ProjectRuleStore {
+    pub rules: Vec<Box<dyn ProjectRule>>,
+}
+
+impl ProjectRuleStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All built in project rules.
+    pub fn builtins(mut self) -> Self {
+        self.rules.extend(project());
+        self
+    }
+
+    /// All recommended project rules.
+    pub fn recommended(mut self) -> Self {
+        self.rules
+            .extend(project().into_iter().filter(|x| x.recommended()));
+        self
+    }
+
+    /// Whether any rule of this store needs the syntax trees of files.
+    pub fn needs_trees(&self) -> bool {
+        self.rules.iter().any(|rule| rule.needs_trees())
+    }
+
+    /// Load a list of rules into this store.
+    pub fn load_rules(&mut self, rules: impl IntoIterator<Item = Box<dyn ProjectRule>>) {
+        self.rules.extend(rules);
+    }
+
+    /// Get a rule using its rule name from this store.
+    ///
+    /// # Examples
+    /// ```
+    /// use rslint_core::ProjectRuleStore;
+    ///
+    /// assert!(ProjectRuleStore::new().builtins().get("no-duplicate-module-names").is_some())
+    /// ```
+    pub fn get(&self, rule_name: impl AsRef<str>) -> Option<Box<dyn ProjectRule>> {
+        self.rules
+            .iter()
+            .find(|rule| rule.name() == rule_name.as_ref())
+            .cloned()
+    }
+}
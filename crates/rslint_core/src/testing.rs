@@ -72,3 +72,69 @@ macro_rules! rule_tests {
         }
     };
 }
+
+/// A project made of files written to a temporary directory, for testing project rules which read the disk.
+///
+/// Files with a JavaScript or TypeScript extension are parsed and given ids in order starting at `0`.
+/// The directory is removed when the project is dropped.
+#[cfg(test)]
+pub(crate) struct TempProject {
+    pub dir: std::path::PathBuf,
+    pub files: Vec<crate::File>,
+}
+
+#[cfg(test)]
+impl TempProject {
+    pub fn new(files: &[(&str, &str)]) -> Self {
+        use rslint_parser::FileKind;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "rslint-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+
+        let mut parsed = vec![];
+        for (path, source) in files {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, source).unwrap();
+
+            let kind = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("ts") | Some("tsx") => FileKind::TypeScript,
+                Some("js") | Some("jsx") | Some("mjs") | Some("cjs") => FileKind::Module,
+                _ => continue,
+            };
+            let mut file = crate::File::from_string(source, kind, path.display());
+            file.path = Some(path);
+            file.id = parsed.len();
+            parsed.push(file);
+        }
+
+        Self { dir, files: parsed }
+    }
+
+    pub fn project(&self) -> crate::Project {
+        crate::Project::new(self.files.iter().map(|file| crate::ProjectFile {
+            file,
+            root: Some(file.parse()),
+        }))
+    }
+
+    pub fn module_graph(&self) -> std::sync::Arc<crate::modules::ModuleGraph> {
+        self.project().module_graph()
+    }
+
+    pub fn lint(&self, rule: &dyn crate::ProjectRule) -> Vec<crate::Diagnostic> {
+        crate::run_project_rule(rule, &self.project(), false)
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempProject {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
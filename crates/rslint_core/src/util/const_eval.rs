@@ -0,0 +1,847 @@
+//! Evaluation of constant expressions following the semantics of ECMAScript.
+
+use crate::rule_prelude::*;
+use ast::{AssignOp, BinOp, Expr, ExprOrSpread, LiteralKind, UnaryOp};
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// The value of a constant expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsValue {
+    Undefined,
+    Null,
+    Bool(bool),
+    /// A number or a bigint.
+    Number(JsNum),
+    String(String),
+    /// An array, with its elements if all of them are known.
+    Array(Option<Vec<JsValue>>),
+    /// An object which is not an array.
+    Object(ObjectKind),
+}
+
+/// The kind of an object which is not an array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    /// An object literal.
+    Plain,
+    /// A function, arrow function or class.
+    Function,
+    /// A regex literal.
+    Regex,
+}
+
+impl JsValue {
+    /// Make a number value from a float.
+    pub fn number(num: f64) -> Self {
+        JsValue::Number(JsNum::Float(num))
+    }
+
+    pub fn is_nan(&self) -> bool {
+        matches!(self, JsValue::Number(JsNum::Float(num)) if num.is_nan())
+    }
+
+    /// The result of `typeof` on the value.
+    pub fn type_of(&self) -> &'static str {
+        match self {
+            JsValue::Undefined => "undefined",
+            JsValue::Null | JsValue::Array(_) => "object",
+            JsValue::Bool(_) => "boolean",
+            JsValue::Number(JsNum::Float(_)) => "number",
+            JsValue::Number(JsNum::BigInt(_)) => "bigint",
+            JsValue::String(_) => "string",
+            JsValue::Object(ObjectKind::Function) => "function",
+            JsValue::Object(_) => "object",
+        }
+    }
+
+    /// Whether the value is truthy.
+    pub fn to_boolean(&self) -> bool {
+        match self {
+            JsValue::Undefined | JsValue::Null => false,
+            JsValue::Bool(val) => *val,
+            JsValue::Number(JsNum::Float(num)) => *num != 0.0 && !num.is_nan(),
+            JsValue::Number(JsNum::BigInt(num)) => *num != BigInt::from(0),
+            JsValue::String(string) => !string.is_empty(),
+            JsValue::Array(_) | JsValue::Object(_) => true,
+        }
+    }
+
+    /// Convert the value to a primitive like the language would in an operator, `None` if that is
+    /// unknown or calls user code.
+    pub fn to_primitive(&self) -> Option<JsValue> {
+        match self {
+            JsValue::Array(_) | JsValue::Object(ObjectKind::Plain) => {
+                Some(JsValue::String(self.to_js_string()?))
+            }
+            JsValue::Object(_) => None,
+            _ => Some(self.clone()),
+        }
+    }
+
+    /// Convert the value to a number, `None` if the value is a bigint, which cannot be converted
+    /// implicitly, or if the conversion is unknown.
+    pub fn to_number(&self) -> Option<f64> {
+        match self.to_numeric()? {
+            JsNum::Float(num) => Some(num),
+            JsNum::BigInt(_) => None,
+        }
+    }
+
+    /// Convert the value to a number or a bigint, `None` if the conversion is unknown.
+    pub fn to_numeric(&self) -> Option<JsNum> {
+        let num = match self.to_primitive()? {
+            JsValue::Undefined => f64::NAN,
+            JsValue::Null => 0.0,
+            JsValue::Bool(val) => val as u8 as f64,
+            JsValue::Number(num) => return Some(num),
+            JsValue::String(string) => string_to_number(&string),
+            JsValue::Array(_) | JsValue::Object(_) => return None,
+        };
+        Some(JsNum::Float(num))
+    }
+
+    /// Convert the value to a string, `None` if the conversion is unknown, such as for functions.
+    pub fn to_js_string(&self) -> Option<String> {
+        Some(match self {
+            JsValue::Undefined => "undefined".to_string(),
+            JsValue::Null => "null".to_string(),
+            JsValue::Bool(val) => val.to_string(),
+            JsValue::Number(JsNum::Float(num)) => number_to_string(*num),
+            JsValue::Number(JsNum::BigInt(num)) => num.to_string(),
+            JsValue::String(string) => string.clone(),
+            JsValue::Array(elements) => {
+                let elements = elements
+                    .as_ref()?
+                    .iter()
+                    .map(|elem| match elem {
+                        JsValue::Undefined | JsValue::Null => Some(String::new()),
+                        elem => elem.to_js_string(),
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                elements.join(",")
+            }
+            JsValue::Object(ObjectKind::Plain) => "[object Object]".to_string(),
+            JsValue::Object(_) => return None,
+        })
+    }
+
+    /// The result of `===` between two values.
+    ///
+    /// Every array and object value is assumed to be a different object, since they are made by literals.
+    pub fn strict_equals(&self, other: &JsValue) -> bool {
+        match (self, other) {
+            (JsValue::Undefined, JsValue::Undefined) | (JsValue::Null, JsValue::Null) => true,
+            (JsValue::Bool(a), JsValue::Bool(b)) => a == b,
+            (JsValue::Number(JsNum::Float(a)), JsValue::Number(JsNum::Float(b))) => a == b,
+            (JsValue::Number(JsNum::BigInt(a)), JsValue::Number(JsNum::BigInt(b))) => a == b,
+            (JsValue::String(a), JsValue::String(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// The result of `==` between two values, `None` if it is unknown.
+    ///
+    /// Every array and object value is assumed to be a different object, since they are made by literals.
+    pub fn loose_equals(&self, other: &JsValue) -> Option<bool> {
+        use JsValue::*;
+
+        Some(match (self, other) {
+            (Undefined, Undefined) | (Null, Null) | (Undefined, Null) | (Null, Undefined) => true,
+            (Undefined, _) | (Null, _) | (_, Undefined) | (_, Null) => false,
+            (Array(_), Array(_))
+            | (Object(_), Object(_))
+            | (Array(_), Object(_))
+            | (Object(_), Array(_)) => false,
+            (Array(_), _) | (Object(_), _) => return self.to_primitive()?.loose_equals(other),
+            (_, Array(_)) | (_, Object(_)) => return self.loose_equals(&other.to_primitive()?),
+            (Bool(val), _) => return JsValue::number(*val as u8 as f64).loose_equals(other),
+            (_, Bool(val)) => return self.loose_equals(&JsValue::number(*val as u8 as f64)),
+            (Number(JsNum::Float(num)), String(string))
+            | (String(string), Number(JsNum::Float(num))) => *num == string_to_number(string),
+            (Number(JsNum::BigInt(big)), String(string))
+            | (String(string), Number(JsNum::BigInt(big))) => {
+                string_to_bigint(string).map_or(false, |num| num == *big)
+            }
+            (Number(JsNum::BigInt(big)), Number(JsNum::Float(num)))
+            | (Number(JsNum::Float(num)), Number(JsNum::BigInt(big))) => {
+                compare_bigint(big, *num) == Some(Ordering::Equal)
+            }
+            _ => self.strict_equals(other),
+        })
+    }
+
+    /// Compare two values like the relational operators, `None` if it is unknown and `Some(None)` if
+    /// the values cannot be ordered, such as when one of them is `NaN`.
+    pub fn compare(&self, other: &JsValue) -> Option<Option<Ordering>> {
+        let (lhs, rhs) = (self.to_primitive()?, other.to_primitive()?);
+        Some(match (lhs, rhs) {
+            (JsValue::String(a), JsValue::String(b)) => {
+                Some(a.encode_utf16().cmp(b.encode_utf16()))
+            }
+            (JsValue::Number(JsNum::BigInt(big)), JsValue::String(string)) => {
+                string_to_bigint(&string).map(|num| big.cmp(&num))
+            }
+            (JsValue::String(string), JsValue::Number(JsNum::BigInt(big))) => {
+                string_to_bigint(&string).map(|num| num.cmp(&big))
+            }
+            (lhs, rhs) => match (lhs.to_numeric()?, rhs.to_numeric()?) {
+                (JsNum::Float(a), JsNum::Float(b)) => a.partial_cmp(&b),
+                (JsNum::BigInt(a), JsNum::BigInt(b)) => Some(a.cmp(&b)),
+                (JsNum::BigInt(big), JsNum::Float(num)) => compare_bigint(&big, num),
+                (JsNum::Float(num), JsNum::BigInt(big)) => {
+                    compare_bigint(&big, num).map(Ordering::reverse)
+                }
+            },
+        })
+    }
+}
+
+/// Evaluate an expression whose value does not depend on the program, `None` if the value is unknown.
+///
+/// This folds literals, templates without substitutions, arrays, and unary, binary, logical and
+/// conditional operators. `undefined`, `NaN` and `Infinity` are assumed to be the globals, expressions which
+/// throw an error such as `1n + 1` are unknown.
+pub fn eval_const_expr(expr: Expr) -> Option<JsValue> {
+    match expr {
+        Expr::Literal(lit) => Some(match lit.kind() {
+            LiteralKind::Null => JsValue::Null,
+            LiteralKind::Bool(val) => JsValue::Bool(val),
+            LiteralKind::Number(num) => JsValue::number(num),
+            LiteralKind::BigInt(num) => JsValue::Number(JsNum::BigInt(num)),
+            LiteralKind::String => {
+                JsValue::String(unescape(&lit.inner_string_text()?.to_string())?)
+            }
+            LiteralKind::Regex => JsValue::Object(ObjectKind::Regex),
+        }),
+        Expr::Template(tpl) => {
+            if tpl.elements().next().is_some() || tpl.tag().is_some() {
+                return None;
+            }
+            let raw = tpl
+                .quasis()
+                .map(|chunk| chunk.text().to_string())
+                .collect::<String>();
+            Some(JsValue::String(unescape(
+                &raw.replace("\r\n", "\n").replace('\r', "\n"),
+            )?))
+        }
+        Expr::NameRef(name) => match name.ident_token()?.text().as_str() {
+            "undefined" => Some(JsValue::Undefined),
+            "NaN" => Some(JsValue::number(f64::NAN)),
+            "Infinity" => Some(JsValue::number(f64::INFINITY)),
+            _ => None,
+        },
+        Expr::ArrayExpr(array) => {
+            if !array.sparse_elements().is_empty() {
+                return Some(JsValue::Array(None));
+            }
+            let elements = array
+                .elements()
+                .map(|elem| match elem {
+                    ExprOrSpread::Expr(expr) => eval_const_expr(expr),
+                    ExprOrSpread::Spread(_) => None,
+                })
+                .collect();
+            Some(JsValue::Array(elements))
+        }
+        Expr::ObjectExpr(_) => Some(JsValue::Object(ObjectKind::Plain)),
+        Expr::FnExpr(_) | Expr::ArrowExpr(_) | Expr::ClassExpr(_) => {
+            Some(JsValue::Object(ObjectKind::Function))
+        }
+        Expr::GroupingExpr(group) => eval_const_expr(group.inner()?),
+        Expr::SequenceExpr(seq) => eval_const_expr(seq.exprs().last()?),
+        Expr::AssignExpr(assign) if assign.op()? == AssignOp::Assign => {
+            eval_const_expr(assign.rhs()?)
+        }
+        Expr::CondExpr(cond) => {
+            if eval_const_expr(cond.test()?)?.to_boolean() {
+                eval_const_expr(cond.cons()?)
+            } else {
+                eval_const_expr(cond.alt()?)
+            }
+        }
+        Expr::UnaryExpr(unary) => {
+            let op = unary.op()?;
+            // the operand of void is evaluated for its side effects only
+            if op == UnaryOp::Void {
+                return Some(JsValue::Undefined);
+            }
+            eval_unary(op, eval_const_expr(unary.expr()?)?)
+        }
+        Expr::BinExpr(bin) => {
+            let op = bin.op()?;
+            let lhs = eval_const_expr(bin.lhs()?)?;
+            let short_circuits = match op {
+                BinOp::LogicalAnd => !lhs.to_boolean(),
+                BinOp::LogicalOr => lhs.to_boolean(),
+                BinOp::NullishCoalescing => !matches!(lhs, JsValue::Undefined | JsValue::Null),
+                _ => false,
+            };
+            if short_circuits {
+                return Some(lhs);
+            }
+            let rhs = eval_const_expr(bin.rhs()?)?;
+            if matches!(
+                op,
+                BinOp::LogicalAnd | BinOp::LogicalOr | BinOp::NullishCoalescing
+            ) {
+                return Some(rhs);
+            }
+            eval_binary(op, lhs, rhs)
+        }
+        _ => None,
+    }
+}
+
+fn eval_unary(op: UnaryOp, value: JsValue) -> Option<JsValue> {
+    Some(match op {
+        UnaryOp::Typeof => JsValue::String(value.type_of().to_string()),
+        UnaryOp::LogicalNot => JsValue::Bool(!value.to_boolean()),
+        UnaryOp::Plus => JsValue::number(value.to_number()?),
+        UnaryOp::Minus => JsValue::Number(match value.to_numeric()? {
+            JsNum::Float(num) => JsNum::Float(-num),
+            JsNum::BigInt(num) => JsNum::BigInt(-num),
+        }),
+        UnaryOp::BitwiseNot => JsValue::Number(match value.to_numeric()? {
+            JsNum::Float(num) => JsNum::Float(!to_int32(num) as f64),
+            JsNum::BigInt(num) => JsNum::BigInt(-num - 1),
+        }),
+        _ => return None,
+    })
+}
+
+fn eval_binary(op: BinOp, lhs: JsValue, rhs: JsValue) -> Option<JsValue> {
+    let value = match op {
+        BinOp::StrictEquality => JsValue::Bool(lhs.strict_equals(&rhs)),
+        BinOp::StrictInequality => JsValue::Bool(!lhs.strict_equals(&rhs)),
+        BinOp::Equality => JsValue::Bool(lhs.loose_equals(&rhs)?),
+        BinOp::Inequality => JsValue::Bool(!lhs.loose_equals(&rhs)?),
+        BinOp::LessThan => JsValue::Bool(lhs.compare(&rhs)? == Some(Ordering::Less)),
+        BinOp::GreaterThan => JsValue::Bool(lhs.compare(&rhs)? == Some(Ordering::Greater)),
+        BinOp::LessThanOrEqual => JsValue::Bool(
+            lhs.compare(&rhs)?
+                .map_or(false, |ord| ord != Ordering::Greater),
+        ),
+        BinOp::GreaterThanOrEqual => JsValue::Bool(
+            lhs.compare(&rhs)?
+                .map_or(false, |ord| ord != Ordering::Less),
+        ),
+        BinOp::Plus => {
+            let (lhs, rhs) = (lhs.to_primitive()?, rhs.to_primitive()?);
+            if matches!(lhs, JsValue::String(_)) || matches!(rhs, JsValue::String(_)) {
+                JsValue::String(lhs.to_js_string()? + &rhs.to_js_string()?)
+            } else {
+                eval_numeric(op, lhs.to_numeric()?, rhs.to_numeric()?)?
+            }
+        }
+        _ => eval_numeric(op, lhs.to_numeric()?, rhs.to_numeric()?)?,
+    };
+    Some(value)
+}
+
+// the largest result of a bigint operation which is computed, in bits
+const MAX_BIGINT_BITS: u64 = 1 << 16;
+
+fn eval_numeric(op: BinOp, lhs: JsNum, rhs: JsNum) -> Option<JsValue> {
+    let num = match (lhs, rhs) {
+        (JsNum::Float(a), JsNum::Float(b)) => JsNum::Float(match op {
+            BinOp::Plus => a + b,
+            BinOp::Minus => a - b,
+            BinOp::Times => a * b,
+            BinOp::Divide => a / b,
+            BinOp::Remainder => a % b,
+            BinOp::Exponent => {
+                if b.is_nan() || (a.abs() == 1.0 && b.is_infinite()) {
+                    f64::NAN
+                } else {
+                    a.powf(b)
+                }
+            }
+            BinOp::LeftShift => to_int32(a).wrapping_shl(to_uint32(b)) as f64,
+            BinOp::RightShift => to_int32(a).wrapping_shr(to_uint32(b)) as f64,
+            BinOp::UnsignedRightShift => to_uint32(a).wrapping_shr(to_uint32(b)) as f64,
+            BinOp::BitwiseAnd => (to_int32(a) & to_int32(b)) as f64,
+            BinOp::BitwiseOr => (to_int32(a) | to_int32(b)) as f64,
+            BinOp::BitwiseXor => (to_int32(a) ^ to_int32(b)) as f64,
+            _ => return None,
+        }),
+        (JsNum::BigInt(a), JsNum::BigInt(b)) => JsNum::BigInt(match op {
+            BinOp::Plus => a + b,
+            BinOp::Minus => a - b,
+            BinOp::Times => a * b,
+            BinOp::Divide if b != BigInt::from(0) => a / b,
+            BinOp::Remainder if b != BigInt::from(0) => a % b,
+            BinOp::Exponent => {
+                let exp = b.to_string().parse::<u32>().ok()?;
+                if a.bits() * exp as u64 > MAX_BIGINT_BITS {
+                    return None;
+                }
+                a.pow(exp)
+            }
+            BinOp::LeftShift | BinOp::RightShift => {
+                let mut shift = b.to_string().parse::<i64>().ok()?;
+                if op == BinOp::RightShift {
+                    shift = -shift;
+                }
+                if shift >= 0 {
+                    if a.bits() + shift as u64 > MAX_BIGINT_BITS {
+                        return None;
+                    }
+                    a << shift as usize
+                } else if shift.unsigned_abs() >= a.bits() {
+                    // shifting rounds towards negative infinity
+                    BigInt::from(if a < BigInt::from(0) { -1 } else { 0 })
+                } else {
+                    a >> shift.unsigned_abs() as usize
+                }
+            }
+            BinOp::BitwiseAnd => a & b,
+            BinOp::BitwiseOr => a | b,
+            BinOp::BitwiseXor => a ^ b,
+            _ => return None,
+        }),
+        // mixing numbers and bigints throws a type error
+        _ => return None,
+    };
+    Some(JsValue::Number(num))
+}
+
+fn to_uint32(num: f64) -> u32 {
+    if !num.is_finite() {
+        return 0;
+    }
+    (num.trunc() % 4294967296.0) as i64 as u32
+}
+
+fn to_int32(num: f64) -> i32 {
+    to_uint32(num) as i32
+}
+
+fn is_js_whitespace(c: char) -> bool {
+    (c.is_whitespace() && c != '\u{85}') || c == '\u{feff}'
+}
+
+fn split_radix(string: &str) -> (u32, &str) {
+    match string.get(..2) {
+        Some("0x") | Some("0X") => (16, &string[2..]),
+        Some("0o") | Some("0O") => (8, &string[2..]),
+        Some("0b") | Some("0B") => (2, &string[2..]),
+        _ => (10, string),
+    }
+}
+
+/// Convert a string to a number like `Number(string)`.
+fn string_to_number(string: &str) -> f64 {
+    let string = string.trim_matches(is_js_whitespace);
+    if string.is_empty() {
+        return 0.0;
+    }
+    let (radix, digits) = split_radix(string);
+    if radix != 10 {
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return f64::NAN;
+        }
+        return match u128::from_str_radix(digits, radix) {
+            Ok(num) => num as f64,
+            Err(_) => digits.chars().fold(0.0, |acc, c| {
+                acc * radix as f64 + c.to_digit(radix).unwrap() as f64
+            }),
+        };
+    }
+
+    let unsigned = string.trim_start_matches(|c| c == '+' || c == '-');
+    if string.len() - unsigned.len() > 1 {
+        return f64::NAN;
+    }
+    if unsigned == "Infinity" {
+        return if string.starts_with('-') {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        };
+    }
+    let valid = unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && unsigned
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'));
+    if !valid {
+        return f64::NAN;
+    }
+    string.parse().unwrap_or(f64::NAN)
+}
+
+/// Convert a string to a bigint like `BigInt(string)`, `None` if the string is not an integer.
+fn string_to_bigint(string: &str) -> Option<BigInt> {
+    let string = string.trim_matches(is_js_whitespace);
+    if string.is_empty() {
+        return Some(BigInt::from(0));
+    }
+    let (radix, digits) = split_radix(string);
+    let unsigned = if radix == 10 {
+        digits
+            .strip_prefix(|c| c == '+' || c == '-')
+            .unwrap_or(digits)
+    } else {
+        digits
+    };
+    if unsigned.is_empty() || !unsigned.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    BigInt::parse_bytes(digits.as_bytes(), radix)
+}
+
+/// Compare a bigint to a number, `None` if the number is `NaN`.
+fn compare_bigint(big: &BigInt, num: f64) -> Option<Ordering> {
+    if num.is_nan() {
+        return None;
+    }
+    if num.is_infinite() {
+        return Some(if num > 0.0 {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        });
+    }
+    let floor = format!("{:.0}", num.floor()).parse::<BigInt>().ok()?;
+    Some(match big.cmp(&floor) {
+        Ordering::Equal if num.fract() != 0.0 => Ordering::Less,
+        ord => ord,
+    })
+}
+
+/// Convert a number to a string like `String(num)`.
+pub fn number_to_string(num: f64) -> String {
+    if num.is_nan() {
+        return "NaN".to_string();
+    }
+    if num == 0.0 {
+        return "0".to_string();
+    }
+    if num.is_infinite() {
+        return if num > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    if num < 0.0 {
+        return format!("-{}", number_to_string(-num));
+    }
+
+    // the shortest digits which identify the number and the position of the decimal point after the first one
+    let sci = format!("{:e}", num);
+    let (mantissa, exp) = sci.split_at(sci.find('e').unwrap());
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    let n = exp[1..].parse::<i32>().unwrap() + 1;
+
+    if k <= n && n <= 21 {
+        digits + &"0".repeat((n - k) as usize)
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let fraction = if k == 1 {
+            String::new()
+        } else {
+            format!(".{}", &digits[1..])
+        };
+        format!("{}{}e{}{}", &digits[..1], fraction, sign, (n - 1).abs())
+    }
+}
+
+/// Resolve the escapes of the text of a string literal or template, `None` if the text has escapes which
+/// cannot be resolved, such as lone surrogates or octal escapes.
+fn unescape(raw: &str) -> Option<String> {
+    let mut res = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => res.push('\n'),
+            't' => res.push('\t'),
+            'r' => res.push('\r'),
+            'b' => res.push('\u{8}'),
+            'f' => res.push('\u{c}'),
+            'v' => res.push('\u{b}'),
+            '0' if !chars.peek().map_or(false, |c| c.is_ascii_digit()) => res.push('\0'),
+            '0'..='7' => return None,
+            'x' => {
+                let hex = [chars.next()?, chars.next()?].iter().collect::<String>();
+                res.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            'u' => {
+                let mut code = unicode_escape(&mut chars)?;
+                if (0xD800..0xDC00).contains(&code) {
+                    // a surrogate pair written as two escapes
+                    if chars.next() != Some('\\') || chars.next() != Some('u') {
+                        return None;
+                    }
+                    let low = unicode_escape(&mut chars)?;
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return None;
+                    }
+                    code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                }
+                res.push(char::from_u32(code)?);
+            }
+            // line continuations
+            '\r' => {
+                if chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            c => res.push(c),
+        }
+    }
+    Some(res)
+}
+
+fn unicode_escape(chars: &mut Peekable<Chars>) -> Option<u32> {
+    let hex = if chars.peek() == Some(&'{') {
+        chars.next();
+        chars.take_while(|c| *c != '}').collect::<String>()
+    } else {
+        let hex = chars.take(4).collect::<String>();
+        Some(hex).filter(|hex| hex.len() == 4)?
+    };
+    u32::from_str_radix(&hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_parser::parse_expr;
+
+    fn eval(src: &str) -> Option<JsValue> {
+        eval_const_expr(parse_expr(src, 0).tree())
+    }
+
+    fn eval_str(src: &str) -> String {
+        eval(src).unwrap().to_js_string().unwrap()
+    }
+
+    #[test]
+    fn folds_operators() {
+        assert_eq!(eval_str("1 + 2 * 3"), "7");
+        assert_eq!(eval_str("'a' + 1 + 2"), "a12");
+        assert_eq!(eval_str("1 + 2 + 'a'"), "3a");
+        assert_eq!(eval_str("[1, [2, null]] + {}"), "1,2,[object Object]");
+        assert_eq!(eval_str("`a\\x41\\u{42}`"), "aAB");
+        assert_eq!(eval_str("-(2 ** 31) | 0"), "-2147483648");
+        assert_eq!(eval_str("-1 >>> 0"), "4294967295");
+        assert_eq!(eval_str("2n ** 64n"), "18446744073709551616");
+        assert_eq!(eval_str("-7n >> 1n"), "-4");
+        assert_eq!(eval_str("0.1 + 0.2"), "0.30000000000000004");
+        assert_eq!(eval_str("1e21"), "1e+21");
+        assert_eq!(eval_str("1 / 3e7"), "3.3333333333333334e-8");
+        assert_eq!(eval_str("typeof null"), "object");
+        assert_eq!(eval_str("typeof (() => {})"), "function");
+        assert!(eval("0 / 0").unwrap().is_nan());
+        assert!(eval("+'0x'").unwrap().is_nan());
+    }
+
+    #[test]
+    fn compares_values() {
+        let truthy = |src: &str| eval(src).unwrap().to_boolean();
+        assert!(truthy("null == undefined"));
+        assert!(!truthy("null == 0"));
+        assert!(truthy("'1' == 1n"));
+        assert!(truthy("[1] == 1"));
+        assert!(truthy("true == '1'"));
+        assert!(!truthy("NaN === NaN"));
+        assert!(truthy("0 === -0"));
+        assert!(!truthy("[] === []"));
+        assert!(truthy("'b' > 'a'"));
+        assert!(!truthy("NaN <= 1"));
+        assert!(truthy("1n < 1.5"));
+        assert!(truthy("'10' > 9"));
+    }
+
+    #[test]
+    fn short_circuits_unknown_values() {
+        assert_eq!(eval("false && foo"), Some(JsValue::Bool(false)));
+        assert_eq!(eval("null ?? 'a'"), Some(JsValue::String("a".to_string())));
+        assert_eq!(eval("void foo()"), Some(JsValue::Undefined));
+        assert_eq!(eval("true ? 1 : foo"), Some(JsValue::number(1.0)));
+        assert_eq!(eval("true && foo"), None);
+        assert_eq!(eval("1n + 1"), None);
+        assert_eq!(eval("`${a}`"), None);
+        assert_eq!(eval("typeof foo"), None);
+    }
+
+    #[test]
+    fn plus_coerces_numbers_and_strings() {
+        assert_eq!(eval("1 + '2'"), Some(JsValue::String("12".to_string())));
+        assert_eq!(eval("'1' - 1"), Some(JsValue::number(0.0)));
+        assert_eq!(eval("true + 1"), Some(JsValue::number(2.0)));
+        assert_eq!(eval("null + 1"), Some(JsValue::number(1.0)));
+        assert!(eval("undefined + 1").unwrap().is_nan());
+        assert_eq!(eval_str("'a' + null + undefined"), "anullundefined");
+        assert_eq!(eval_str("[] + []"), "");
+        assert_eq!(eval_str("[1, 2] + 3"), "1,23");
+        assert_eq!(eval_str("1n + '1'"), "11");
+        assert_eq!(eval_str("'' + -0"), "0");
+        assert_eq!(eval_str("'' + 1e-7"), "1e-7");
+        assert_eq!(eval("+' 12 '"), Some(JsValue::number(12.0)));
+        assert_eq!(eval("+''"), Some(JsValue::number(0.0)));
+        assert_eq!(eval("+'0b101'"), Some(JsValue::number(5.0)));
+        assert!(eval("+'1_000'").unwrap().is_nan());
+        assert_eq!(eval("'' + (() => {})"), None);
+    }
+
+    #[test]
+    fn loose_and_strict_equality() {
+        let cases = [
+            ("1 == '1'", true, false),
+            ("0 == ''", true, false),
+            ("0 == '0'", true, false),
+            ("'' == '0'", false, false),
+            ("false == '0'", true, false),
+            ("false == undefined", false, false),
+            ("null == false", false, false),
+            ("undefined == null", true, false),
+            ("1n == 1", true, false),
+            ("1n == '1'", true, false),
+            ("1n == '1.5'", false, false),
+            ("[] == ''", true, false),
+            ("[1, 2] == '1,2'", true, false),
+            ("({}) == '[object Object]'", true, false),
+            ("({}) == ({})", false, false),
+            ("'a' == 'a'", true, true),
+            ("1n == 1n", true, true),
+            ("true == true", true, true),
+        ];
+        for (src, loose, strict) in cases.iter() {
+            let truthy = |src: &str| eval(src).unwrap().to_boolean();
+            assert_eq!(truthy(src), *loose, "{}", src);
+            assert_eq!(truthy(&src.replace("==", "!=")), !*loose, "{}", src);
+            assert_eq!(truthy(&src.replace("==", "===")), *strict, "{}", src);
+            assert_eq!(truthy(&src.replace("==", "!==")), !*strict, "{}", src);
+        }
+        assert_eq!(eval("(() => {}) == 'a'"), None);
+    }
+
+    #[test]
+    fn nan_and_negative_zero() {
+        let number = |src: &str| match eval(src) {
+            Some(JsValue::Number(JsNum::Float(num))) => num,
+            value => panic!("{} is not a number: {:?}", src, value),
+        };
+        assert!(number("-0").is_sign_negative());
+        assert!(number("0 * -1").is_sign_negative());
+        assert!(number("-0 - 0").is_sign_negative());
+        assert!(number("-0 + 0").is_sign_positive());
+        assert_eq!(number("1 / -0"), f64::NEG_INFINITY);
+        assert!(number("NaN + 1").is_nan());
+        assert!(number("Infinity - Infinity").is_nan());
+        assert!(number("1 ** Infinity").is_nan());
+        assert_eq!(number("NaN ** 0"), 1.0);
+        assert_eq!(number("NaN | 0"), 0.0);
+
+        let truthy = |src: &str| eval(src).unwrap().to_boolean();
+        assert!(truthy("-0 === 0"));
+        assert!(truthy("-0 == '0'"));
+        assert!(!truthy("NaN == NaN"));
+        assert!(truthy("NaN != NaN"));
+        assert!(!truthy("NaN < NaN || NaN >= NaN"));
+        assert!(!truthy("-0"));
+        assert!(!truthy("NaN"));
+        assert_eq!(eval_str("NaN"), "NaN");
+        assert_eq!(eval_str("-0"), "0");
+        assert_eq!(eval_str("[-0]"), "0");
+    }
+
+    #[test]
+    fn bigints_mixed_with_numbers_are_unknown() {
+        for src in &[
+            "1n + 1",
+            "1 - 1n",
+            "2n * 1.5",
+            "1n / true",
+            "1n % '1'",
+            "1n ** 1",
+            "1n | 0",
+            "1n << 1",
+            "+1n",
+            "1n >>> 0n",
+            "1n / 0n",
+            "2n ** -1n",
+        ] {
+            assert_eq!(eval(src), None, "{}", src);
+        }
+        assert_eq!(eval_str("-1n"), "-1");
+        assert_eq!(eval_str("~1n"), "-2");
+        assert_eq!(eval_str("7n / 2n"), "3");
+        assert_eq!(eval_str("-7n % 2n"), "-1");
+        assert!(eval("1n < 2").unwrap().to_boolean());
+        assert!(!eval("1n === 1").unwrap().to_boolean());
+        assert!(!eval("0n").unwrap().to_boolean());
+    }
+
+    #[test]
+    fn type_of() {
+        let cases = [
+            ("1", "number"),
+            ("NaN", "number"),
+            ("1n", "bigint"),
+            ("''", "string"),
+            ("`a`", "string"),
+            ("!0", "boolean"),
+            ("undefined", "undefined"),
+            ("void 0", "undefined"),
+            ("null", "object"),
+            ("[]", "object"),
+            ("({})", "object"),
+            ("/a/", "object"),
+            ("function() {}", "function"),
+            ("class {}", "function"),
+            ("typeof 1", "string"),
+        ];
+        for (src, expected) in cases.iter() {
+            assert_eq!(eval_str(&format!("typeof ({})", src)), *expected, "{}", src);
+        }
+        assert_eq!(eval("typeof foo"), None);
+        assert_eq!(eval("typeof foo()"), None);
+    }
+
+    #[test]
+    fn logical_operators_with_unknown_operands() {
+        assert_eq!(eval("0 && foo"), Some(JsValue::number(0.0)));
+        assert_eq!(eval("'' && foo"), Some(JsValue::String(String::new())));
+        assert_eq!(eval("1 || foo"), Some(JsValue::number(1.0)));
+        assert_eq!(eval("[] || foo"), Some(JsValue::Array(Some(vec![]))));
+        assert_eq!(eval("0 ?? foo"), Some(JsValue::number(0.0)));
+        assert_eq!(eval("false ?? foo"), Some(JsValue::Bool(false)));
+        assert_eq!(eval("1 && 'a'"), Some(JsValue::String("a".to_string())));
+        assert_eq!(eval("undefined ?? null"), Some(JsValue::Null));
+
+        for src in &[
+            "1 && foo",
+            "0 || foo",
+            "null ?? foo",
+            "undefined ?? foo",
+            "foo && false",
+            "foo || true",
+            "foo ?? 1",
+            "(0, foo) || 1",
+            "(1 && foo) || 1",
+        ] {
+            assert_eq!(eval(src), None, "{}", src);
+        }
+    }
+
+    #[test]
+    fn templates_with_unknown_parts() {
+        assert_eq!(eval_str("`a`"), "a");
+        assert_eq!(eval_str("`a\\`b`"), "a`b");
+        assert_eq!(eval_str("`a\\\nb`"), "ab");
+        for src in &["`a${b}c`", "`${foo()}`", "`${1}`", "tag`a`", "`a` + b"] {
+            assert_eq!(eval(src), None, "{}", src);
+        }
+    }
+}
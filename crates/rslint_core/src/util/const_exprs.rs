@@ -1,11 +1,16 @@
+use super::eval_const_expr;
 use crate::rule_prelude::*;
 use ast::*;
 use SyntaxKind::*;
 
 /// Attempt to check if a simple expression is always truthy or always falsey.
 ///
-/// For example, `true`, `false`, and `foo = true`, this does not consider math ops like `0 + 0`.
+/// For example, `true`, `false`, `foo = true` and constant expressions such as `0 + 0` which [`eval_const_expr`]
+/// can evaluate.
 pub fn simple_bool_coerce(condition: Expr) -> Option<bool> {
+    if let Some(value) = eval_const_expr(condition.clone()) {
+        return Some(value.to_boolean());
+    }
     match condition {
         Expr::Literal(lit) => {
             let coerced = match lit.kind() {
@@ -1,8 +1,10 @@
 //! General utilities to make linting easier.
 
+mod const_eval;
 mod const_exprs;
 mod style;
 
+pub use const_eval::*;
 pub use const_exprs::*;
 pub use style::*;
 
@@ -0,0 +1,128 @@
+//! Snapshot tests of rules run on the fixtures in `tests/rules/<rule>/`.
+//!
+//! Every `.js` (script), `.mjs` (module) and `.ts` (TypeScript) file in the directory of a rule is linted with only
+//! that rule. For a fixture `case.js`, the rendered diagnostics are compared with `case.js.snap`, and the code after
+//! applying every fix, including unsafe fixes, is compared with `case.fixed.js`. A fixture which is not changed by
+//! fixes must not have a fixed file. Options for the rule can be given in `case.options.json`, which is shared by
+//! the variants of a case with different extensions.
+//!
+//! Run the tests with `UPDATE_EXPECT=1` to bless the current output, this writes every snapshot and fixed file again.
+
+use expect_test::expect_file;
+use rslint_core::autofix::recursively_apply_fixes;
+use rslint_core::{get_rule_by_name, lint_file, CstRule, CstRuleStore, File};
+use rslint_errors::{file::SimpleFile, termcolor::Buffer, Emitter};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const EXTENSIONS: &[&str] = &["js", "mjs", "ts"];
+
+fn bless() -> bool {
+    std::env::var("UPDATE_EXPECT").is_ok()
+}
+
+/// The fixtures of a rule, leaving out the fixed files.
+fn fixtures(dir: &Path) -> Vec<PathBuf> {
+    let mut fixtures = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let ext = path.extension().and_then(|ext| ext.to_str());
+            let stem = path.file_stem().unwrap().to_string_lossy();
+            ext.map_or(false, |ext| EXTENSIONS.contains(&ext)) && !stem.ends_with(".fixed")
+        })
+        .collect::<Vec<_>>();
+    fixtures.sort();
+    fixtures
+}
+
+/// Make a rule with the options of a fixture, options which are not given keep their default values.
+fn rule(name: &str, fixture: &Path) -> Box<dyn CstRule> {
+    let rule =
+        get_rule_by_name(name).unwrap_or_else(|| panic!("`{}` is not the name of a rule", name));
+    let options_path = fixture.with_extension("options.json");
+    if !options_path.exists() {
+        return rule;
+    }
+
+    let options: Value = serde_json::from_str(&fs::read_to_string(&options_path).unwrap())
+        .unwrap_or_else(|err| panic!("invalid options in {}: {}", options_path.display(), err));
+    let mut tagged = serde_json::to_value(&rule).unwrap();
+    let defaults = tagged
+        .as_object_mut()
+        .and_then(|object| object.values_mut().next())
+        .and_then(Value::as_object_mut)
+        .unwrap_or_else(|| panic!("`{}` does not take any options", name));
+    match options {
+        Value::Object(options) => defaults.extend(options),
+        _ => panic!(
+            "the options in {} must be an object",
+            options_path.display()
+        ),
+    }
+    serde_json::from_value(tagged)
+        .unwrap_or_else(|err| panic!("invalid options in {}: {}", options_path.display(), err))
+}
+
+/// Lint a fixture with a rule and check its snapshot and fixed code.
+fn check(name: &str, fixture: &Path) {
+    let source = fs::read_to_string(fixture).unwrap();
+    let file = File::new_concrete(source.clone(), fixture.to_owned());
+    let mut store = CstRuleStore::new();
+    store.load_rules(vec![rule(name, fixture)]);
+    let mut result = lint_file(&file, &store, false);
+    assert!(
+        result.parser_diagnostics.is_empty(),
+        "{} has syntax errors",
+        fixture.display()
+    );
+
+    let files = SimpleFile::new(file.name.clone(), source.clone());
+    let mut snapshot = String::new();
+    for diagnostic in &result.rule_results[name].diagnostics {
+        let mut write = Buffer::no_color();
+        Emitter::new(&files)
+            .emit_with_writer(diagnostic, &mut write)
+            .expect("failed to emit diagnostic");
+        snapshot.push_str("--\n");
+        snapshot.push_str(std::str::from_utf8(write.as_slice()).expect("non utf8 in error buffer"));
+    }
+    let snapshot_path = fixture.with_file_name(format!("{}.snap", file.name));
+    expect_file![snapshot_path].assert_eq(&snapshot);
+
+    let fixed = recursively_apply_fixes(&mut result, &file, true);
+    let ext = fixture.extension().unwrap().to_string_lossy();
+    let fixed_path = fixture.with_extension(format!("fixed.{}", ext));
+    if fixed != source {
+        expect_file![fixed_path].assert_eq(&fixed);
+    } else if fixed_path.exists() {
+        if bless() {
+            fs::remove_file(&fixed_path).unwrap();
+        } else {
+            panic!(
+                "{} was not fixed but {} exists, rerun with `UPDATE_EXPECT=1` to remove it",
+                fixture.display(),
+                fixed_path.display()
+            );
+        }
+    }
+}
+
+#[test]
+fn rule_snapshots() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/rules");
+    let mut dirs = fs::read_dir(root)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    dirs.sort();
+
+    for dir in dirs {
+        let name = dir.file_name().unwrap().to_string_lossy().to_string();
+        for fixture in fixtures(&dir) {
+            check(&name, &fixture);
+        }
+    }
+}
@@ -0,0 +1,5 @@
+if (Object.is(foo, -0)) {}
+
+if (Object.is(foo, -0)) {}
+
+if (foo === 0) {}
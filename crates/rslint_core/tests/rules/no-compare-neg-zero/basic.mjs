@@ -0,0 +1,5 @@
+if (foo === -0) {}
+
+if (-0 > foo) {}
+
+if (foo === 0) {}
@@ -0,0 +1,3 @@
+if (a, Object.is(b, -0)) {}
+const c = Object.is((d, e), -0);
+f(Object.is(g, -0));
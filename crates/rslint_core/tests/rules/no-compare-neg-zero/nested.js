@@ -0,0 +1,3 @@
+if (a, b === -0) {}
+const c = -0 !== (d, e);
+f(g === /* zero */ -0);
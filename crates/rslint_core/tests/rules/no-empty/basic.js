@@ -0,0 +1,7 @@
+if (foo) {}
+
+try {
+  foo();
+} catch (e) {}
+
+switch (foo) {}
@@ -0,0 +1,9 @@
+class Foo {
+  ;
+}
+
+if (foo) {
+  ;
+}
+
+foo();;
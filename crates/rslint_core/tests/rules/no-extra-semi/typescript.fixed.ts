@@ -0,0 +1,9 @@
+let a: number = 1;
+
+interface Foo {
+  bar: string;
+}
+
+function foo<T>(a: T): T {
+  return a;
+}
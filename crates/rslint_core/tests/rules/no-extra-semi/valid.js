@@ -0,0 +1,3 @@
+for (;;) {}
+
+while (foo) {}
@@ -1,7 +1,7 @@
 use crate::suggestion::SuggestionChange;
 use crate::{
-    file::{FileId, FileSpan, Span},
-    Applicability, CodeSuggestion, DiagnosticTag, Severity, SuggestionStyle,
+    file::{FileId, FileSpan, Files, Span},
+    Applicability, CodeSuggestion, DiagnosticTag, Fix, Severity, SuggestionStyle,
 };
 use rslint_text_edit::*;
 
@@ -20,6 +20,8 @@ pub struct Diagnostic {
     pub children: Vec<SubDiagnostic>,
     pub suggestions: Vec<CodeSuggestion>,
     pub footers: Vec<Footer>,
+    /// The fix of the problem this diagnostic reports, if it can be fixed automatically.
+    pub fix: Option<Fix>,
 }
 
 impl Diagnostic {
@@ -67,6 +69,7 @@ impl Diagnostic {
             children: vec![],
             suggestions: vec![],
             footers: vec![],
+            fix: None,
         }
     }
 
@@ -377,6 +380,16 @@ impl Diagnostic {
         self
     }
 
+    /// Sets the fix of this `Diagnostic`, which replaces any previous fix.
+    pub fn fix(
+        mut self,
+        indels: impl IntoIterator<Item = Indel>,
+        applicability: Applicability,
+    ) -> Self {
+        self.fix = Some(Fix::new(indels.into_iter().collect(), applicability));
+        self
+    }
+
     /// Adds a footer to this `Diagnostic`, which will be displayed under the actual error.
     pub fn footer(mut self, severity: Severity, msg: impl Into<String>) -> Self {
         self.footers.push(Footer {
@@ -397,6 +410,45 @@ impl Diagnostic {
     }
 }
 
+/// Sort diagnostics by the name of their file, then by where their primary label starts, then by their code,
+/// and remove exact duplicates.
+///
+/// Diagnostics without a primary label come first in their file, diagnostics which are at the same
+/// place and have the same code keep their order.
+pub fn sort_diagnostics<'a>(
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+    files: &dyn Files,
+) -> Vec<&'a Diagnostic> {
+    let mut diagnostics = diagnostics.into_iter().collect::<Vec<_>>();
+    diagnostics.sort_by_cached_key(|d| {
+        (
+            files.name(d.file_id),
+            d.file_id,
+            d.primary.as_ref().map(|primary| primary.span.range.start),
+            d.code.clone(),
+        )
+    });
+
+    let mut sorted: Vec<&Diagnostic> = Vec::with_capacity(diagnostics.len());
+    // the index of the first diagnostic of the group of diagnostics at the same place with the same code
+    let mut group = 0;
+    for d in diagnostics {
+        let same_group = sorted.last().map_or(false, |last| {
+            last.file_id == d.file_id
+                && last.primary.as_ref().map(|p| p.span.range.start)
+                    == d.primary.as_ref().map(|p| p.span.range.start)
+                && last.code == d.code
+        });
+        if !same_group {
+            group = sorted.len();
+        }
+        if !sorted[group..].contains(&d) {
+            sorted.push(d);
+        }
+    }
+    sorted
+}
+
 /// Everything that can be added to a diagnostic, like
 /// a suggestion that will be displayed under the actual error.
 #[derive(Debug, Clone, PartialEq, Hash)]
@@ -412,3 +464,39 @@ pub struct Footer {
     pub msg: String,
     pub severity: Severity,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::SimpleFiles;
+
+    #[test]
+    fn sorted_by_file_position_and_code() {
+        let mut files = SimpleFiles::new();
+        let b = files.add("b.js".to_string(), "let a = 5;".to_string());
+        let a = files.add("a.js".to_string(), "let a = 5;".to_string());
+        let diagnostics = vec![
+            Diagnostic::error(b, "no-b", "b").primary(4usize..5, ""),
+            Diagnostic::error(a, "no-y", "y").primary(4usize..5, ""),
+            Diagnostic::error(a, "no-z", "z").primary(0usize..3, ""),
+            Diagnostic::error(a, "no-x", "x").primary(4usize..5, ""),
+            Diagnostic::error(a, "no-y", "y").primary(4usize..5, ""),
+            Diagnostic::error(a, "no-y", "other").primary(4usize..5, ""),
+        ];
+
+        let sorted = sort_diagnostics(&diagnostics, &files)
+            .into_iter()
+            .map(|d| (files.name(d.file_id).unwrap(), d.title.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sorted,
+            vec![
+                ("a.js", "z"),
+                ("a.js", "x"),
+                ("a.js", "y"),
+                ("a.js", "other"),
+                ("b.js", "b")
+            ]
+        );
+    }
+}
@@ -3,13 +3,13 @@ use crate::*;
 use codespan::files::Error;
 use colored::*;
 use file::Files;
-use std::collections::HashSet;
 use std::io;
 
 /// A trait describing a struct which can render diagnostics to a writer such as stderr.
 ///
 /// Each formatter may rely on behavior specific to a batch of diagnostics, therefore
-/// you should collect all diagnostics and then call the appropriate formatter
+/// you should collect all diagnostics and then call the appropriate formatter.
+/// Formatters emit diagnostics in the order of [`sort_diagnostics`], without duplicates.
 pub trait Formatter {
     fn emit_stdout(&mut self, diagnostics: &[Diagnostic], files: &dyn Files) -> io::Result<()> {
         let stderr = StandardStream::stderr(ColorChoice::Always);
@@ -41,29 +41,30 @@ impl Formatter for ShortFormatter {
         files: &dyn Files,
         writer: &mut dyn WriteColor,
     ) -> io::Result<()> {
-        let mut ids = HashSet::new();
-        diagnostics.iter().for_each(|d| {
-            ids.insert(d.file_id);
-        });
-        for id in ids {
-            let cur_diags = diagnostics
-                .iter()
-                .filter(|x| x.file_id == id && x.primary.is_some());
-            if cur_diags.clone().count() == 0 {
-                continue;
+        let diagnostics = sort_diagnostics(diagnostics, files);
+        let mut ids = vec![];
+        for d in &diagnostics {
+            if !ids.contains(&d.file_id) {
+                ids.push(d.file_id);
             }
+        }
+        for id in ids {
+            let cur_diags = diagnostics.iter().copied().filter(|x| x.file_id == id);
 
             let name = files.name(id).expect("Invalid file id");
             writeln!(writer, "{}", name.white().underline())?;
             let mut line_starts = vec![];
 
             for diag in cur_diags.clone() {
-                let line_index = files
-                    .line_index(id, diag.primary.as_ref().unwrap().span.range.start)
-                    .expect("Line index out of bounds");
-                let line_span = files.line_range(id, line_index).unwrap();
-                let column = diag.primary.as_ref().unwrap().span.range.start - line_span.start;
-                line_starts.push((line_index, column));
+                // diagnostics about the whole file have no location
+                let line_start = diag.primary.as_ref().map(|primary| {
+                    let line_index = files
+                        .line_index(id, primary.span.range.start)
+                        .expect("Line index out of bounds");
+                    let line_span = files.line_range(id, line_index).unwrap();
+                    (line_index, primary.span.range.start - line_span.start)
+                });
+                line_starts.push(line_start);
             }
             let max_msg_len = cur_diags
                 .clone()
@@ -77,25 +78,25 @@ impl Formatter for ShortFormatter {
                 .max()
                 .unwrap();
 
-            let max_loc = line_starts
-                .iter()
-                .map(|x| x.0.to_string().len() + x.1.to_string().len() + 1)
-                .max()
-                .unwrap();
-            for (diag, (line, column)) in cur_diags.zip(line_starts) {
+            let loc_len = |line_start: &Option<(usize, usize)>| {
+                line_start.map_or(0, |(line, column)| {
+                    line.to_string().len() + column.to_string().len() + 1
+                })
+            };
+            let max_loc = line_starts.iter().map(loc_len).max().unwrap();
+            for (diag, line_start) in cur_diags.zip(line_starts) {
+                write!(writer, "  ")?;
+                write!(writer, "{} ", " ".repeat(max_loc - loc_len(&line_start)))?;
+                if let Some((line, column)) = line_start {
+                    write!(
+                        writer,
+                        "{}{}{}",
+                        line.to_string().truecolor(140, 140, 140),
+                        ":".truecolor(140, 140, 140),
+                        column.to_string().truecolor(140, 140, 140)
+                    )?;
+                }
                 write!(writer, "  ")?;
-                write!(
-                    writer,
-                    "{} ",
-                    " ".repeat(max_loc - (line.to_string().len() + column.to_string().len() + 1))
-                )?;
-                write!(
-                    writer,
-                    "{}{}{}  ",
-                    line.to_string().truecolor(140, 140, 140),
-                    ":".truecolor(140, 140, 140),
-                    column.to_string().truecolor(140, 140, 140)
-                )?;
                 let color = match diag.severity {
                     Severity::Bug | Severity::Error => Color::BrightRed,
                     Severity::Note => Color::BrightCyan,
@@ -136,7 +137,7 @@ impl Formatter for LongFormatter {
         files: &dyn Files,
         writer: &mut dyn WriteColor,
     ) -> io::Result<()> {
-        for diag in diagnostics {
+        for diag in sort_diagnostics(diagnostics, files) {
             match Emitter::new(files).emit_with_writer(diag, writer) {
                 Ok(_) => {}
                 Err(err) => {
@@ -10,7 +10,7 @@ mod emit;
 mod formatters;
 mod suggestion;
 
-pub use diagnostic::{Diagnostic, SubDiagnostic};
+pub use diagnostic::{sort_diagnostics, Diagnostic, SubDiagnostic};
 pub use emit::Emitter;
 pub use file::Span;
 pub use formatters::*;
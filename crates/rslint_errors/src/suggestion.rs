@@ -17,6 +17,35 @@ pub struct CodeSuggestion {
     pub labels: Vec<Range<usize>>,
 }
 
+/// A fix of the problem a diagnostic reports, which is applied when fixing a file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fix {
+    /// The changes of the fix, which must not overlap.
+    pub indels: Vec<Indel>,
+    /// Whether the fix is safe to apply, only [`Applicability::Always`] fixes are applied
+    /// unless unsafe fixes are requested.
+    pub applicability: Applicability,
+}
+
+impl Fix {
+    pub fn new(indels: Vec<Indel>, applicability: Applicability) -> Self {
+        Self {
+            indels,
+            applicability,
+        }
+    }
+
+    /// Whether this fix is applied when fixing a file, fixes which may be incorrect
+    /// are only applied if `unsafe_fixes` is true. Fixes with placeholders are never applied.
+    pub fn is_applied(&self, unsafe_fixes: bool) -> bool {
+        match self.applicability {
+            Applicability::Always => true,
+            Applicability::MaybeIncorrect => unsafe_fixes,
+            Applicability::HasPlaceholders | Applicability::Unspecified => false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SuggestionChange {
     Indels(Vec<Indel>),
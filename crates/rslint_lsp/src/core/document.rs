@@ -1,15 +1,16 @@
 //! Core definitions related to documents.
 
 use crate::core::language::{Language, LanguageId};
-use rslint_core::{autofix::Fixer, Directive, DirectiveError, DirectiveParser, File};
+use rslint_config::ParserConfig;
+use rslint_core::{Directive, DirectiveError, DirectiveParser, File};
 use rslint_errors::file::SimpleFiles;
 use rslint_parser::{FileKind, SyntaxNode};
 use std::convert::TryFrom;
 use tower_lsp::lsp_types::*;
 
 pub struct RuleResult {
-    pub diagnostics: Vec<Diagnostic>,
-    pub fixer: Option<Fixer>,
+    /// The diagnostics of a rule, with the diagnostics they were converted from which hold their fixes.
+    pub diagnostics: Vec<(Diagnostic, rslint_errors::Diagnostic)>,
 }
 
 /// The current state of a document.
@@ -34,8 +35,13 @@ pub struct Document {
 }
 
 impl Document {
-    /// Create a new Document.
-    pub fn new(uri: Url, language_id: String, text: String) -> anyhow::Result<Self> {
+    /// Create a new Document, parsed with the syntax configured for its path.
+    pub fn new(
+        uri: Url,
+        language_id: String,
+        text: String,
+        parser_config: &ParserConfig,
+    ) -> anyhow::Result<Self> {
         let language = {
             if let Ok(path) = uri.to_file_path() {
                 Language::try_from(path.as_path())?
@@ -53,6 +59,7 @@ impl Document {
         };
         let mut file = File::from_string(text, kind, uri.path());
         file.id = file_id;
+        file.syntax = parser_config.syntax(uri.to_file_path().ok().as_deref(), kind);
 
         let (parsing_errors, root) = file.parse_with_errors();
 
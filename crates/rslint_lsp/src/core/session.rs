@@ -6,10 +6,15 @@ use dashmap::{
     DashMap,
 };
 use futures::executor::block_on;
-use rslint_core::CstRuleStore;
+use rslint_config::ParserConfig;
+use rslint_core::{CstRuleStore, RuleLevel};
 use serde::Deserialize;
 use serde_json::Value;
-use std::sync::RwLock;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
 use taplo::{parser::Parse, util::coords::Mapper};
 use tower_lsp::lsp_types::ConfigurationItem;
 use tower_lsp::{lsp_types::*, Client};
@@ -46,11 +51,43 @@ impl TomlDocument {
     }
 }
 
+/// The rules and parser options loaded from the rslint config of the workspace.
+pub struct LintConfig {
+    pub store: CstRuleStore,
+    pub parser_config: ParserConfig,
+    pub rule_levels: HashMap<&'static str, RuleLevel>,
+}
+
+impl LintConfig {
+    /// Load the config found in `root` or its ancestors, or use the default config if there is no root.
+    pub fn load(root: Option<&Path>) -> Self {
+        let rslint_config = match root {
+            Some(root) => rslint_config::Config::new_in(root, false, |_, _| {}),
+            None => Default::default(),
+        };
+        // the store is made after loading the config so it has the rules of the plugins the config declares
+        let store = CstRuleStore::new().builtins();
+        let parser_config = rslint_config.parser().clone();
+        let rule_levels = store
+            .rules
+            .iter()
+            .map(|rule| (rule.name(), rslint_config.rule_level_by_name(rule.name())))
+            .collect();
+        Self {
+            store,
+            parser_config,
+            rule_levels,
+        }
+    }
+}
+
 /// Represents the current state of the LSP session.
 pub struct Session {
     client: Option<Client>,
     documents: DashMap<Url, Document>,
-    pub(crate) store: CstRuleStore,
+    /// The folder of the workspace, which the rslint config is searched from.
+    root: RwLock<Option<PathBuf>>,
+    pub(crate) lint_config: RwLock<LintConfig>,
     pub(crate) config: RwLock<Config>,
     pub(crate) config_doc: RwLock<Option<TomlDocument>>,
 }
@@ -59,7 +96,6 @@ impl Session {
     /// Create a new session.
     pub fn new(client: Option<Client>) -> anyhow::Result<Self> {
         let documents = DashMap::new();
-        let store = CstRuleStore::new().builtins();
         let config = RwLock::new(
             client
                 .as_ref()
@@ -78,12 +114,25 @@ impl Session {
         Ok(Session {
             client,
             documents,
-            store,
+            root: RwLock::new(None),
+            lint_config: RwLock::new(LintConfig::load(None)),
             config,
             config_doc: RwLock::new(None),
         })
     }
 
+    /// Set the folder of the workspace and load the rslint config found from it.
+    pub fn set_root(&self, root: Option<PathBuf>) {
+        *self.root.write().unwrap() = root;
+        self.reload_config();
+    }
+
+    /// Load the rslint config of the workspace again, after it changed.
+    pub fn reload_config(&self) {
+        let lint_config = LintConfig::load(self.root.read().unwrap().as_deref());
+        *self.lint_config.write().unwrap() = lint_config;
+    }
+
     pub(crate) fn client(&self) -> anyhow::Result<&Client> {
         self.client
             .as_ref()
@@ -106,6 +155,11 @@ impl Session {
         Ok(result)
     }
 
+    /// The uris of every document opened in the session.
+    pub fn document_uris(&self) -> Vec<Url> {
+        self.documents.iter().map(|doc| doc.key().clone()).collect()
+    }
+
     /// Get a reference to a document associated with the session, if possible.
     pub async fn get_document(&self, uri: &Url) -> anyhow::Result<Ref<'_, Url, Document>> {
         self.documents
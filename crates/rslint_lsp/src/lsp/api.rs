@@ -14,7 +14,16 @@ use tower_lsp::{
 
 #[tower_lsp::async_trait]
 impl LanguageServer for Server {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        // the config is searched from the first workspace folder, and not from where the server was started
+        let root = params
+            .workspace_folders
+            .and_then(|folders| folders.into_iter().next())
+            .map(|folder| folder.uri)
+            .or(params.root_uri)
+            .and_then(|uri| uri.to_file_path().ok());
+        self.session.set_root(root);
+
         let capabilities = crate::lsp::server::capabilities();
         Ok(InitializeResult {
             capabilities,
@@ -66,6 +75,30 @@ impl LanguageServer for Server {
         let typ = MessageType::Info;
         let message = "RSLint Language Server initialized!";
         self.client.log_message(typ, message).await;
+
+        let watchers = rslint_config::CONFIG_NAMES
+            .iter()
+            .map(|name| FileSystemWatcher {
+                glob_pattern: format!("**/{}", name),
+                kind: None,
+            })
+            .collect();
+        let options = DidChangeWatchedFilesRegistrationOptions { watchers };
+        let registration = Registration {
+            id: "rslint-config-watcher".to_owned(),
+            method: "workspace/didChangeWatchedFiles".to_owned(),
+            register_options: serde_json::to_value(options).ok(),
+        };
+        if self
+            .client
+            .register_capability(vec![registration])
+            .await
+            .is_err()
+        {
+            let message =
+                "RSLint could not watch the config, it will not be reloaded when it changes";
+            self.client.log_message(MessageType::Warning, message).await;
+        }
     }
 
     async fn shutdown(&self) -> Result<()> {
@@ -114,6 +147,11 @@ impl LanguageServer for Server {
             .unwrap()
     }
 
+    async fn did_change_watched_files(&self, _: DidChangeWatchedFilesParams) {
+        self.session.reload_config();
+        synchronizer::document::reload(&self.session).await.unwrap()
+    }
+
     async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
         if let Ok(config) = Config::from_value(params.settings) {
             *self.session.config.write().unwrap() = config;
@@ -2,7 +2,7 @@
 
 use crate::core::{document::Document, session::Session};
 use anyhow::Result;
-use rslint_errors::Severity;
+use rslint_errors::{Applicability, Severity, SuggestionChange};
 use rslint_parser::{util::*, T};
 use std::ops::Range;
 use tower_lsp::lsp_types::{
@@ -31,7 +31,7 @@ pub async fn actions(
     let diagnostics = document
         .rule_results
         .iter()
-        .flat_map(|x| x.diagnostics.clone())
+        .flat_map(|x| x.diagnostics.iter().map(|(d, _)| d.clone()))
         .collect();
 
     actions.push(CodeActionOrCommand::CodeAction(ignore_file_action(
@@ -40,67 +40,102 @@ pub async fn actions(
         diagnostics,
     )));
 
-    for res in document.rule_results.iter() {
-        let matched_diag = res.diagnostics.iter().find(|d| {
-            rslint_errors::lsp::range_to_byte_span(&document.files, document.file.id, &d.range).ok()
-                == Some(action_range.to_owned())
-        });
-
-        if let Some(fixer) = res.fixer.as_ref() {
-            if matched_diag.is_some() {
-                let edits = fixer
-                    .indels
-                    .iter()
-                    .filter_map(|i| {
-                        Some(TextEdit {
-                            range: rslint_errors::lsp::byte_span_to_range(
-                                &document.files,
-                                document.file.id,
-                                Range::<usize>::from(i.delete),
-                            )
-                            .ok()?,
-                            new_text: i.insert.to_owned(),
-                        })
-                    })
-                    .collect::<Vec<_>>();
-
-                let edit = Some(WorkspaceEdit::new(
-                    vec![(params.text_document.uri.to_owned(), edits)]
-                        .into_iter()
-                        .collect(),
-                ));
+    // offer the fix and the suggestions of every diagnostic under the cursor
+    for (lsp_diag, diagnostic) in document
+        .rule_results
+        .iter()
+        .flat_map(|res| res.diagnostics.iter())
+    {
+        if rslint_errors::lsp::range_to_byte_span(
+            &document.files,
+            document.file.id,
+            &lsp_diag.range,
+        )
+        .ok()
+            != Some(action_range.to_owned())
+        {
+            continue;
+        }
 
-                let diagnostics = Some(
-                    res.diagnostics
-                        .iter()
-                        .filter(|d| {
-                            rslint_errors::lsp::range_to_byte_span(
-                                &document.files,
-                                document.file.id,
-                                &d.range,
-                            )
-                            .ok()
-                                == Some(action_range.to_owned())
-                        })
-                        .cloned()
-                        .collect(),
-                );
+        if let Some(fix) = &diagnostic.fix {
+            let title = if fix.applicability == Applicability::Always {
+                "Fix this issue"
+            } else {
+                "Fix this issue (may change behavior)"
+            };
+            let changes = fix
+                .indels
+                .iter()
+                .map(|i| (Range::<usize>::from(i.delete), i.insert.to_owned()));
+            actions.push(CodeActionOrCommand::CodeAction(edit_action(
+                document.value(),
+                &params.text_document.uri,
+                title,
+                changes,
+                lsp_diag,
+                fix.applicability == Applicability::Always,
+            )));
+        }
 
-                let action = CodeAction {
-                    title: "Fix this issue".to_string(),
-                    edit,
-                    is_preferred: Some(true),
-                    diagnostics,
-                    kind: Some(CodeActionKind::QUICKFIX),
-                    ..Default::default()
-                };
-                actions.push(CodeActionOrCommand::CodeAction(action));
-            }
+        for suggestion in &diagnostic.suggestions {
+            let changes = match &suggestion.substitution {
+                SuggestionChange::Indels(indels) => indels
+                    .iter()
+                    .map(|i| (Range::<usize>::from(i.delete), i.insert.to_owned()))
+                    .collect(),
+                SuggestionChange::String(string) => {
+                    vec![(suggestion.span.range.to_owned(), string.to_owned())]
+                }
+            };
+            actions.push(CodeActionOrCommand::CodeAction(edit_action(
+                document.value(),
+                &params.text_document.uri,
+                &suggestion.msg,
+                changes,
+                lsp_diag,
+                false,
+            )));
         }
     }
     Ok(Some(actions))
 }
 
+/// An action which makes changes to a document to resolve a diagnostic.
+fn edit_action(
+    document: &Document,
+    uri: &Url,
+    title: &str,
+    changes: impl IntoIterator<Item = (Range<usize>, String)>,
+    diagnostic: &Diagnostic,
+    is_preferred: bool,
+) -> CodeAction {
+    let edits = changes
+        .into_iter()
+        .filter_map(|(range, new_text)| {
+            Some(TextEdit {
+                range: rslint_errors::lsp::byte_span_to_range(
+                    &document.files,
+                    document.file.id,
+                    range,
+                )
+                .ok()?,
+                new_text,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    CodeAction {
+        title: title.to_string(),
+        edit: Some(WorkspaceEdit::new(
+            vec![(uri.to_owned(), edits)].into_iter().collect(),
+        )),
+        is_preferred: Some(is_preferred),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        kind: Some(CodeActionKind::QUICKFIX),
+        ..Default::default()
+    }
+}
+
 fn ignore_file_action(document: &Document, uri: &Url, diagnostics: Vec<Diagnostic>) -> CodeAction {
     // if the file has a shebang we cant insert a comment at the start without causing a syntax error
     let line = document
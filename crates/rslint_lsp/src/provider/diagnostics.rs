@@ -5,10 +5,15 @@ use crate::core::{
     session::Session,
 };
 use rslint_core::{
-    apply_top_level_directives, directives::DirectiveResult, run_rule, DirectiveParser,
+    apply_top_level_directives,
+    directives::DirectiveResult,
+    isolation::{catch_panic, panic_diagnostic},
+    run_rule_with_semantic,
+    semantic::LazySemanticModel,
+    DirectiveParser, RuleLevel,
 };
 use rslint_errors::{lsp::convert_to_lsp_diagnostic, Diagnostic as RslintDiagnostic};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::BTreeMap, sync::Arc};
 use tower_lsp::lsp_types::*;
 
 fn process_diagnostics(
@@ -33,14 +38,20 @@ fn process_diagnostics(
     }
 }
 
-pub async fn publish_diagnostics(session: &Session, uri: Url) -> anyhow::Result<()> {
-    let mut document = session.get_mut_document(&uri).await?;
-
-    let mut new_store = session.store.clone();
+/// Run the rules of the session on a document, returning the diagnostics of its directives and of each rule.
+fn lint_document(
+    session: &Session,
+    document: &Document,
+) -> (
+    Vec<RslintDiagnostic>,
+    BTreeMap<&'static str, rslint_core::RuleResult>,
+) {
+    let lint_config = session.lint_config.read().unwrap();
+    let mut new_store = lint_config.store.clone();
     let DirectiveResult {
         directives,
         diagnostics: mut directive_diagnostics,
-    } = DirectiveParser::new_with_store(document.root.clone(), &document.file, &session.store)
+    } = DirectiveParser::new_with_store(document.root.clone(), &document.file, &lint_config.store)
         .get_file_directives();
 
     apply_top_level_directives(
@@ -52,35 +63,54 @@ pub async fn publish_diagnostics(session: &Session, uri: Url) -> anyhow::Result<
 
     let verbose = false;
     let src = Arc::from(document.file.source.clone());
-    let rule_results: HashMap<&str, rslint_core::RuleResult> = new_store
+    let semantic = LazySemanticModel::new(document.root.clone());
+    let rule_results = new_store
         .rules
         .iter()
-        .map(|rule| {
-            (
-                rule.name(),
-                run_rule(
-                    &**rule,
-                    document.file.id,
-                    document.root.clone(),
-                    verbose,
-                    &directives,
-                    Arc::clone(&src),
-                ),
-            )
+        .filter_map(|rule| {
+            let level = lint_config
+                .rule_levels
+                .get(rule.name())
+                .copied()
+                .unwrap_or(RuleLevel::Error);
+            if level == RuleLevel::Off {
+                return None;
+            }
+
+            let mut result = run_rule_with_semantic(
+                &**rule,
+                document.file.id,
+                document.root.clone(),
+                verbose,
+                &directives,
+                Arc::clone(&src),
+                semantic.clone(),
+            );
+            level.remap_diagnostics(&mut result.diagnostics);
+            Some((rule.name(), result))
         })
         .collect();
 
+    let directive_diagnostics = directive_diagnostics
+        .into_iter()
+        .map(|x| x.diagnostic)
+        .collect();
+    (directive_diagnostics, rule_results)
+}
+
+pub async fn publish_diagnostics(session: &Session, uri: Url) -> anyhow::Result<()> {
+    let mut document = session.get_mut_document(&uri).await?;
+
+    // a panic in the linter is reported in the document instead of taking the server down with it
+    let linted = catch_panic(|| lint_document(session, &document));
+    let (directive_diagnostics, rule_results) = linted.unwrap_or_else(|panic| {
+        let d = panic_diagnostic(document.file.id, None, &panic);
+        (vec![d], BTreeMap::new())
+    });
+
     let mut diags = vec![];
 
-    process_diagnostics(
-        &document,
-        uri.clone(),
-        directive_diagnostics
-            .into_iter()
-            .map(|x| x.diagnostic)
-            .collect(),
-        &mut diags,
-    );
+    process_diagnostics(&document, uri.clone(), directive_diagnostics, &mut diags);
 
     process_diagnostics(
         &document,
@@ -89,18 +119,32 @@ pub async fn publish_diagnostics(session: &Session, uri: Url) -> anyhow::Result<
         &mut diags,
     );
 
-    for diagnostics in rule_results.clone().into_iter().map(|(_, r)| r.diagnostics) {
-        process_diagnostics(&document, uri.clone(), diagnostics, &mut diags);
-    }
-
+    let files = document.files.clone();
     document.rule_results = rule_results
         .into_iter()
-        .map(|(_, v)| v)
-        .map(|res| RuleResult {
-            diagnostics: diags.to_owned(),
-            fixer: res.fixer,
+        .map(|(_, res)| RuleResult {
+            diagnostics: res
+                .diagnostics
+                .into_iter()
+                .filter_map(|diagnostic| {
+                    let lsp_diag = convert_to_lsp_diagnostic(
+                        diagnostic.clone(),
+                        &files,
+                        document.file.id,
+                        uri.clone(),
+                        Some("rslint".to_string()),
+                    )?;
+                    Some((lsp_diag, diagnostic))
+                })
+                .collect(),
         })
         .collect();
+    diags.extend(
+        document
+            .rule_results
+            .iter()
+            .flat_map(|res| res.diagnostics.iter().map(|(d, _)| d.clone())),
+    );
 
     let version = Default::default();
     session
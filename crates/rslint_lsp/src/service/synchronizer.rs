@@ -84,11 +84,35 @@ pub(crate) mod document {
                 },
         } = params;
 
-        let document = Document::new(uri.clone(), language_id, text)?;
+        let document = {
+            let lint_config = session.lint_config.read().unwrap();
+            Document::new(uri.clone(), language_id, text, &lint_config.parser_config)?
+        };
         session.insert_document(uri.clone(), document)?;
 
         provider::diagnostics::publish_diagnostics(session, uri).await?;
 
         Ok(())
     }
+
+    /// Parse and lint every open document again, after the rslint config changed.
+    pub(crate) async fn reload(session: &Session) -> anyhow::Result<()> {
+        for uri in session.document_uris() {
+            // the document is made again so it is parsed with the new parser options
+            let document = {
+                let document = session.get_document(&uri).await?;
+                let lint_config = session.lint_config.read().unwrap();
+                Document::new(
+                    uri.clone(),
+                    document.language_id.0.clone(),
+                    document.file.source.clone(),
+                    &lint_config.parser_config,
+                )?
+            };
+            session.insert_document(uri.clone(), document)?;
+
+            provider::diagnostics::publish_diagnostics(session, uri).await?;
+        }
+        Ok(())
+    }
 }
@@ -7,6 +7,7 @@
 #[macro_use]
 mod expr_ext;
 mod generated;
+pub mod make;
 mod stmt_ext;
 mod ts_ext;
 
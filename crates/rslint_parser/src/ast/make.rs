@@ -0,0 +1,658 @@
+//! Constructors for AST nodes, which are made by parsing the code of the node.
+//!
+//! Expressions given to the constructors are wrapped in parentheses if their precedence requires it where they
+//! are placed, for example `bin_expr(a + b, BinOp::Times, c)` makes `(a + b) * c`. [`needs_parens`] can be used
+//! to check the same thing for an expression put in an existing tree.
+//!
+//! The constructors panic if the code they make is not valid, which only happens if names or raw code given to
+//! them are not valid.
+
+use crate::{
+    ast::{ArrayPattern, AssignOp, BinExpr, BinOp, Expr, Pattern, PatternOrExpr, Stmt, UnaryOp},
+    parse_expr, parse_text,
+    syntax::util::get_precedence,
+    AstNode, SyntaxKind, SyntaxNode, SyntaxNodeExt, TextSize, T,
+};
+use std::fmt::Write;
+
+/// Where an expression is placed in its parent, which decides whether it must be wrapped in parentheses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExprPosition {
+    /// The left hand side of a binary expression with an operator.
+    Lhs(SyntaxKind),
+    /// The right hand side of a binary expression with an operator.
+    Rhs(SyntaxKind),
+    /// The operand of a unary expression.
+    Operand,
+    /// The object of a member expression or the callee of a call or new expression.
+    Callee,
+    /// The test of a conditional expression.
+    Test,
+    /// An element of a list, such as an argument, or a value which can be any expression but a sequence,
+    /// such as the value of an assignment or a branch of a conditional expression.
+    Element,
+    /// The body of an arrow function.
+    ArrowBody,
+    /// The expression of an expression statement.
+    Statement,
+    /// A place where any expression is allowed, such as the condition of an `if` statement.
+    Any,
+}
+
+impl ExprPosition {
+    /// The position of an expression in its parent.
+    pub fn of(expr: &SyntaxNode) -> Self {
+        let parent = match expr.parent() {
+            Some(parent) => parent,
+            None => return ExprPosition::Any,
+        };
+        let first = parent.first_child().as_ref() == Some(expr);
+        match parent.kind() {
+            SyntaxKind::BIN_EXPR => match parent.to::<BinExpr>().op_token() {
+                Some(op) if first => ExprPosition::Lhs(op.kind()),
+                Some(op) => ExprPosition::Rhs(op.kind()),
+                None => ExprPosition::Any,
+            },
+            SyntaxKind::UNARY_EXPR | SyntaxKind::AWAIT_EXPR => ExprPosition::Operand,
+            SyntaxKind::DOT_EXPR
+            | SyntaxKind::BRACKET_EXPR
+            | SyntaxKind::CALL_EXPR
+            | SyntaxKind::NEW_EXPR
+                if first =>
+            {
+                ExprPosition::Callee
+            }
+            SyntaxKind::COND_EXPR if first => ExprPosition::Test,
+            SyntaxKind::COND_EXPR
+            | SyntaxKind::ARG_LIST
+            | SyntaxKind::ARRAY_EXPR
+            | SyntaxKind::SPREAD_ELEMENT
+            | SyntaxKind::LITERAL_PROP
+            | SyntaxKind::DECLARATOR
+            | SyntaxKind::ASSIGN_EXPR
+            | SyntaxKind::ASSIGN_PATTERN => ExprPosition::Element,
+            SyntaxKind::ARROW_EXPR => ExprPosition::ArrowBody,
+            SyntaxKind::EXPR_STMT => ExprPosition::Statement,
+            _ => ExprPosition::Any,
+        }
+    }
+}
+
+// precedences of expressions, binary expressions are between `COND` and `UNARY`
+const SEQUENCE: u8 = 0;
+const ASSIGN: u8 = 1;
+const COND: u8 = 2;
+const UNARY: u8 = 14;
+const POSTFIX: u8 = 15;
+const MEMBER: u8 = 16;
+
+fn bin_precedence(op: SyntaxKind) -> u8 {
+    match op {
+        // `in` and `instanceof` are relational operators
+        T![in] | T![instanceof] => 7 + COND,
+        // `%` is multiplicative, `**` binds tighter
+        T![%] => 10 + COND,
+        _ => get_precedence(op).unwrap_or(7) + COND,
+    }
+}
+
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::SequenceExpr(_) => SEQUENCE,
+        Expr::AssignExpr(_) | Expr::ArrowExpr(_) | Expr::YieldExpr(_) => ASSIGN,
+        Expr::CondExpr(_) => COND,
+        Expr::BinExpr(bin) => bin
+            .op_token()
+            .map_or(COND + 1, |op| bin_precedence(op.kind())),
+        Expr::UnaryExpr(unary) if unary.is_prefix() == Some(false) => POSTFIX,
+        Expr::UnaryExpr(_) | Expr::AwaitExpr(_) => UNARY,
+        _ => MEMBER,
+    }
+}
+
+fn is_logical(expr: &Expr, ops: &[SyntaxKind]) -> bool {
+    match expr {
+        Expr::BinExpr(bin) => bin.op_token().map_or(false, |op| ops.contains(&op.kind())),
+        _ => false,
+    }
+}
+
+// whether an operand of a binary expression mixes `??` with `||` or `&&`, which is not allowed without parentheses
+fn mixes_nullish(op: SyntaxKind, expr: &Expr) -> bool {
+    match op {
+        T![??] => is_logical(expr, &[T![||], T![&&]]),
+        T![||] | T![&&] => is_logical(expr, &[T![??]]),
+        _ => false,
+    }
+}
+
+// whether the code of an expression starts with something which would not be parsed as an expression
+// at the start of a statement
+fn starts_ambiguously(expr: &Expr, stmt: bool) -> bool {
+    let tokens = expr
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|elem| elem.into_token())
+        .filter(|token| !token.kind().is_trivia())
+        .map(|token| token.kind())
+        .take(2)
+        .collect::<Vec<_>>();
+    match tokens.as_slice() {
+        [T!['{'], ..] => true,
+        _ if !stmt => false,
+        [T![function], ..] | [T![class], ..] => true,
+        [T![async], T![function]] => true,
+        [T![ident], T!['[']] => expr.syntax().trimmed_text().to_string().starts_with("let"),
+        _ => false,
+    }
+}
+
+/// Whether an expression must be wrapped in parentheses to be put at a position.
+pub fn needs_parens(expr: &Expr, position: ExprPosition) -> bool {
+    let prec = precedence(expr);
+    match position {
+        ExprPosition::Lhs(op) => {
+            let op_prec = bin_precedence(op);
+            // `**` is right associative and its left hand side cannot be a unary expression
+            prec < op_prec
+                || (op == T![**] && (prec == op_prec || prec == UNARY))
+                || mixes_nullish(op, expr)
+        }
+        ExprPosition::Rhs(op) => {
+            let op_prec = bin_precedence(op);
+            prec < op_prec || (prec == op_prec && op != T![**]) || mixes_nullish(op, expr)
+        }
+        ExprPosition::Operand => prec < UNARY,
+        ExprPosition::Callee => {
+            // `1.foo` is a number followed by `foo`
+            let integer = matches!(expr, Expr::Literal(lit) if lit.is_number()
+                && lit.syntax().trimmed_text().to_string().bytes().all(|b| b.is_ascii_digit()));
+            prec < MEMBER || integer
+        }
+        ExprPosition::Test => prec <= COND,
+        ExprPosition::Element => prec == SEQUENCE,
+        ExprPosition::ArrowBody => prec == SEQUENCE || starts_ambiguously(expr, false),
+        ExprPosition::Statement => starts_ambiguously(expr, true),
+        ExprPosition::Any => false,
+    }
+}
+
+// the code of an expression at a position
+fn code(expr: &Expr, position: ExprPosition) -> String {
+    if needs_parens(expr, position) {
+        format!("({})", expr.text())
+    } else {
+        expr.text()
+    }
+}
+
+fn list(exprs: impl IntoIterator<Item = Expr>) -> String {
+    exprs
+        .into_iter()
+        .map(|expr| code(&expr, ExprPosition::Element))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Make an expression from its code.
+///
+/// # Panics
+/// Panics if the code is not a single valid expression.
+pub fn expr_from_text(text: &str) -> Expr {
+    let parse = parse_expr(text, 0);
+    let expr = parse.tree();
+    assert!(
+        parse.errors().is_empty() && expr.syntax().text_range().len() == TextSize::of(text),
+        "`{}` is not a valid expression",
+        text
+    );
+    expr
+}
+
+/// Make a statement from its code.
+///
+/// # Panics
+/// Panics if the code is not a single valid statement.
+pub fn stmt_from_text(text: &str) -> Stmt {
+    let parse = parse_text(text, 0);
+    let mut items = parse.tree().items();
+    match (items.next(), items.next()) {
+        (Some(stmt), None) if parse.errors().is_empty() => stmt,
+        _ => panic!("`{}` is not a valid statement", text),
+    }
+}
+
+/// Make a pattern from its code.
+///
+/// # Panics
+/// Panics if the code is not a valid pattern.
+pub fn pattern_from_text(text: &str) -> Pattern {
+    // the pattern is put in an array pattern so rest and assignment patterns are allowed
+    let stmt = stmt_from_text(&format!("let [{}] = [];", text));
+    stmt.syntax()
+        .descendants()
+        .find_map(|node| node.try_to::<ArrayPattern>())
+        .and_then(|array| array.elements().next())
+        .filter(|pattern| pattern.text() == text)
+        .unwrap_or_else(|| panic!("`{}` is not a valid pattern", text))
+}
+
+/// A reference to a variable, such as `foo`.
+pub fn ident(name: &str) -> Expr {
+    expr_from_text(name)
+}
+
+/// A string literal with double quotes.
+pub fn string(value: &str) -> Expr {
+    let mut text = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => text.push_str("\\\""),
+            '\\' => text.push_str("\\\\"),
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            '\u{2028}' | '\u{2029}' => write!(text, "\\u{:04x}", c as u32).unwrap(),
+            c => text.push(c),
+        }
+    }
+    text.push('"');
+    expr_from_text(&text)
+}
+
+/// A number, negative numbers are made with a unary minus.
+pub fn number(value: f64) -> Expr {
+    let text = if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        "Infinity".to_string()
+    } else {
+        value.abs().to_string()
+    };
+    let num = expr_from_text(&text);
+    if value.is_sign_negative() && !value.is_nan() {
+        unary_expr(UnaryOp::Minus, num)
+    } else {
+        num
+    }
+}
+
+pub fn bool(value: bool) -> Expr {
+    expr_from_text(if value { "true" } else { "false" })
+}
+
+pub fn null() -> Expr {
+    expr_from_text("null")
+}
+
+fn bin_op_kind(op: BinOp) -> SyntaxKind {
+    match op {
+        BinOp::LessThan => T![<],
+        BinOp::GreaterThan => T![>],
+        BinOp::LessThanOrEqual => T![<=],
+        BinOp::GreaterThanOrEqual => T![>=],
+        BinOp::Equality => T![==],
+        BinOp::StrictEquality => T![===],
+        BinOp::Inequality => T![!=],
+        BinOp::StrictInequality => T![!==],
+        BinOp::Plus => T![+],
+        BinOp::Minus => T![-],
+        BinOp::Times => T![*],
+        BinOp::Divide => T![/],
+        BinOp::Remainder => T![%],
+        BinOp::Exponent => T![**],
+        BinOp::LeftShift => T![<<],
+        BinOp::RightShift => T![>>],
+        BinOp::UnsignedRightShift => T![>>>],
+        BinOp::BitwiseAnd => T![&],
+        BinOp::BitwiseOr => T![|],
+        BinOp::BitwiseXor => T![^],
+        BinOp::NullishCoalescing => T![??],
+        BinOp::LogicalOr => T![||],
+        BinOp::LogicalAnd => T![&&],
+        BinOp::In => T![in],
+        BinOp::Instanceof => T![instanceof],
+    }
+}
+
+fn bin_op_text(op: BinOp) -> &'static str {
+    match op {
+        BinOp::LessThan => "<",
+        BinOp::GreaterThan => ">",
+        BinOp::LessThanOrEqual => "<=",
+        BinOp::GreaterThanOrEqual => ">=",
+        BinOp::Equality => "==",
+        BinOp::StrictEquality => "===",
+        BinOp::Inequality => "!=",
+        BinOp::StrictInequality => "!==",
+        BinOp::Plus => "+",
+        BinOp::Minus => "-",
+        BinOp::Times => "*",
+        BinOp::Divide => "/",
+        BinOp::Remainder => "%",
+        BinOp::Exponent => "**",
+        BinOp::LeftShift => "<<",
+        BinOp::RightShift => ">>",
+        BinOp::UnsignedRightShift => ">>>",
+        BinOp::BitwiseAnd => "&",
+        BinOp::BitwiseOr => "|",
+        BinOp::BitwiseXor => "^",
+        BinOp::NullishCoalescing => "??",
+        BinOp::LogicalOr => "||",
+        BinOp::LogicalAnd => "&&",
+        BinOp::In => "in",
+        BinOp::Instanceof => "instanceof",
+    }
+}
+
+/// A binary expression, such as `a + b`.
+pub fn bin_expr(lhs: Expr, op: BinOp, rhs: Expr) -> Expr {
+    let kind = bin_op_kind(op);
+    expr_from_text(&format!(
+        "{} {} {}",
+        code(&lhs, ExprPosition::Lhs(kind)),
+        bin_op_text(op),
+        code(&rhs, ExprPosition::Rhs(kind))
+    ))
+}
+
+/// A prefix unary expression, such as `!a` or `typeof a`.
+pub fn unary_expr(op: UnaryOp, expr: Expr) -> Expr {
+    let op = match op {
+        UnaryOp::Increment => "++",
+        UnaryOp::Decrement => "--",
+        UnaryOp::Delete => "delete ",
+        UnaryOp::Void => "void ",
+        UnaryOp::Typeof => "typeof ",
+        UnaryOp::Plus => "+",
+        UnaryOp::Minus => "-",
+        UnaryOp::BitwiseNot => "~",
+        UnaryOp::LogicalNot => "!",
+        UnaryOp::Await => "await ",
+    };
+    let operand = code(&expr, ExprPosition::Operand);
+    // `- -a` is not `--a`
+    let sep = if op.ends_with(['+', '-']) && operand.starts_with(op.chars().last().unwrap()) {
+        " "
+    } else {
+        ""
+    };
+    expr_from_text(&format!("{}{}{}", op, sep, operand))
+}
+
+/// Wrap an expression in parentheses.
+pub fn grouping_expr(expr: Expr) -> Expr {
+    expr_from_text(&format!("({})", expr.text()))
+}
+
+/// A conditional expression, such as `a ? b : c`.
+pub fn cond_expr(test: Expr, cons: Expr, alt: Expr) -> Expr {
+    expr_from_text(&format!(
+        "{} ? {} : {}",
+        code(&test, ExprPosition::Test),
+        code(&cons, ExprPosition::Element),
+        code(&alt, ExprPosition::Element)
+    ))
+}
+
+/// An assignment, such as `a = b` or `a += b`.
+pub fn assign_expr(target: PatternOrExpr, op: AssignOp, value: Expr) -> Expr {
+    let op = match op {
+        AssignOp::Assign => "=",
+        AssignOp::AddAssign => "+=",
+        AssignOp::SubtractAssign => "-=",
+        AssignOp::TimesAssign => "*=",
+        AssignOp::RemainderAssign => "%=",
+        AssignOp::ExponentAssign => "**=",
+        AssignOp::LeftShiftAssign => "<<=",
+        AssignOp::RightShiftAssign => ">>=",
+        AssignOp::UnsignedRightShiftAssign => ">>>=",
+        AssignOp::BitwiseAndAssign => "&=",
+        AssignOp::BitwiseOrAssign => "|=",
+        AssignOp::BitwiseXorAssign => "^=",
+        AssignOp::LogicalAndAssign => "&&=",
+        AssignOp::LogicalOrAssign => "||=",
+        AssignOp::NullishCoalescingAssign => "??=",
+    };
+    let target = match target {
+        PatternOrExpr::Expr(expr) => code(&expr, ExprPosition::Callee),
+        PatternOrExpr::Pattern(pattern) => pattern.text(),
+    };
+    expr_from_text(&format!(
+        "{} {} {}",
+        target,
+        op,
+        code(&value, ExprPosition::Element)
+    ))
+}
+
+/// A member expression with a name, such as `a.b`.
+pub fn dot_expr(object: Expr, prop: &str) -> Expr {
+    expr_from_text(&format!("{}.{}", code(&object, ExprPosition::Callee), prop))
+}
+
+/// A member expression with a computed property, such as `a[b]`.
+pub fn bracket_expr(object: Expr, prop: Expr) -> Expr {
+    expr_from_text(&format!(
+        "{}[{}]",
+        code(&object, ExprPosition::Callee),
+        prop.text()
+    ))
+}
+
+/// A call, such as `a(b, c)`.
+pub fn call_expr(callee: Expr, args: impl IntoIterator<Item = Expr>) -> Expr {
+    expr_from_text(&format!(
+        "{}({})",
+        code(&callee, ExprPosition::Callee),
+        list(args)
+    ))
+}
+
+/// A new expression, such as `new A(b)`.
+pub fn new_expr(callee: Expr, args: impl IntoIterator<Item = Expr>) -> Expr {
+    // the parentheses of a call in the callee would be taken as the arguments of the new expression
+    let callee = if matches!(callee, Expr::CallExpr(_)) {
+        format!("({})", callee.text())
+    } else {
+        code(&callee, ExprPosition::Callee)
+    };
+    expr_from_text(&format!("new {}({})", callee, list(args)))
+}
+
+/// An array, such as `[a, b]`.
+pub fn array_expr(elements: impl IntoIterator<Item = Expr>) -> Expr {
+    expr_from_text(&format!("[{}]", list(elements)))
+}
+
+/// A sequence of expressions, such as `a, b`.
+pub fn sequence_expr(exprs: impl IntoIterator<Item = Expr>) -> Expr {
+    expr_from_text(&list(exprs))
+}
+
+/// An arrow function with an expression body, such as `(a, b) => a + b`.
+pub fn arrow_expr(params: impl IntoIterator<Item = Pattern>, body: Expr) -> Expr {
+    let params = params
+        .into_iter()
+        .map(|param| param.text())
+        .collect::<Vec<_>>()
+        .join(", ");
+    expr_from_text(&format!(
+        "({}) => {}",
+        params,
+        code(&body, ExprPosition::ArrowBody)
+    ))
+}
+
+/// An expression statement, such as `a();`.
+pub fn expr_stmt(expr: Expr) -> Stmt {
+    stmt_from_text(&format!("{};", code(&expr, ExprPosition::Statement)))
+}
+
+/// The kind of a variable declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VarKind {
+    Var,
+    Let,
+    Const,
+}
+
+/// A variable declaration with a single declarator, such as `let a = b;`.
+pub fn var_decl(kind: VarKind, pattern: Pattern, value: Option<Expr>) -> Stmt {
+    let kind = match kind {
+        VarKind::Var => "var",
+        VarKind::Let => "let",
+        VarKind::Const => "const",
+    };
+    let value = value
+        .map(|value| format!(" = {}", code(&value, ExprPosition::Element)))
+        .unwrap_or_default();
+    stmt_from_text(&format!("{} {}{};", kind, pattern.text(), value))
+}
+
+/// A return statement, such as `return a;`.
+pub fn return_stmt(value: Option<Expr>) -> Stmt {
+    match value {
+        Some(value) => stmt_from_text(&format!("return {};", value.text())),
+        None => stmt_from_text("return;"),
+    }
+}
+
+/// A throw statement, such as `throw a;`.
+pub fn throw_stmt(value: Expr) -> Stmt {
+    stmt_from_text(&format!("throw {};", value.text()))
+}
+
+/// A block with each statement on its own line.
+pub fn block_stmt(stmts: impl IntoIterator<Item = Stmt>) -> Stmt {
+    let mut text = String::from("{\n");
+    for stmt in stmts {
+        writeln!(text, "{}", stmt.text()).unwrap();
+    }
+    text.push('}');
+    stmt_from_text(&text)
+}
+
+/// An if statement, such as `if (a) b; else c;`.
+pub fn if_stmt(test: Expr, cons: Stmt, alt: Option<Stmt>) -> Stmt {
+    let alt = alt
+        .map(|alt| format!(" else {}", alt.text()))
+        .unwrap_or_default();
+    stmt_from_text(&format!("if ({}) {}{}", test.text(), cons.text(), alt))
+}
+
+/// A pattern which binds a single name, such as `a`.
+pub fn ident_pattern(name: &str) -> Pattern {
+    pattern_from_text(name)
+}
+
+/// A pattern with a default value, such as `a = b`.
+pub fn assign_pattern(pattern: Pattern, value: Expr) -> Pattern {
+    pattern_from_text(&format!(
+        "{} = {}",
+        pattern.text(),
+        code(&value, ExprPosition::Element)
+    ))
+}
+
+/// A rest pattern, such as `...a`.
+pub fn rest_pattern(pattern: Pattern) -> Pattern {
+    pattern_from_text(&format!("...{}", pattern.text()))
+}
+
+/// An array pattern, `None` elements are holes, such as `[a, , b]`.
+pub fn array_pattern(elements: impl IntoIterator<Item = Option<Pattern>>) -> Pattern {
+    let elements = elements
+        .into_iter()
+        .map(|elem| elem.map(|pattern| pattern.text()).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join(", ");
+    pattern_from_text(&format!("[{}]", elements))
+}
+
+/// An object pattern which binds properties to names, such as `{ a, b: c }`.
+pub fn object_pattern<'a>(props: impl IntoIterator<Item = (&'a str, Pattern)>) -> Pattern {
+    let props = props
+        .into_iter()
+        .map(|(key, pattern)| {
+            if pattern.text() == key {
+                key.to_string()
+            } else {
+                format!("{}: {}", key, pattern.text())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    pattern_from_text(&format!("{{ {} }}", props))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_parens_by_precedence() {
+        let sum = bin_expr(ident("a"), BinOp::Plus, ident("b"));
+        assert_eq!(
+            bin_expr(sum.clone(), BinOp::Times, ident("c")).text(),
+            "(a + b) * c"
+        );
+        assert_eq!(
+            bin_expr(ident("c"), BinOp::Minus, sum.clone()).text(),
+            "c - (a + b)"
+        );
+        assert_eq!(
+            bin_expr(sum.clone(), BinOp::Minus, ident("c")).text(),
+            "a + b - c"
+        );
+        let pow = bin_expr(ident("a"), BinOp::Exponent, ident("b"));
+        assert_eq!(
+            bin_expr(ident("c"), BinOp::Exponent, pow.clone()).text(),
+            "c ** a ** b"
+        );
+        assert_eq!(
+            bin_expr(pow, BinOp::Exponent, ident("c")).text(),
+            "(a ** b) ** c"
+        );
+        let neg = unary_expr(UnaryOp::Minus, ident("a"));
+        assert_eq!(
+            bin_expr(neg.clone(), BinOp::Exponent, ident("b")).text(),
+            "(-a) ** b"
+        );
+        assert_eq!(unary_expr(UnaryOp::Minus, neg).text(), "- -a");
+        let or = bin_expr(ident("a"), BinOp::LogicalOr, ident("b"));
+        assert_eq!(
+            bin_expr(or, BinOp::NullishCoalescing, ident("c")).text(),
+            "(a || b) ?? c"
+        );
+        assert_eq!(unary_expr(UnaryOp::LogicalNot, sum).text(), "!(a + b)");
+        assert_eq!(dot_expr(number(1.0), "toString").text(), "(1).toString");
+        assert_eq!(number(-2.5).text(), "-2.5");
+    }
+
+    #[test]
+    fn makes_statements_and_patterns() {
+        let object = expr_from_text("{ a: 1 }");
+        assert_eq!(expr_stmt(object.clone()).text(), "({ a: 1 });");
+        assert_eq!(
+            arrow_expr(vec![ident_pattern("a")], object).text(),
+            "(a) => ({ a: 1 })"
+        );
+        let pattern = object_pattern(vec![
+            ("a", ident_pattern("a")),
+            ("b", assign_pattern(ident_pattern("c"), number(1.0))),
+        ]);
+        assert_eq!(
+            var_decl(VarKind::Const, pattern, Some(ident("d"))).text(),
+            "const { a, b: c = 1 } = d;"
+        );
+        assert_eq!(
+            call_expr(
+                ident("f"),
+                vec![sequence_expr(vec![ident("a"), ident("b")]), string("\"x\"")]
+            )
+            .text(),
+            r#"f((a, b), "\"x\"")"#
+        );
+        assert_eq!(rest_pattern(ident_pattern("rest")).text(), "...rest");
+    }
+}
@@ -64,6 +64,7 @@ mod lossless_tree_sink;
 mod lossy_tree_sink;
 mod numbers;
 mod parse;
+mod reparse;
 mod state;
 mod syntax_node;
 mod token_source;
@@ -84,6 +85,7 @@ pub use crate::{
     numbers::{parse_js_num, BigInt, JsNum},
     parse::*,
     parser::{Checkpoint, CompletedMarker, Marker, Parser},
+    reparse::{reparse_block, Reparse},
     state::{ParserState, StrictMode},
     syntax_node::*,
     token_set::TokenSet,
@@ -19,7 +19,7 @@ pub fn parse_js_num(num: String) -> Option<JsNum> {
         _ => (10, num.as_str().replace("_", "")),
     };
 
-    if radix == 10 && raw.starts_with('0') {
+    if radix == 10 && raw.starts_with('0') && raw.bytes().all(|b| (b'0'..=b'7').contains(&b)) {
         // account for legacy octal literals
         if let Ok(parsed) = parse_radix(raw.as_bytes(), 8) {
             return Some(JsNum::Float(parsed));
@@ -79,6 +79,7 @@ mod tests {
         assert_float!("0", 0.0);
         assert_float!("9e999", f64::INFINITY);
         assert_float!("9e-999", 0.0);
+        assert_float!("0.1", 0.1);
     }
 
     #[test]
@@ -0,0 +1,255 @@
+//! Incremental reparsing of edited text.
+//!
+//! Rather than reparsing a whole file after an edit, the smallest block statement which contains the edit
+//! is reparsed on its own with the parser state of its surroundings, then its new green node is spliced into the
+//! old tree. Every node outside of the block is reused as is.
+//!
+//! Reparsing gives up if the edit touches the curly braces of every enclosing block, or if the reparsed block
+//! has errors or does not end where the old one did, in which case the file should be reparsed in full.
+
+use crate::{
+    process, syntax::stmt::block_stmt, tokenize, LosslessTreeSink, NodeOrToken, Parser,
+    ParserState, StrictMode, Syntax, SyntaxKind::*, SyntaxNode, SyntaxNodeExt, TextRange, TextSize,
+    TokenSource, T,
+};
+use std::collections::HashMap;
+
+/// The result of reparsing a block after an edit.
+#[derive(Debug, Clone)]
+pub struct Reparse {
+    /// The root of the new tree.
+    pub root: SyntaxNode,
+    /// The range of the reparsed block in the old tree.
+    pub old_range: TextRange,
+    /// The range of the reparsed block in the new tree.
+    pub new_range: TextRange,
+}
+
+/// Reparse the smallest block which contains an edit replacing `delete` with `insert`.
+///
+/// Returns `None` if no block could be reparsed on its own, the whole file must be reparsed instead.
+///
+/// # Examples
+///
+/// ```
+/// use rslint_parser::{parse_module, reparse_block, Syntax, TextRange};
+///
+/// let root = parse_module("foo(); function bar() { return 1; }", 0).syntax();
+/// let reparse = reparse_block(&root, TextRange::new(31.into(), 32.into()), "2", 0, Syntax::default().module()).unwrap();
+///
+/// assert_eq!(reparse.root.to_string(), "foo(); function bar() { return 2; }");
+/// assert_eq!(reparse.old_range, TextRange::new(22.into(), 35.into()));
+/// ```
+pub fn reparse_block(
+    root: &SyntaxNode,
+    delete: TextRange,
+    insert: &str,
+    file_id: usize,
+    syntax: Syntax,
+) -> Option<Reparse> {
+    if !root.text_range().contains_range(delete) {
+        return None;
+    }
+    let covering = match root.covering_element(delete) {
+        NodeOrToken::Node(node) => node,
+        NodeOrToken::Token(token) => token.parent(),
+    };
+    let block = covering
+        .ancestors()
+        .find(|node| node.kind() == BLOCK_STMT && inside_curlies(node, delete))?;
+
+    let old_range = block.text_range();
+    let mut text = block.text().to_string();
+    let relative = delete - old_range.start();
+    text.replace_range(
+        usize::from(relative.start())..usize::from(relative.end()),
+        insert,
+    );
+
+    let (tokens, lex_errors) = tokenize(&text, file_id);
+    if !lex_errors.is_empty() {
+        return None;
+    }
+    let mut parser = Parser::new(TokenSource::new(&text, &tokens), file_id, syntax);
+    parser.state = surrounding_state(&block, parser.state.clone());
+    block_stmt(&mut parser, is_function_body(&block), None)?;
+    if !parser.at(EOF) {
+        return None;
+    }
+    let (events, errors) = parser.finish();
+    if !errors.is_empty() {
+        return None;
+    }
+
+    let mut sink = LosslessTreeSink::new(&text, &tokens);
+    process(&mut sink, events, vec![]);
+    let (green, _) = sink.finish();
+    let new_block = SyntaxNode::new_root(green.clone());
+    if new_block.kind() != BLOCK_STMT || new_block.text_range().len() != TextSize::of(&*text) {
+        return None;
+    }
+
+    let root = SyntaxNode::new_root(block.replace_with(green));
+    Some(Reparse {
+        root,
+        old_range,
+        new_range: TextRange::at(old_range.start(), TextSize::of(&*text)),
+    })
+}
+
+/// Whether an edit is between the curly braces of a block, without touching them.
+fn inside_curlies(block: &SyntaxNode, delete: TextRange) -> bool {
+    let tokens = block
+        .children_with_tokens()
+        .filter_map(|elem| elem.into_token())
+        .filter(|token| !token.kind().is_trivia())
+        .collect::<Vec<_>>();
+    match (tokens.first(), tokens.last()) {
+        (Some(l_curly), Some(r_curly))
+            if l_curly.kind() == T!['{'] && r_curly.kind() == T!['}'] =>
+        {
+            l_curly.text_range().end() <= delete.start()
+                && delete.end() <= r_curly.text_range().start()
+        }
+        _ => false,
+    }
+}
+
+fn is_function(node: &SyntaxNode) -> bool {
+    matches!(
+        node.kind(),
+        FN_DECL | FN_EXPR | ARROW_EXPR | METHOD | GETTER | SETTER | CONSTRUCTOR
+    )
+}
+
+fn is_function_body(block: &SyntaxNode) -> bool {
+    block.parent().map_or(false, |parent| is_function(&parent))
+}
+
+/// Whether the statements of a function body or a file start with a `"use strict"` directive.
+fn has_use_strict(node: &SyntaxNode) -> bool {
+    node.children()
+        .take_while(|child| child.kind() == EXPR_STMT)
+        .any(|stmt| {
+            let tokens = stmt.lossy_tokens();
+            tokens.first().map_or(false, |token| {
+                token.kind() == STRING
+                    && matches!(token.text().as_str(), "\"use strict\"" | "'use strict'")
+            })
+        })
+}
+
+/// The state the parser is in at the start of a block when the whole file is parsed.
+fn surrounding_state(block: &SyntaxNode, mut state: ParserState) -> ParserState {
+    let function = block.ancestors().skip(1).find(is_function);
+    let scope = function
+        .clone()
+        .unwrap_or_else(|| block.ancestors().last().unwrap());
+
+    for ancestor in block.ancestors().skip(1) {
+        if function.as_ref() == Some(&ancestor) {
+            break;
+        }
+        match ancestor.kind() {
+            FOR_STMT | FOR_IN_STMT | FOR_OF_STMT | WHILE_STMT | DO_WHILE_STMT => {
+                state.break_allowed = true;
+                state.continue_allowed = true;
+            }
+            SWITCH_STMT => state.break_allowed = true,
+            _ => {}
+        }
+    }
+
+    // labels are kept in the state until the end of the function they are declared in
+    let mut labels = HashMap::new();
+    for labelled in scope.descendants().filter(|node| {
+        node.kind() == LABELLED_STMT
+            && node.text_range().start() < block.text_range().start()
+            && node.ancestors().skip(1).find(is_function) == function
+    }) {
+        if let Some(name) = labelled.first_child() {
+            let range = name.trimmed_range();
+            labels.insert(
+                name.trimmed_text().to_string(),
+                usize::from(range.start())..usize::from(range.end()),
+            );
+        }
+    }
+    state.labels = labels;
+
+    if let Some(function) = &function {
+        state.in_function = true;
+        let tokens = function
+            .children_with_tokens()
+            .filter_map(|elem| elem.into_token())
+            .collect::<Vec<_>>();
+        state.in_async = tokens.iter().any(|token| token.text() == "async");
+        state.in_generator =
+            function.kind() != ARROW_EXPR && tokens.iter().any(|token| token.kind() == T![*]);
+    }
+
+    let strict = block.ancestors().any(|node| match node.kind() {
+        CLASS_DECL | CLASS_EXPR => true,
+        SCRIPT | MODULE => has_use_strict(&node),
+        BLOCK_STMT => is_function_body(&node) && has_use_strict(&node),
+        _ => false,
+    });
+    if strict && state.strict.is_none() {
+        state.strict = Some(StrictMode::Explicit(0..0));
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_module, parse_with_syntax};
+
+    /// Apply an edit both incrementally and by reparsing the whole file, and check that the trees are the same.
+    fn check(src: &str, delete: (u32, u32), insert: &str) -> Option<Reparse> {
+        let root = parse_module(src, 0).syntax();
+        let delete = TextRange::new(delete.0.into(), delete.1.into());
+        let reparse = reparse_block(&root, delete, insert, 0, Syntax::default().module())?;
+
+        let mut text = src.to_string();
+        text.replace_range(
+            usize::from(delete.start())..usize::from(delete.end()),
+            insert,
+        );
+        let full = parse_with_syntax(&text, 0, Syntax::default().module()).syntax();
+        assert_eq!(format!("{:#?}", reparse.root), format!("{:#?}", full));
+        Some(reparse)
+    }
+
+    #[test]
+    fn reparses_smallest_block() {
+        let src = "let a = 1;\nfunction foo() {\n  if (a) {\n    bar();\n  }\n}\n";
+        let reparse = check(src, (43, 46), "bazz").unwrap();
+        assert_eq!(reparse.old_range, TextRange::new(37.into(), 53.into()));
+        assert_eq!(reparse.new_range, TextRange::new(37.into(), 54.into()));
+
+        let reparse = check(src, (53, 53), "\n  return 5;").unwrap();
+        assert_eq!(reparse.old_range, TextRange::new(26.into(), 55.into()));
+    }
+
+    #[test]
+    fn keeps_surrounding_state() {
+        let src = "async function* foo() { while (a) { if (b) { x; } } }";
+        let reparse = check(src, (45, 46), "await y; yield z; break; continue").unwrap();
+        assert_eq!(reparse.old_range, TextRange::new(43.into(), 49.into()));
+        check("label: for (;;) { { a; } }", (20, 21), "break label;").unwrap();
+    }
+
+    #[test]
+    fn gives_up() {
+        let src = "function foo() { a; } b;";
+        // the edit touches a curly brace or is outside of any block
+        assert!(check(src, (20, 21), "").is_none());
+        assert!(check(src, (22, 24), "c;").is_none());
+        // the new block has errors
+        assert!(check(src, (17, 19), "a +").is_none());
+        assert!(check(src, (17, 19), "} {").is_none());
+        // `return` is not allowed outside of a function
+        assert!(check("{ a; }", (2, 4), "return;").is_none());
+    }
+}
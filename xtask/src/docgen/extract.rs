@@ -90,6 +90,7 @@ pub struct LintDeclaration {
     pub docstring: Option<String>,
     pub config_fields: Vec<ConfigField>,
     pub tags: Option<Tags>,
+    pub fixes: Option<Fixes>,
 }
 
 #[derive(Clone)]
@@ -112,6 +113,13 @@ impl Parse for LintDeclaration {
         } else {
             None
         };
+        let fixes = if input.lookahead1().peek(kw::fixes) {
+            let res = Some(input.parse()?);
+            input.parse::<Token!(,)>()?;
+            res
+        } else {
+            None
+        };
         let name = input.parse::<LitStr>()?.value();
         let _ = input.parse::<Token!(,)>();
 
@@ -125,6 +133,7 @@ impl Parse for LintDeclaration {
             docstring,
             config_fields,
             tags,
+            fixes,
         })
     }
 }
@@ -147,6 +156,25 @@ impl Parse for Tags {
     }
 }
 
+/// The applicabilities of the fixes a rule makes.
+#[derive(Clone)]
+pub struct Fixes {
+    pub fixes: Vec<String>,
+}
+
+impl Parse for Fixes {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<kw::fixes>()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let fixes = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?
+            .iter()
+            .map(|x| x.to_string())
+            .collect();
+        Ok(Self { fixes })
+    }
+}
+
 impl Parse for ConfigField {
     fn parse(input: ParseStream) -> Result<Self> {
         let docstring = parse_docstring(input);
@@ -184,6 +212,7 @@ mod kw {
     syn::custom_keyword!(err);
     syn::custom_keyword!(ok);
     syn::custom_keyword!(tags);
+    syn::custom_keyword!(fixes);
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -140,6 +140,21 @@ pub fn extract_group(group_name: &str) -> Result<HashMap<String, RuleFile>, Box<
     Ok(res)
 }
 
+/// The section of a rule's docs which says how its fixes are applied.
+pub fn fixes_markdown(fixes: &Fixes) -> String {
+    let mut ret = "\n## Fixes\n".to_string();
+    if fixes.fixes.iter().any(|fix| fix == "Always") {
+        ret.push_str("\nProblems reported by this rule can be fixed automatically with `--fix`.\n");
+    }
+    if fixes.fixes.iter().any(|fix| fix != "Always") {
+        ret.push_str(
+            "\nSome fixes of this rule may change the behavior of the code, \
+             they are only applied with `--fix-unsafe`.\n",
+        );
+    }
+    ret
+}
+
 pub fn rule_markdown(rule: RuleFile, group: &Group) -> String {
     let mut ret = rule
         .lint_declaration
@@ -154,6 +169,10 @@ pub fn rule_markdown(rule: RuleFile, group: &Group) -> String {
         ),
     );
 
+    if let Some(fixes) = &rule.lint_declaration.fixes {
+        ret.push_str(&fixes_markdown(fixes));
+    }
+
     if !rule.lint_declaration.config_fields.is_empty() {
         ret.push_str("\n## Config\n");
         ret.push_str("| Name | Type | Description |\n");